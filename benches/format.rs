@@ -0,0 +1,155 @@
+use bitformat::{ByteList, Markup, Notation, Style, Theme, WebSocketFrame};
+use colored::{ColoredString, Colorize};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// Builds a masked binary frame with a medium length payload.
+fn frame_bytes(payload_len: u16) -> Vec<u8> {
+    let [high, low] = payload_len.to_be_bytes();
+    let mut data = vec![0x82, 0xfe, high, low, 0x37, 0xfa, 0x21, 0x3d];
+    data.extend((0..payload_len).map(|i| i as u8));
+    data
+}
+
+fn bench_frame(c: &mut Criterion) {
+    let data = frame_bytes(u16::MAX);
+    let mut frame = WebSocketFrame::from_bytes(&data);
+    frame.markup = Markup::Plain;
+    let mut output = String::new();
+    let mut bytes = Vec::new();
+
+    let mut group = c.benchmark_group("frame 64 KiB");
+    group.bench_function("format", |b| b.iter(|| black_box(frame.format())));
+    group.bench_function("render_to", |b| {
+        b.iter(|| {
+            output.clear();
+            frame.render_to(&mut output).unwrap();
+            black_box(output.len())
+        })
+    });
+    group.bench_function("write_to", |b| {
+        b.iter(|| {
+            bytes.clear();
+            frame.write_to(&mut bytes).unwrap();
+            black_box(bytes.len())
+        })
+    });
+    group.finish();
+}
+
+fn bench_byte_list(c: &mut Criterion) {
+    let data: Vec<u8> = (0..256 * 1024).map(|i| i as u8).collect();
+    let mut list = ByteList::from_bytes(&data);
+    list.markup = Markup::Plain;
+    let mut output = String::new();
+    let mut bytes = Vec::new();
+    assert_eq!(baseline_format(&data, &list.theme, false), list.format());
+
+    let mut group = c.benchmark_group("byte list 256 KiB");
+    group.bench_function("baseline", |b| b.iter(|| black_box(baseline_format(&data, &list.theme, false))));
+    group.bench_function("format", |b| b.iter(|| black_box(list.format())));
+    group.bench_function("render_to", |b| {
+        b.iter(|| {
+            output.clear();
+            list.render_to(&mut output).unwrap();
+            black_box(output.len())
+        })
+    });
+    group.bench_function("write_to", |b| {
+        b.iter(|| {
+            bytes.clear();
+            list.write_to(&mut bytes).unwrap();
+            black_box(bytes.len())
+        })
+    });
+    group.finish();
+}
+
+fn bench_byte_list_ansi(c: &mut Criterion) {
+    // Paint even when the benchmark's output isn't a terminal
+    colored::control::set_override(true);
+    let data: Vec<u8> = (0..256 * 1024).map(|i| i as u8).collect();
+    let mut list = ByteList::from_bytes(&data);
+    list.markup = Markup::Ansi;
+    list.theme = Theme::solarized();
+    let mut output = String::new();
+    assert_eq!(baseline_format(&data, &list.theme, true), list.format());
+
+    let mut group = c.benchmark_group("byte list 256 KiB ANSI");
+    group.bench_function("baseline", |b| b.iter(|| black_box(baseline_format(&data, &list.theme, true))));
+    group.bench_function("render_to", |b| {
+        b.iter(|| {
+            output.clear();
+            list.render_to(&mut output).unwrap();
+            black_box(output.len())
+        })
+    });
+    group.finish();
+    colored::control::unset_override();
+}
+
+/// The String-building table renderer that `render_to` replaced, kept as a baseline: each line,
+/// label and cell is formatted into its own `String` and painted through a `ColoredString`.
+///
+/// Only the default options are rendered (a binary and a decimal line per QWORD row).
+///
+/// # Arguments
+///
+/// * `data` - The bytes to format (a whole number of rows).
+/// * `theme` - The styles of each part of the table.
+/// * `ansi` - Whether to paint with ANSI escape codes (rather than plain text).
+fn baseline_format(data: &[u8], theme: &Theme, ansi: bool) -> String {
+    let paint = |text: &str, style: Style| paint(text, style, ansi);
+    let wall = paint("|", theme.border).to_string();
+    let rule = paint(&format!("+------+{}", "--------+".repeat(8)), theme.border).to_string();
+
+    let mut result = format!("{}\n", paint(&format!("{:7}+{}", "", "--------+".repeat(8)), theme.border));
+    result.push_str(&format!(" {:<6}{}", paint("Bytes", theme.title), wall));
+    result.push_str(&(0..8).map(|i| format!("{:^8}{}", paint(&format!("Byte {}", i), theme.column_title), wall)).collect::<String>());
+    result.push('\n');
+    for (i, row) in data.chunks(8).enumerate() {
+        result.push_str(&rule);
+        result.push('\n');
+        for (line, notation) in [Notation::Binary, Notation::UnsignedDecimal].iter().enumerate() {
+            let label = match line {
+                0 => format!("{:<6}", paint("QWORD", theme.dword_title)),
+                _ => format!("{:^6}", paint(&(i + 1).to_string(), theme.dword_title)),
+            };
+            result.push_str(&format!("{0}{1}{0}", wall, label));
+            let style = if *notation == Notation::Binary { theme.bit } else { theme.byte_value };
+            let cells: Vec<String> = row.iter().map(|byte| format!("{:>8}{}", paint(&notation.format_byte(*byte), style), wall)).collect();
+            result.push_str(&cells.concat());
+            result.push('\n');
+        }
+    }
+    result.push_str(&rule);
+    result.push('\n');
+    result
+}
+
+/// Paints text with a style through `colored`, as the renderer did before writing escape codes
+/// itself.
+///
+/// # Arguments
+///
+/// * `text` - The text to paint.
+/// * `style` - The style of the text.
+/// * `ansi` - Whether to paint with ANSI escape codes (rather than plain text).
+fn paint(text: &str, style: Style, ansi: bool) -> ColoredString {
+    let mut styled = match style.color {
+        Some(color) if ansi => text.color(color),
+        _ => text.normal(),
+    };
+    if ansi && style.bold {
+        styled = styled.bold();
+    }
+    if ansi && style.dim {
+        styled = styled.dimmed();
+    }
+    if ansi && style.underline {
+        styled = styled.underline();
+    }
+    styled
+}
+
+criterion_group!(benches, bench_frame, bench_byte_list, bench_byte_list_ansi);
+criterion_main!(benches);
@@ -0,0 +1,41 @@
+#![no_main]
+
+use bitformat::{ByteList, Layout, Markup, Notation, OffsetColumn, OffsetFormat, RowWidth};
+use libfuzzer_sys::fuzz_target;
+
+const NOTATIONS: [Notation; 6] = [
+    Notation::Binary,
+    Notation::Hex,
+    Notation::Octal,
+    Notation::SignedDecimal,
+    Notation::UnsignedDecimal,
+    Notation::Ascii,
+];
+
+// Renders arbitrary bytes, taking the table options from the first four bytes.
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 4 {
+        return;
+    }
+    let (options, bytes) = data.split_at(4);
+    let mut list = ByteList::from_bytes(bytes);
+    list.markup = Markup::Plain;
+    list.row_width = RowWidth::Custom(usize::from(options[0] % 32) + 1);
+    list.notations = NOTATIONS.iter().copied().filter(|notation| options[1] & (1 << *notation as u8) != 0).collect();
+    if options[2] & 1 != 0 {
+        list.offset_column = Some(OffsetColumn::new(OffsetFormat::Hex, usize::from(options[2] >> 4)));
+    }
+    list.base_address = u64::from(options[2]) << 56;
+    list.max_width = match options[3] {
+        0 => None,
+        width => Some(usize::from(width)),
+    };
+    list.layout = match options[1] >> 6 {
+        0 => Layout::Xxd { collapse: true },
+        1 => Layout::Hexdump { collapse: true },
+        _ => Layout::Table,
+    };
+
+    list.format();
+    list.to_json();
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use bitformat::InputFormat;
+use libfuzzer_sys::fuzz_target;
+
+// Decodes arbitrary bytes in every input format, which must fail cleanly rather than panic.
+fuzz_target!(|data: &[u8]| {
+    for format in [InputFormat::Raw, InputFormat::Hex, InputFormat::Base64, InputFormat::CArray] {
+        let _ = format.decode(data);
+    }
+});
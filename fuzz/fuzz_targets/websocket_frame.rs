@@ -0,0 +1,22 @@
+#![no_main]
+
+use bitformat::{Markup, WebSocketFrame};
+use libfuzzer_sys::fuzz_target;
+
+// Parses arbitrary bytes as a frame and renders whatever parses, every way it can be rendered.
+fuzz_target!(|data: &[u8]| {
+    let mut frame = match WebSocketFrame::try_from_bytes(data) {
+        Ok(frame) => frame,
+        Err(_) => return,
+    };
+    assert_eq!(&data[..frame.frame_len], &frame.to_bytes()[..]);
+
+    frame.markup = Markup::Plain;
+    for max_width in [None, Some(60), Some(30)] {
+        frame.max_width = max_width;
+        frame.format();
+    }
+    frame.to_json();
+    frame.to_markdown();
+    frame.to_rst();
+});
@@ -0,0 +1,18 @@
+#![no_main]
+
+use bitformat::{Markup, WebSocketFrame};
+use libfuzzer_sys::fuzz_target;
+
+// Uses each byte as the first byte of an empty frame, whose low four bits must parse as a named
+// opcode (reserved opcodes included) that fits the opcode cell of the diagram.
+fuzz_target!(|data: &[u8]| {
+    for &byte in data {
+        let bytes = [byte, 0];
+        let mut frame = WebSocketFrame::from_bytes(&bytes);
+        assert_eq!(byte & 0x0f, frame.opcode());
+        assert_ne!("Unrecognized", frame.opcode_name());
+
+        frame.markup = Markup::Plain;
+        frame.format();
+    }
+});
@@ -0,0 +1,167 @@
+use core::fmt;
+use core::str::FromStr;
+use crate::prelude::*;
+
+/// Which end of a byte (or row) holds bit 0.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum BitOrder {
+    /// Bit 0 is the most significant (leftmost) bit, as in network protocol documents (e.g. RFC 6455).
+    #[default]
+    Msb0,
+    /// Bit 0 is the least significant (rightmost) bit, as in most datasheets and register maps.
+    Lsb0,
+}
+
+/// How far bit numbers run before starting again.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum BitSpan {
+    /// Numbers run across each row (e.g. 0-31 across a DWORD).
+    #[default]
+    Row,
+    /// Numbers start again in every byte (0-7).
+    Byte,
+}
+
+/// How bits are numbered in tick marks and bit offsets, so that diagrams match the document they
+/// are compared against.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BitNumbering {
+    /// Which end holds bit 0.
+    pub order: BitOrder,
+    /// How far numbers run before starting again.
+    pub span: BitSpan,
+}
+
+impl BitNumbering {
+    /// Creates a bit numbering.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - Which end holds bit 0.
+    /// * `span` - How far numbers run before starting again.
+    pub fn new(order: BitOrder, span: BitSpan) -> BitNumbering {
+        BitNumbering { order, span }
+    }
+
+    /// Gets the number of a bit in a row.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The position of the bit, counted from the leftmost (most significant) bit of the row.
+    /// * `row_bits` - The number of bits in the row.
+    pub fn number(self, position: usize, row_bits: usize) -> usize {
+        let (position, len) = match self.span {
+            BitSpan::Row => (position, row_bits),
+            BitSpan::Byte => (position % 8, 8),
+        };
+        match self.order {
+            BitOrder::Msb0 => position,
+            BitOrder::Lsb0 => len - 1 - position,
+        }
+    }
+
+    /// Gets the number of a bit within its byte (0-7), whatever the span.
+    ///
+    /// # Arguments
+    ///
+    /// * `position` - The position of the bit, counted from the most significant bit of the byte.
+    pub fn number_in_byte(self, position: usize) -> usize {
+        BitNumbering::new(self.order, BitSpan::Byte).number(position, 8)
+    }
+
+    /// Gets the mask selecting a numbered bit of a byte (zero for numbers above 7).
+    ///
+    /// # Arguments
+    ///
+    /// * `number` - The number of the bit within the byte (0-7).
+    pub fn mask(self, number: u8) -> u8 {
+        match self.order {
+            BitOrder::Msb0 => 0b10000000u8.checked_shr(number.into()),
+            BitOrder::Lsb0 => 1u8.checked_shl(number.into()),
+        }
+        .unwrap_or(0)
+    }
+
+    /// Checks whether a numbered bit of a byte is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `byte` - The byte.
+    /// * `number` - The number of the bit within the byte (0-7).
+    pub fn get_bit(self, byte: u8, number: u8) -> bool {
+        byte & self.mask(number) != 0
+    }
+}
+
+impl FromStr for BitNumbering {
+    type Err = String;
+
+    /// Parses a numbering from its name: `msb0` or `lsb0`, with a `-byte` suffix when numbers
+    /// start again in every byte (e.g. `lsb0-byte`).
+    fn from_str(text: &str) -> Result<BitNumbering, String> {
+        let text_lower = text.to_ascii_lowercase();
+        let (order, span) = match text_lower.strip_suffix("-byte") {
+            Some(order) => (order, BitSpan::Byte),
+            None => (text_lower.as_str(), BitSpan::Row),
+        };
+        match order {
+            "msb0" => Ok(BitNumbering::new(BitOrder::Msb0, span)),
+            "lsb0" => Ok(BitNumbering::new(BitOrder::Lsb0, span)),
+            _ => Err(format!("'{}' is not a bit numbering. Use msb0, lsb0, msb0-byte or lsb0-byte.", text)),
+        }
+    }
+}
+
+impl fmt::Display for BitNumbering {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let order = match self.order {
+            BitOrder::Msb0 => "msb0",
+            BitOrder::Lsb0 => "lsb0",
+        };
+        match self.span {
+            BitSpan::Row => write!(f, "{}", order),
+            BitSpan::Byte => write!(f, "{}-byte", order),
+        }
+    }
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_number() {
+        let numbers = |numbering: BitNumbering| -> Vec<usize> { [0, 7, 8, 31].iter().map(|position| numbering.number(*position, 32)).collect() };
+
+        assert_eq!(vec![0, 7, 8, 31], numbers(BitNumbering::new(BitOrder::Msb0, BitSpan::Row)));
+        assert_eq!(vec![31, 24, 23, 0], numbers(BitNumbering::new(BitOrder::Lsb0, BitSpan::Row)));
+        assert_eq!(vec![0, 7, 0, 7], numbers(BitNumbering::new(BitOrder::Msb0, BitSpan::Byte)));
+        assert_eq!(vec![7, 0, 7, 0], numbers(BitNumbering::new(BitOrder::Lsb0, BitSpan::Byte)));
+        assert_eq!(4, BitNumbering::new(BitOrder::Lsb0, BitSpan::Row).number_in_byte(11));
+    }
+
+    #[test]
+    fn test_get_bit() {
+        let msb0 = BitNumbering::default();
+        let lsb0 = BitNumbering::new(BitOrder::Lsb0, BitSpan::Row);
+
+        assert!(msb0.get_bit(0x81, 0));
+        assert!(!msb0.get_bit(0x81, 1));
+        assert!(lsb0.get_bit(0x02, 1));
+        assert!(!lsb0.get_bit(0x02, 6));
+        assert!(!msb0.get_bit(0xff, 8));
+    }
+
+    #[test]
+    fn test_parse() {
+        for text in ["msb0", "lsb0", "msb0-byte", "lsb0-byte"] {
+            assert_eq!(text, text.parse::<BitNumbering>().unwrap().to_string());
+        }
+        assert_eq!(Ok(BitNumbering::new(BitOrder::Lsb0, BitSpan::Byte)), "LSB0-Byte".parse());
+        assert!("msb1".parse::<BitNumbering>().is_err());
+    }
+}
+
+// #endregion Unit tests
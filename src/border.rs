@@ -0,0 +1,187 @@
+use crate::prelude::*;
+
+/// The characters used to draw the borders of tables and frame diagrams.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum BorderStyle {
+    /// ASCII characters (`+`, `-` and `|`).
+    #[default]
+    Ascii,
+    /// Light Unicode box drawing characters (e.g. `┌─┬┐`).
+    Light,
+    /// Heavy Unicode box drawing characters (e.g. `┏━┳┓`).
+    Heavy,
+    /// Double-line Unicode box drawing characters (e.g. `╔═╦╗`).
+    Double,
+    /// Light Unicode box drawing characters with rounded corners (e.g. `╭─┬╮`).
+    Rounded,
+    /// No borders: walls are drawn as spaces (so columns stay aligned) and rules are left out.
+    None,
+}
+
+impl BorderStyle {
+    /// Gets the characters of the style: horizontal, vertical, the four corners (top left, top
+    /// right, bottom left, bottom right), the four tees (left, right, top, bottom) and the cross.
+    fn glyphs(self) -> [char; 11] {
+        match self {
+            BorderStyle::Ascii => ['-', '|', '+', '+', '+', '+', '+', '+', '+', '+', '+'],
+            BorderStyle::Light => ['─', '│', '┌', '┐', '└', '┘', '├', '┤', '┬', '┴', '┼'],
+            BorderStyle::Heavy => ['━', '┃', '┏', '┓', '┗', '┛', '┣', '┫', '┳', '┻', '╋'],
+            BorderStyle::Double => ['═', '║', '╔', '╗', '╚', '╝', '╠', '╣', '╦', '╩', '╬'],
+            BorderStyle::Rounded => ['─', '│', '╭', '╮', '╰', '╯', '├', '┤', '┬', '┴', '┼'],
+            BorderStyle::None => [' '; 11],
+        }
+    }
+
+    /// Gets the wall drawn between cells.
+    pub(crate) fn vertical(self) -> &'static str {
+        match self {
+            BorderStyle::Ascii => "|",
+            BorderStyle::Light | BorderStyle::Rounded => "│",
+            BorderStyle::Heavy => "┃",
+            BorderStyle::Double => "║",
+            BorderStyle::None => " ",
+        }
+    }
+
+    /// Draws a horizontal rule between two lines of a table, joining the walls of both lines.
+    ///
+    /// The rule runs from the first to the last wall of either line (empty for `None`).
+    ///
+    /// # Arguments
+    ///
+    /// * `above` - The positions of the walls in the line above (in ascending order).
+    /// * `below` - The positions of the walls in the line below (in ascending order).
+    pub(crate) fn rule(self, above: &[usize], below: &[usize]) -> String {
+        let start = match (above.first(), below.first()) {
+            (Some(a), Some(b)) => *a.min(b),
+            (Some(x), None) | (None, Some(x)) => *x,
+            (None, None) => return String::new(),
+        };
+        let end = *above.last().into_iter().chain(below.last()).max().expect("One of the lines has walls.");
+        if self == BorderStyle::None {
+            return String::new();
+        }
+
+        let mut rule = " ".repeat(start);
+        for x in start..=end {
+            let up = above.binary_search(&x).is_ok();
+            let down = below.binary_search(&x).is_ok();
+            rule.push(self.junction(up, down, x > start, x < end));
+        }
+        rule
+    }
+
+    /// Gets the character joining lines from any of four directions.
+    ///
+    /// # Arguments
+    ///
+    /// * `up` - Whether a wall joins from above.
+    /// * `down` - Whether a wall joins from below.
+    /// * `left` - Whether the rule continues to the left.
+    /// * `right` - Whether the rule continues to the right.
+    fn junction(self, up: bool, down: bool, left: bool, right: bool) -> char {
+        let [horizontal, vertical, top_left, top_right, bottom_left, bottom_right, left_tee, right_tee, top_tee, bottom_tee, cross] =
+            self.glyphs();
+        match (up, down, left, right) {
+            (false, false, _, _) => horizontal,
+            (true, true, false, false) | (true, false, false, false) | (false, true, false, false) => vertical,
+            (false, true, false, true) => top_left,
+            (false, true, true, false) => top_right,
+            (true, false, false, true) => bottom_left,
+            (true, false, true, false) => bottom_right,
+            (true, true, false, true) => left_tee,
+            (true, true, true, false) => right_tee,
+            (false, true, true, true) => top_tee,
+            (true, false, true, true) => bottom_tee,
+            (true, true, true, true) => cross,
+        }
+    }
+
+    /// Checks that the walls of a rendered table (drawn in this style) line up across the whole
+    /// table: the lines of each row (the lines between two rules) are as wide as each other, the
+    /// walls of a row's first line meet junctions of the rule above it, and the walls of its last
+    /// line meet junctions of the rule beneath it. Rules join the walls of neighbouring rows, so
+    /// a wall drawn out of place anywhere in the table is caught.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The rendered table, without markup.
+    #[cfg(test)]
+    pub(crate) fn rows_aligned(self, text: &str) -> bool {
+        let glyphs = self.glyphs();
+        let (horizontal, vertical) = (glyphs[0], glyphs[1]);
+        let is_rule = |line: &str| {
+            let line = line.trim();
+            !line.is_empty() && line.chars().all(|c| c != vertical && glyphs.contains(&c))
+        };
+        // Lines without walls (such as notes beneath a table) aren't part of the grid
+        let meets = |line: Option<&&str>, rule: Option<&str>| match (line, rule) {
+            (Some(line), Some(rule)) if line.contains(vertical) => {
+                line.chars().count() <= rule.chars().count()
+                    && line.chars().zip(rule.chars()).all(|(c, r)| c != vertical || (r != horizontal && r != ' '))
+            }
+            _ => true,
+        };
+        let row_aligned = |row: &[&str], above: Option<&str>, below: Option<&str>| {
+            row.windows(2).all(|pair| pair[0].chars().count() == pair[1].chars().count())
+                && meets(row.first(), above)
+                && meets(row.last(), below)
+        };
+
+        let mut rule_above = None;
+        let mut row = Vec::new();
+        for line in text.lines() {
+            if !is_rule(line) {
+                row.push(line);
+                continue;
+            }
+            if !row_aligned(&row, rule_above, Some(line)) {
+                return false;
+            }
+            rule_above = Some(line);
+            row.clear();
+        }
+        row_aligned(&row, rule_above, None)
+    }
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ascii_rule() {
+        assert_eq!("+--+-+", BorderStyle::Ascii.rule(&[0, 3], &[0, 3, 5]));
+        assert_eq!("  +-+", BorderStyle::Ascii.rule(&[], &[2, 4]));
+    }
+
+    #[test]
+    fn test_junctions() {
+        // Walls that only reach the rule from one side get tees, and shared walls get crosses
+        assert_eq!("├─┬─┼─┴─┤", BorderStyle::Light.rule(&[0, 4, 6, 8], &[0, 2, 4, 8]));
+        assert_eq!("╭──╮", BorderStyle::Rounded.rule(&[], &[0, 3]));
+        assert_eq!("╚══╩══╝", BorderStyle::Double.rule(&[0, 3, 6], &[]));
+        assert_eq!("┗━━┻━━┓", BorderStyle::Heavy.rule(&[0, 3], &[6]));
+    }
+
+    #[test]
+    fn test_rows_aligned() {
+        let table = "+--+--+\n|ab|cd|\n+--+--+\n|ef|\n+--+\n";
+
+        assert!(BorderStyle::Ascii.rows_aligned(table));
+        // A wall that misses the junctions of the rules around it
+        assert!(!BorderStyle::Ascii.rows_aligned("+--+--+\n|abc|d|\n+--+--+\n"));
+        // A row wider than the rules around it
+        assert!(!BorderStyle::Ascii.rows_aligned("+--+\n|ab|cd|\n+--+\n"));
+    }
+
+    #[test]
+    fn test_no_border() {
+        assert_eq!("", BorderStyle::None.rule(&[0, 3], &[0, 3]));
+        assert_eq!(" ", BorderStyle::None.vertical());
+    }
+}
+
+// #endregion Unit tests
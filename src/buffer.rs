@@ -0,0 +1,130 @@
+use core::fmt;
+use core::str;
+
+/// A fixed-capacity text buffer over a byte slice, for rendering without allocating the output
+/// (e.g. into a static buffer that is then written to a serial port).
+///
+/// ```
+/// use core::fmt::Write;
+/// use bitformat::{FixedBuffer, Markup, WebSocketFrame};
+///
+/// let mut storage = [0u8; 2048];
+/// let mut buffer = FixedBuffer::new(&mut storage);
+/// let mut frame = WebSocketFrame::from_bytes(&[0x89, 0x00]);
+/// frame.markup = Markup::Plain;
+/// frame.render_to(&mut buffer).unwrap();
+/// assert!(buffer.as_str().contains("Ping"));
+/// ```
+///
+/// Writes that don't fit fail with `fmt::Error`. The text that did fit is kept (up to the last
+/// whole character), so that a truncated diagram can still be shown.
+#[derive(Debug)]
+pub struct FixedBuffer<'a> {
+    buffer: &'a mut [u8],
+    len: usize,
+    truncated: bool,
+}
+
+impl<'a> FixedBuffer<'a> {
+    /// Creates an empty buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - The storage for the text, which sets the capacity of the buffer.
+    pub fn new(buffer: &'a mut [u8]) -> FixedBuffer<'a> {
+        FixedBuffer { buffer, len: 0, truncated: false }
+    }
+
+    /// Gets the text written to the buffer.
+    pub fn as_str(&self) -> &str {
+        str::from_utf8(self.as_bytes()).expect("Only whole characters are written to the buffer.")
+    }
+
+    /// Gets the UTF-8 bytes written to the buffer.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer[..self.len]
+    }
+
+    /// Gets the number of bytes written to the buffer.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Checks whether nothing has been written to the buffer.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Gets the number of bytes the buffer can hold.
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Checks whether a write did not fit, so that the text is incomplete.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Empties the buffer so that it can be reused.
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.truncated = false;
+    }
+}
+
+impl fmt::Write for FixedBuffer<'_> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        // Nothing follows a cut, so that the text never has a gap in it
+        if self.truncated {
+            return Err(fmt::Error);
+        }
+        let mut end = text.len().min(self.buffer.len() - self.len);
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.buffer[self.len..self.len + end].copy_from_slice(&text.as_bytes()[..end]);
+        self.len += end;
+        if end < text.len() {
+            self.truncated = true;
+            return Err(fmt::Error);
+        }
+        Ok(())
+    }
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_write() {
+        let mut storage = [0u8; 8];
+        let mut buffer = FixedBuffer::new(&mut storage);
+        write!(buffer, "{}-{}", 12, 34).unwrap();
+
+        assert_eq!("12-34", buffer.as_str());
+        assert_eq!(8, buffer.capacity());
+        assert!(!buffer.is_truncated());
+    }
+
+    #[test]
+    fn test_truncated() {
+        let mut storage = [0u8; 8];
+        let mut buffer = FixedBuffer::new(&mut storage);
+
+        // The three-byte character doesn't fit after the first six bytes
+        assert!(buffer.write_str("abcdef─").is_err());
+        assert_eq!("abcdef", buffer.as_str());
+        assert!(buffer.is_truncated());
+        assert!(buffer.write_str("g").is_err());
+
+        buffer.clear();
+        assert!(buffer.is_empty());
+        assert!(buffer.write_str("g").is_ok());
+    }
+}
+
+// #endregion Unit tests
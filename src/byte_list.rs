@@ -1,113 +1,678 @@
-const BITS_IN_BYTE: u8 = 8;
+mod annotation;
+mod interpretation;
+mod layout;
+mod notation;
+mod offset;
+mod row_width;
+mod source;
+#[cfg(feature = "std")]
+mod stream;
+mod value_style;
 
+use crate::bit_numbering::BitNumbering;
+use crate::border::BorderStyle;
+use crate::buffer::FixedBuffer;
+use crate::doc_table::DocTable;
+use crate::json;
+use crate::logging::PlainText;
+use alloc::borrow::Cow;
+use core::fmt::{self, Write};
+#[cfg(feature = "std")]
+use std::io;
+use crate::markup::{Markup, Paint, Painted};
+use crate::prelude::*;
+use crate::theme::{Style, Theme};
+pub use annotation::{Annotation, AnnotationLabels};
+pub use interpretation::{Endianness, Interpretation, NumberType};
+pub use layout::Layout;
+pub use notation::Notation;
+pub(crate) use notation::CELL_WIDTH;
+pub use offset::{OffsetColumn, OffsetFormat};
+pub use row_width::RowWidth;
+#[cfg(feature = "std")]
+pub use stream::ByteStream;
+pub use value_style::ValueStyle;
+
+/// The most bytes taken by a painted wall (a box drawing character in the longest ANSI style).
+pub(crate) const WALL_CAPACITY: usize = 64;
+
+#[derive(Clone)]
 pub struct ByteList<'a> {
-    data: &'a Vec<u8>,
+    pub markup: Markup,
+    /// The overall layout. The `xxd` and `hexdump -C` layouts only use `base_address` and `markup`.
+    pub layout: Layout,
+    /// The notations used to show each byte, one line per notation.
+    pub notations: Vec<Notation>,
+    /// The number of bytes shown on each row.
+    pub row_width: RowWidth,
+    /// The multi-byte numbers shown beneath the bytes of each row, one line per interpretation.
+    pub interpretations: Vec<Interpretation>,
+    /// The column showing the address of the first column in each row (hidden when `None`).
+    pub offset_column: Option<OffsetColumn>,
+    /// The address of the first byte. Rows are aligned to multiples of the row width, so an
+    /// unaligned base address starts part way through the first row.
+    pub base_address: u64,
+    /// Labelled ranges of bits whose cells are coloured (later annotations are drawn over earlier ones).
+    pub annotations: Vec<Annotation>,
+    /// Where the labels of the annotations are shown.
+    pub annotation_labels: AnnotationLabels,
+    /// The characters drawing the borders of the table.
+    pub border_style: BorderStyle,
+    /// The width the table must fit (e.g. the width of the terminal). Rows are narrowed (and
+    /// interpretations wider than a row left out) until the table fits.
+    pub max_width: Option<usize>,
+    /// The styles of each part of the table (plain by default).
+    pub theme: Theme,
+    /// Styles given to cells by the value of their byte (later styles are drawn over earlier ones,
+    /// and annotations over all of them).
+    pub value_styles: Vec<ValueStyle>,
+    /// How the bits are numbered in a line of tick marks beneath the column labels (hidden when `None`).
+    pub bit_numbering: Option<BitNumbering>,
+    data: &'a [u8],
+    /// The most bytes a `ByteStream` renders in place of `data` (`u64::MAX` when its length is
+    /// unknown), which sizes the offset and row label columns.
+    stream_len: Option<u64>,
+    /// The narrowest row label column, widened for labels other than row numbers (such as the
+    /// offsets of a `ByteDiff`).
+    min_label_width: usize,
 }
 
 impl<'a> ByteList<'a> {
-    pub fn from_bytes(data: &Vec<u8>) -> ByteList {
-        ByteList { data }
-    }
-
-    /// Formats a vector of bytes as a qword table.
+    /// Creates a list formatting borrowed bytes.
+    ///
+    /// Anything that dereferences to a byte slice can be borrowed (e.g. a `Vec<u8>`, an array or
+    /// a memory-mapped file).
     ///
     /// # Arguments
     ///
     /// * `data` - The bytes to format.
+    pub fn from_bytes(data: &'a [u8]) -> ByteList<'a> {
+        ByteList { 
+            markup: Markup::default(),
+            layout: Layout::Table,
+            notations: vec![Notation::Binary, Notation::UnsignedDecimal],
+            row_width: RowWidth::Qword,
+            interpretations: Vec::new(),
+            offset_column: None,
+            base_address: 0,
+            annotations: Vec::new(),
+            annotation_labels: AnnotationLabels::Legend,
+            border_style: BorderStyle::Ascii,
+            max_width: None,
+            theme: Theme::plain(),
+            value_styles: Vec::new(),
+            bit_numbering: None,
+            data,
+            stream_len: None,
+            min_label_width: 0,
+        }
+    }
+
+    /// Creates a list without any bytes, whose options can be used by a `ByteStream`.
+    pub fn empty() -> ByteList<'static> {
+        ByteList::from_bytes(&[])
+    }
+
+    /// Formats a vector of bytes using the layout (by default a table with `row_width` bytes per row).
     pub fn format(self: &ByteList<'a>) -> String {
-        let mut result = self.format_qword_table_header();
-        let num_qwords = self.data.len().div_euclid(BITS_IN_BYTE as usize);
-        // Append full qwords
-        for i in 0..num_qwords {
-            let from_byte_ix = i * BITS_IN_BYTE as usize;
-            let to_byte_ix = from_byte_ix + BITS_IN_BYTE as usize;
-            let qword_number: usize = i + 1;
-            result.push_str(&self.format_qword_row(
-                qword_number,
-                &self.data[from_byte_ix..to_byte_ix],
-                BITS_IN_BYTE as usize,
+        self.to_string()
+    }
+
+    /// Renders the bytes straight into a writer, without building intermediate strings.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the rendered bytes (e.g. a `String` or `fmt::Formatter`).
+    pub fn render_to<W: fmt::Write>(self: &ByteList<'a>, out: &mut W) -> fmt::Result {
+        let mut data = self.data;
+        self.render_source(self.base_address, &mut data, out).map(|_| ())
+    }
+
+    /// Displays the bytes as plain text, rendered only when displayed (e.g. by a log sink that has
+    /// the level enabled).
+    pub fn plain(self: &ByteList<'a>) -> PlainText<'_, ByteList<'a>> {
+        PlainText::new(self)
+    }
+
+    /// Renders the bytes into an I/O writer (such as a file, socket or log sink).
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the rendered bytes.
+    #[cfg(feature = "std")]
+    pub fn write_to<W: io::Write>(self: &ByteList<'a>, writer: W) -> io::Result<()> {
+        let mut stream = ByteStream::new(self);
+        stream.length = Some(self.data.len() as u64);
+        stream.write(self.data, writer).map(|_| ())
+    }
+
+    /// Splits the bytes into rows aligned to the base address.
+    ///
+    /// Each row is paired with the number of columns skipped before its first byte, which is only
+    /// non-zero for the first row when the base address is unaligned.
+    fn rows(self: &ByteList<'a>) -> Vec<(usize, &'a [u8])> {
+        let row_width = self.row_width.num_bytes();
+        let skipped = (self.base_address % row_width as u64) as usize;
+        let (first, rest) = self.data.split_at((row_width - skipped).min(self.data.len()));
+        let mut rows = Vec::new();
+        if !first.is_empty() {
+            rows.push((skipped, first));
+        }
+        rows.extend(rest.chunks(row_width).map(|row| (0, row)));
+        rows
+    }
+
+    /// Creates a copy of the list rendering a stream of bytes in place of its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_address` - The address of the first byte of the stream.
+    /// * `len` - The most bytes the stream holds (`None` when unknown).
+    #[cfg(feature = "std")]
+    pub(crate) fn for_stream(self: &ByteList<'a>, base_address: u64, len: Option<u64>) -> ByteList<'a> {
+        ByteList {
+            base_address,
+            stream_len: Some(len.unwrap_or(u64::MAX)),
+            ..self.clone()
+        }
+    }
+
+    /// Creates a copy of the list's table options for drawing rows of other cells (such as the
+    /// rows of a `ByteDiff`), without bytes, interpretations, annotations or an offset column.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_label_width` - The narrowest row label column.
+    pub(crate) fn for_rows(self: &ByteList<'a>, min_label_width: usize) -> ByteList<'a> {
+        ByteList {
+            interpretations: Vec::new(),
+            offset_column: None,
+            base_address: 0,
+            annotations: Vec::new(),
+            data: &[],
+            stream_len: None,
+            min_label_width,
+            ..self.clone()
+        }
+    }
+
+    /// Gets the most bytes rendered: the length of the stream being rendered, or of the list.
+    fn rendered_len(self: &ByteList<'a>) -> u64 {
+        self.stream_len.unwrap_or(self.data.len() as u64)
+    }
+
+    /// Gets the offset column (if shown), widened to fit the last address rendered.
+    fn fitted_offset_column(self: &ByteList<'a>) -> Option<OffsetColumn> {
+        self.offset_column.map(|column| column.fitting(self.base_address, self.rendered_len()))
+    }
+
+    /// Gets the width of the row label column, widened to fit the number of the last row rendered.
+    pub(crate) fn label_width(self: &ByteList<'a>) -> usize {
+        let row_width = self.row_width.num_bytes() as u64;
+        let skipped = self.base_address % row_width;
+        let num_rows = skipped.saturating_add(self.rendered_len()).div_ceil(row_width);
+        let digits = num_rows.checked_ilog10().map_or(1, |digits| digits as usize + 1);
+        digits.max(6).max(self.min_label_width)
+    }
+
+    /// Exports the bytes as a JSON object listing the bytes in each row.
+    pub fn to_json(self: &ByteList<'a>) -> String {
+        let mut offset = 0;
+        let mut rows: Vec<String> = Vec::new();
+        for (i, (_, row)) in self.rows().into_iter().enumerate() {
+            rows.push(format!(
+                "{{\"row\":{},\"offset\":{},\"address\":{},\"bytes\":{}}}",
+                i + 1,
+                offset,
+                self.base_address.wrapping_add(offset as u64),
+                json::byte_array(row)
             ));
+            offset += row.len();
         }
-        // Append final bytes
-        let remaining_bytes = self.data.len().rem_euclid(BITS_IN_BYTE as usize);
-        let from_byte_ix: usize = num_qwords * BITS_IN_BYTE as usize;
-        let to_byte_ix: usize = from_byte_ix + remaining_bytes as usize;
-        let qword_number: usize = num_qwords + 1;
-        result.push_str(&self.format_qword_row(
-            qword_number,
-            &self.data[from_byte_ix..to_byte_ix],
-            remaining_bytes,
-        ));
-        result
-    }
-
-    /// Formats the header for a qword table.
-    fn format_qword_table_header(self: &ByteList<'a>) -> String {
+        format!(
+            "{{\"length\":{},\"row_width\":{},\"base_address\":{},\"rows\":[{}]}}",
+            self.data.len(),
+            self.row_width.num_bytes(),
+            self.base_address,
+            rows.join(",")
+        )
+    }
+
+    /// Exports the bytes as a GitHub-flavoured Markdown table, with a row per row of bytes (the
+    /// lines of each notation and interpretation are joined with `<br>`).
+    ///
+    /// The table has no colour codes and ignores `max_width`, so it can be committed to docs.
+    pub fn to_markdown(self: &ByteList<'a>) -> String {
+        self.doc_table().to_markdown()
+    }
+
+    /// Exports the bytes as a reStructuredText grid table, with a row per row of bytes.
+    ///
+    /// The table has no colour codes and ignores `max_width`, so it can be committed to docs.
+    pub fn to_rst(self: &ByteList<'a>) -> String {
+        self.doc_table().to_rst()
+    }
+
+    /// Builds a plain table for documentation: the row label, the offset (when shown) and a cell
+    /// per byte holding a line per notation, then a line per interpretation (each number is shown
+    /// in the cell of its first byte).
+    fn doc_table(self: &ByteList<'a>) -> DocTable {
+        let num_columns = self.row_width.num_bytes();
+        let mut header = vec![String::from("Row")];
+        if self.offset_column.is_some() {
+            header.push(String::from("Offset"));
+        }
+        header.extend((0..num_columns).map(|i| format!("Byte {}", i)));
+        let mut table = DocTable::new(header);
+
+        let mut offset = 0;
+        for (i, (skipped, row)) in self.rows().into_iter().enumerate() {
+            let mut label = vec![format!("{} {}", self.row_width.label(), i + 1)];
+            label.resize(self.notations.len().max(1), String::new());
+            label.extend(self.interpretations.iter().map(|interpretation| interpretation.label()));
+            let mut cells = vec![label];
+            if let Some(column) = self.fitted_offset_column() {
+                let address = self.base_address.wrapping_add(offset as u64).wrapping_sub(skipped as u64);
+                cells.push(vec![column.format_address(address)]);
+            }
+            let mut columns: Vec<Vec<String>> = vec![Vec::new(); skipped];
+            columns.extend(row.iter().map(|byte| self.notations.iter().map(|notation| notation.format_byte(*byte)).collect()));
+            for (index, interpretation) in self.interpretations.iter().enumerate() {
+                let group_len = interpretation.number_type.num_bytes();
+                for start in (0..columns.len()).step_by(group_len) {
+                    let end = start + group_len;
+                    if start >= skipped && end <= columns.len() {
+                        let value = interpretation.format_group(&row[start - skipped..end - skipped], group_len * 9 - 1);
+                        columns[start].resize(self.notations.len() + index, String::new());
+                        columns[start].push(value);
+                    }
+                }
+            }
+            cells.extend(columns);
+            table.push_row(cells);
+            offset += row.len();
+        }
+        table
+    }
+
+    /// Gets the list used to render a table: the list itself when the table fits `max_width`, or
+    /// a copy with rows narrowed until it fits.
+    pub(crate) fn fitted(self: &ByteList<'a>) -> Cow<'_, ByteList<'a>> {
+        let max_width = match self.max_width {
+            Some(max_width) if self.table_width(self.row_width.num_bytes()) > max_width => max_width,
+            _ => return Cow::Borrowed(self),
+        };
+        let mut num_bytes = self.row_width.num_bytes();
+        while num_bytes > 1 && self.table_width(num_bytes) > max_width {
+            num_bytes /= 2;
+        }
+        let mut list = self.clone();
+        list.row_width = RowWidth::from(num_bytes);
+        // Numbers are never split across rows
+        list.interpretations.retain(|interpretation| interpretation.number_type.num_bytes() <= num_bytes);
+        Cow::Owned(list)
+    }
+
+    /// Gets the number of characters in each line of a table.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_bytes` - The number of bytes in each row.
+    fn table_width(self: &ByteList<'a>, num_bytes: usize) -> usize {
+        self.walls(1..=num_bytes).last().map_or(0, |wall| wall + 1)
+    }
+
+    /// Formats the header for a table.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the header.
+    pub(crate) fn write_table_header<W: fmt::Write>(self: &ByteList<'a>, out: &mut W) -> fmt::Result {
+        let num_columns = self.row_width.num_bytes();
+        let wall = self.wall();
         // Top border
-        let mut result = String::from("       +");
-        result.push_str(&(0..BITS_IN_BYTE).map(|_| "--------+").collect::<String>());
+        self.write_rule(out, &[], &self.header_walls())?;
+        let label_width = self.label_width();
         // Append table label
-        result.push_str("\n Bytes |");
+        write!(out, " {:<1$}{2}", "Bytes".paint(self.theme.title, self.markup), label_width, wall)?;
+        if let Some(column) = self.fitted_offset_column() {
+            write!(out, "{:^1$}{2}", "Offset".paint(self.theme.column_title, self.markup), column.width(), wall)?;
+        }
         // Append column labels
-        result.push_str(
-            &(0..BITS_IN_BYTE)
-                .map(|i| format!(" Byte {} |", i))
-                .collect::<String>(),
-        );
-        // Append bottom border
-        result.push_str("\n+------+");
-        result.push_str(&(0..BITS_IN_BYTE).map(|_| "--------+").collect::<String>());
-        result.push_str("\n");
-        result
+        for i in 0..num_columns {
+            write!(out, "{:^8}{}", format_args!("Byte {}", i).paint(self.theme.column_title, self.markup), wall)?;
+        }
+        out.write_char('\n')?;
+        // Append bit tick marks (the units digits of each bit number, matching binary cells)
+        if let Some(numbering) = self.bit_numbering {
+            write!(out, " {:<1$}{2}", "Bits".paint(self.theme.column_title, self.markup), label_width, wall)?;
+            self.write_blank_offset(out)?;
+            let row_bits = num_columns * 8;
+            for i in 0..num_columns {
+                let ticks: String = (0..8).map(|bit| ((numbering.number(i * 8 + bit, row_bits) % 10) as u8 + b'0') as char).collect();
+                write!(out, "{}{}", ticks.paint(self.theme.tick_mark, self.markup), wall)?;
+            }
+            out.write_char('\n')?;
+        }
+        Ok(())
+    }
+
+    /// Gets the positions of the walls in the header of a table.
+    pub(crate) fn header_walls(self: &ByteList<'a>) -> Vec<usize> {
+        let mut walls = self.walls(1..=self.row_width.num_bytes());
+        walls.remove(0);
+        walls
+    }
+
+    /// Gets the positions of the walls in a line of a table: either side of the row label and the
+    /// offset column, then after the byte columns with the given indexes.
+    ///
+    /// # Arguments
+    ///
+    /// * `boundaries` - The indexes of the byte columns followed by walls, counted from 1.
+    pub(crate) fn walls<I: IntoIterator<Item = usize>>(self: &ByteList<'a>, boundaries: I) -> Vec<usize> {
+        let label_wall = self.label_width() + 1;
+        let mut walls = vec![0, label_wall];
+        if let Some(column) = self.fitted_offset_column() {
+            walls.push(label_wall + 1 + column.width());
+        }
+        let start = *walls.last().expect("The row label has walls.");
+        walls.extend(boundaries.into_iter().map(|boundary| start + boundary * 9));
+        walls
     }
 
-    /// Formats a row of bytes in a qword table.
+    /// Formats a line holding a horizontal rule (nothing when borders are hidden).
     ///
     /// # Arguments
     ///
-    /// * `qword_number` - The sequence number of this qword.
-    /// * `data` - The bytes within the qword to format.
-    /// * `num_bytes` - The number of bytes to format.
-    fn format_qword_row(
+    /// * `out` - The writer receiving the rule.
+    /// * `above` - The positions of the walls in the line above.
+    /// * `below` - The positions of the walls in the line below.
+    pub(crate) fn write_rule<W: fmt::Write>(self: &ByteList<'a>, out: &mut W, above: &[usize], below: &[usize]) -> fmt::Result {
+        match self.border_style {
+            BorderStyle::None => Ok(()),
+            style => writeln!(out, "{}", style.rule(above, below).paint(self.theme.border, self.markup)),
+        }
+    }
+
+    /// Gets the wall drawn between cells.
+    pub(crate) fn wall(self: &ByteList<'a>) -> Painted<&'static str> {
+        self.border_style.vertical().paint(self.theme.border, self.markup)
+    }
+
+    /// Formats a blank offset cell (nothing when the offset column is hidden).
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the cell.
+    fn write_blank_offset<W: fmt::Write>(self: &ByteList<'a>, out: &mut W) -> fmt::Result {
+        match self.fitted_offset_column() {
+            Some(column) => write!(out, "{:1$}{2}", "", column.width(), self.wall()),
+            None => Ok(()),
+        }
+    }
+
+    /// Formats a row of bytes in a table (without the rules above and beneath it), returning the
+    /// positions of the walls in its last line.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the row.
+    /// * `row_number` - The sequence number of this row.
+    /// * `address` - The address of the first column in this row.
+    /// * `skipped` - The number of blank columns before the first byte.
+    /// * `offset` - The index of the first byte of the row within the data.
+    /// * `data` - The bytes within the row to format.
+    fn write_row<W: fmt::Write>(
         self: &ByteList<'a>,
-        qword_number: usize,
+        out: &mut W,
+        row_number: usize,
+        address: u64,
+        skipped: usize,
+        offset: usize,
         data: &[u8],
-        num_bytes: usize,
-    ) -> String {
-        if data.len() != num_bytes {
-            return format!(
-                "ERROR: Data must contain exactly {} bytes. QWORD: {}\n",
-                num_bytes, qword_number
-            );
-        }
-
-        // Row header
-        let mut result = String::from("|QWORD |");
-        // Append byte values
-        result.push_str(
-            &(0..num_bytes)
-                .map(|i| format!("{:0>8b}|", data[i]))
-                .collect::<String>(),
-        );
-        // Append qword number
-        result.push_str(&format!("\n|{:^6}|", qword_number));
-        // Append byte value
-        result.push_str(
-            &(0..num_bytes)
-                .map(|i| format!("{:>8}|", format!("({})", data[i])))
-                .collect::<String>(),
-        );
-        // Append bottom border
-        result.push_str("\n+------+");
-        result.push_str(&(0..num_bytes).map(|_| "--------+").collect::<String>());
-        result.push_str("\n");
-        result
+    ) -> Result<Vec<usize>, fmt::Error> {
+        let num_columns = skipped + data.len();
+        // The wall is painted once for the whole row
+        let mut storage = [0; WALL_CAPACITY];
+        let mut wall = FixedBuffer::new(&mut storage);
+        write!(wall, "{}", self.wall())?;
+        let wall = wall.as_str();
+        let label_width = self.label_width();
+        let mut boundaries: Vec<usize> = (1..=num_columns).collect();
+
+        // Each row has a line per notation (and at least two lines to fit the row label)
+        for line in 0..self.notations.len().max(2) {
+            // Row header (the row number is on the second line)
+            match line {
+                0 => write!(out, "{0}{1:<2$}{0}", wall, self.row_width.label().paint(self.theme.dword_title, self.markup), label_width)?,
+                1 => write!(out, "{0}{1:^2$}{0}", wall, row_number.paint(self.theme.dword_title, self.markup), label_width)?,
+                _ => write!(out, "{0}{1:2$}{0}", wall, "", label_width)?,
+            }
+            // Offset (on the first line)
+            match (self.fitted_offset_column(), line) {
+                (Some(column), 0) => write!(out, "{}{}", column.format_address(address).paint(self.theme.tick_mark, self.markup), wall)?,
+                _ => self.write_blank_offset(out)?,
+            }
+            // Append byte values
+            for _ in 0..skipped {
+                write!(out, "{:8}{}", "", wall)?;
+            }
+            for (i, byte) in data.iter().enumerate() {
+                match self.notations.get(line) {
+                    Some(notation) => {
+                        self.write_cell(out, *notation, *byte, offset + i)?;
+                        write!(out, "{}", wall)?;
+                    }
+                    None => write!(out, "{:8}{}", "", wall)?,
+                }
+            }
+            out.write_char('\n')?;
+        }
+        // Append a line per interpretation (groups that don't fill a number are left blank)
+        for interpretation in &self.interpretations {
+            write!(out, "{0}{1:<2$}{0}", wall, interpretation.label().paint(self.theme.notes, self.markup), label_width)?;
+            self.write_blank_offset(out)?;
+            let group_len = interpretation.number_type.num_bytes();
+            let group_width = group_len * 9 - 1;
+            boundaries.clear();
+            for start in (0..num_columns).step_by(group_len) {
+                let end = (start + group_len).min(num_columns);
+                if start >= skipped && end - start == group_len {
+                    let group = &data[start - skipped..end - skipped];
+                    let value = interpretation.format_group(group, group_width).paint(self.theme.data_value, self.markup);
+                    write!(out, "{:^1$}{2}", value, group_width, wall)?;
+                    boundaries.push(end);
+                } else {
+                    for column in start..end {
+                        write!(out, "{:8}{}", "", wall)?;
+                        boundaries.push(column + 1);
+                    }
+                }
+            }
+            out.write_char('\n')?;
+        }
+        // Append lines labelling the annotated bytes
+        if self.annotation_labels == AnnotationLabels::Inline {
+            for spans in self.label_lines(offset, data.len()) {
+                write!(out, "{0}{1:2$}{0}", wall, "", label_width)?;
+                self.write_blank_offset(out)?;
+                boundaries.clear();
+                let mut column = 0;
+                for (start, end, index) in spans {
+                    let annotation = &self.annotations[index];
+                    let width = (end - start) * 9 - 1;
+                    let label: String = annotation.label.chars().take(width.saturating_sub(2)).collect();
+                    for blank in column..skipped + start {
+                        write!(out, "{:8}{}", "", wall)?;
+                        boundaries.push(blank + 1);
+                    }
+                    write!(
+                        out,
+                        "{}{}",
+                        format!("<{:-^1$}>", label, width - 2).paint(annotation.style_or_default(index), self.markup),
+                        wall
+                    )?;
+                    column = skipped + end;
+                    boundaries.push(column);
+                }
+                for blank in column..num_columns {
+                    write!(out, "{:8}{}", "", wall)?;
+                    boundaries.push(blank + 1);
+                }
+                out.write_char('\n')?;
+            }
+        }
+        Ok(self.walls(boundaries))
+    }
+
+    /// Formats a byte in a cell, colouring any annotated bits.
+    ///
+    /// Binary cells colour each annotated bit, while other notations colour the whole cell when
+    /// any of its bits are annotated. Bits that aren't annotated take the style of the cell.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the cell.
+    /// * `notation` - The notation used to show the byte.
+    /// * `byte` - The byte to format.
+    /// * `index` - The index of the byte within the data.
+    fn write_cell<W: fmt::Write>(self: &ByteList<'a>, out: &mut W, notation: Notation, byte: u8, index: usize) -> fmt::Result {
+        let mut storage = [0; CELL_WIDTH];
+        let mut text = FixedBuffer::new(&mut storage);
+        notation.format_byte_to(&mut text, byte)?;
+        let text = text.as_str();
+        let style = self.cell_style(notation, byte);
+        let cell_style = self
+            .annotations
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, annotation)| annotation.byte_range().contains(&index))
+            .map(|(i, annotation)| annotation.style_or_default(i));
+        match (cell_style, notation) {
+            (None, _) => write!(out, "{:>8}", text.paint(style, self.markup)),
+            (Some(_), Notation::Binary) => {
+                // Paint each run of bits sharing a style
+                let mut run_start = 0;
+                let mut run_style = None;
+                for bit in 0..text.len() {
+                    let bit_style = self.annotation_style(index * 8 + bit);
+                    if bit_style != run_style {
+                        self.write_run(out, &text[run_start..bit], run_style.unwrap_or(style))?;
+                        run_start = bit;
+                        run_style = bit_style;
+                    }
+                }
+                self.write_run(out, &text[run_start..], run_style.unwrap_or(style))
+            }
+            (Some(cell_style), _) => write!(out, "{:>8}", text.paint(cell_style, self.markup)),
+        }
+    }
+
+    /// Gets the style of a cell before any annotations: the last value style matching its byte,
+    /// or the theme's style for its notation.
+    ///
+    /// # Arguments
+    ///
+    /// * `notation` - The notation used to show the byte.
+    /// * `byte` - The byte in the cell.
+    pub(crate) fn cell_style(self: &ByteList<'a>, notation: Notation, byte: u8) -> Style {
+        match self.value_styles.iter().rev().find(|value_style| value_style.contains(byte)) {
+            Some(value_style) => value_style.style,
+            None if notation == Notation::Binary => self.theme.bit,
+            None => self.theme.byte_value,
+        }
+    }
+
+    /// Paints a run of binary digits.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the run.
+    /// * `run` - The binary digits.
+    /// * `style` - The style of the run.
+    pub(crate) fn write_run<W: fmt::Write>(self: &ByteList<'a>, out: &mut W, run: &str, style: Style) -> fmt::Result {
+        match run {
+            "" => Ok(()),
+            _ => write!(out, "{}", run.paint(style, self.markup)),
+        }
+    }
+
+    /// Gets the style of the last annotation containing a bit.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit` - The bit, numbered from the most significant bit of the first byte.
+    fn annotation_style(self: &ByteList<'a>, bit: usize) -> Option<Style> {
+        self.annotations
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, annotation)| annotation.contains(bit))
+            .map(|(index, annotation)| annotation.style_or_default(index))
+    }
+
+    /// Packs the annotations overlapping a row into lines of labels that don't overlap.
+    ///
+    /// Each line lists the columns spanned by each label (relative to the first byte of the row)
+    /// with the index of its annotation.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The index of the first byte of the row within the data.
+    /// * `num_bytes` - The number of bytes in the row.
+    fn label_lines(self: &ByteList<'a>, offset: usize, num_bytes: usize) -> Vec<Vec<(usize, usize, usize)>> {
+        let mut lines: Vec<Vec<(usize, usize, usize)>> = Vec::new();
+        for (index, annotation) in self.annotations.iter().enumerate() {
+            let bytes = annotation.byte_range();
+            let start = bytes.start.max(offset);
+            let end = bytes.end.min(offset + num_bytes);
+            if start >= end {
+                continue;
+            }
+            let span = (start - offset, end - offset, index);
+            match lines.iter_mut().find(|line| line.iter().all(|(s, e, _)| *e <= span.0 || span.1 <= *s)) {
+                Some(line) => line.push(span),
+                None => lines.push(vec![span]),
+            }
+        }
+        for line in &mut lines {
+            line.sort();
+        }
+        lines
+    }
+
+    /// Formats a legend listing the label and range of each annotation.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the legend.
+    fn write_legend<W: fmt::Write>(self: &ByteList<'a>, out: &mut W) -> fmt::Result {
+        for (index, annotation) in self.annotations.iter().enumerate() {
+            writeln!(
+                out,
+                " {:<12} {}",
+                annotation.label.as_str().paint(annotation.style_or_default(index), self.markup),
+                annotation.describe_range()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ByteList<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render_to(f)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::color::Color;
+    use crate::bit_numbering::{BitOrder, BitSpan};
+    use proptest::prelude::*;
 
     #[test]
     fn test_one_byte() {
@@ -118,4 +683,408 @@ mod tests {
 
         assert_eq!(expected, table.format());
     }
+
+    #[test]
+    fn test_notations() {
+        let data = vec![129, 10];
+        let mut table: ByteList = ByteList::from_bytes(&data);
+        table.notations = vec![Notation::Hex, Notation::Octal, Notation::SignedDecimal, Notation::Ascii];
+
+        let expected = "       +--------+--------+--------+--------+--------+--------+--------+--------+\n Bytes | Byte 0 | Byte 1 | Byte 2 | Byte 3 | Byte 4 | Byte 5 | Byte 6 | Byte 7 |\n+------+--------+--------+--------+--------+--------+--------+--------+--------+\n|QWORD |    0x81|    0x0a|\n|  1   |   0o201|    0o12|\n|      |  (-127)|    (10)|\n|      |       .|    '\\n'|\n+------+--------+--------+\n";
+
+        assert_eq!(expected, table.format());
+    }
+
+    #[test]
+    fn test_single_notation() {
+        let data = vec![129];
+        let mut table: ByteList = ByteList::from_bytes(&data);
+        table.notations = vec![Notation::Hex];
+
+        let expected = "       +--------+--------+--------+--------+--------+--------+--------+--------+\n Bytes | Byte 0 | Byte 1 | Byte 2 | Byte 3 | Byte 4 | Byte 5 | Byte 6 | Byte 7 |\n+------+--------+--------+--------+--------+--------+--------+--------+--------+\n|QWORD |    0x81|\n|  1   |        |\n+------+--------+\n";
+
+        assert_eq!(expected, table.format());
+    }
+
+    #[test]
+    fn test_word_rows() {
+        let data = vec![1, 2, 3];
+        let mut table: ByteList = ByteList::from_bytes(&data);
+        table.row_width = RowWidth::Word;
+
+        let expected = "       +--------+--------+\n Bytes | Byte 0 | Byte 1 |\n+------+--------+--------+\n|WORD  |00000001|00000010|\n|  1   |     (1)|     (2)|\n+------+--------+--------+\n|WORD  |00000011|\n|  2   |     (3)|\n+------+--------+\n";
+
+        assert_eq!(expected, table.format());
+    }
+
+    #[test]
+    fn test_full_rows_only() {
+        let data = vec![1, 2, 3, 4];
+        let mut table: ByteList = ByteList::from_bytes(&data);
+        table.row_width = RowWidth::Dword;
+
+        assert!(table.format().ends_with("|DWORD |00000001|00000010|00000011|00000100|\n|  1   |     (1)|     (2)|     (3)|     (4)|\n+------+--------+--------+--------+--------+\n"));
+    }
+
+    #[test]
+    fn test_custom_rows() {
+        let data: Vec<u8> = (0..12).collect();
+        let mut table: ByteList = ByteList::from_bytes(&data);
+        table.row_width = RowWidth::Custom(11);
+
+        let formatted = table.format();
+
+        assert!(formatted.contains("| Byte 9 |Byte 10 |\n"));
+        assert!(formatted.contains("|ROW   |00001011|\n|  2   |    (11)|\n+------+--------+\n"));
+    }
+
+    #[test]
+    fn test_interpretations() {
+        let data = vec![0x3f, 0x80, 0x00, 0x00, 0x01, 0x00, 0xff];
+        let mut table: ByteList = ByteList::from_bytes(&data);
+        table.notations = vec![Notation::Hex];
+        table.row_width = RowWidth::Dword;
+        table.interpretations = vec![
+            Interpretation::new(NumberType::U16, Endianness::Little),
+            Interpretation::new(NumberType::F32, Endianness::Big),
+        ];
+
+        let expected = "       +--------+--------+--------+--------+\n Bytes | Byte 0 | Byte 1 | Byte 2 | Byte 3 |\n+------+--------+--------+--------+--------+\n|DWORD |    0x3f|    0x80|    0x00|    0x00|\n|  1   |        |        |        |        |\n|u16 LE|      32831      |        0        |\n|f32 BE|                 1                 |\n+------+--------+--------+--------+--------+\n|DWORD |    0x01|    0x00|    0xff|\n|  2   |        |        |        |\n|u16 LE|        1        |        |\n|f32 BE|        |        |        |\n+------+--------+--------+--------+\n";
+
+        assert_eq!(expected, table.format());
+    }
+
+    #[test]
+    fn test_dump_layouts() {
+        let data = b"<hello>".to_vec();
+        let mut table: ByteList = ByteList::from_bytes(&data);
+        table.layout = Layout::Xxd { collapse: false };
+
+        assert_eq!("00000000: 3c68 656c 6c6f 3e                        <hello>\n", table.format());
+
+        table.layout = Layout::Hexdump { collapse: true };
+        table.markup = Markup::Html;
+
+        assert_eq!("<pre class=\"bitformat\">\n00000000  3c 68 65 6c 6c 6f 3e                              |&lt;hello&gt;|\n00000007\n</pre>\n", table.format());
+    }
+
+    #[test]
+    fn test_annotation_legend() {
+        let data = vec![0x81, 0x02, 0x03];
+        let mut table: ByteList = ByteList::from_bytes(&data);
+        table.markup = Markup::Html;
+        table.notations = vec![Notation::Binary, Notation::Hex];
+        table.row_width = RowWidth::Word;
+        let mut flags = Annotation::bits("flags", 0..2);
+        flags.style = Some(Style::new(Color::Red).underline());
+        table.annotations = vec![flags, Annotation::bytes("bad", 2..3)];
+
+        let expected = "<pre class=\"bitformat\">\n       +--------+--------+\n Bytes | Byte 0 | Byte 1 |\n+------+--------+--------+\n|WORD  |<span style=\"color:#cd0000;text-decoration:underline\">10</span>000001|00000010|\n|  1   |    <span style=\"color:#cd0000;text-decoration:underline\">0x81</span>|    0x02|\n+------+--------+--------+\n|WORD  |<span style=\"color:#00cdcd\">00000011</span>|\n|  2   |    <span style=\"color:#00cdcd\">0x03</span>|\n+------+--------+\n <span style=\"color:#cd0000;text-decoration:underline\">flags</span>        bits 0-1\n <span style=\"color:#00cdcd\">bad</span>          byte 2\n</pre>\n";
+
+        assert_eq!(expected, table.format());
+    }
+
+    #[test]
+    fn test_annotation_inline_labels() {
+        let data: Vec<u8> = (0..6).collect();
+        let mut table: ByteList = ByteList::from_bytes(&data);
+        table.markup = Markup::Plain;
+        table.notations = vec![Notation::Hex];
+        table.row_width = RowWidth::Dword;
+        table.annotation_labels = AnnotationLabels::Inline;
+        table.annotations = vec![Annotation::bytes("length", 0..2), Annotation::bytes("CRC", 1..6), Annotation::bits("b", 16..17)];
+
+        let expected = "       +--------+--------+--------+--------+\n Bytes | Byte 0 | Byte 1 | Byte 2 | Byte 3 |\n+------+--------+--------+--------+--------+\n|DWORD |    0x00|    0x01|    0x02|    0x03|\n|  1   |        |        |        |        |\n|      |<----length----->|<--b--->|        |\n|      |        |<----------CRC----------->|\n+------+--------+--------+-----------------+\n|DWORD |    0x04|    0x05|\n|  2   |        |        |\n|      |<------CRC------>|\n+------+-----------------+\n";
+
+        assert_eq!(expected, table.format());
+    }
+
+    #[test]
+    fn test_to_json() {
+        let data = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let table: ByteList = ByteList::from_bytes(&data);
+
+        let expected = "{\"length\":9,\"row_width\":8,\"base_address\":0,\"rows\":[{\"row\":1,\"offset\":0,\"address\":0,\"bytes\":[1,2,3,4,5,6,7,8]},{\"row\":2,\"offset\":8,\"address\":8,\"bytes\":[9]}]}";
+
+        assert_eq!(expected, table.to_json());
+    }
+
+    #[test]
+    fn test_offset_column() {
+        let data = vec![1, 2, 3];
+        let mut table: ByteList = ByteList::from_bytes(&data);
+        table.notations = vec![Notation::Hex];
+        table.row_width = RowWidth::Word;
+        table.offset_column = Some(OffsetColumn::default());
+        table.base_address = 0x2000_0000;
+
+        let expected = "       +----------+--------+--------+\n Bytes |  Offset  | Byte 0 | Byte 1 |\n+------+----------+--------+--------+\n|WORD  |0x20000000|    0x01|    0x02|\n|  1   |          |        |        |\n+------+----------+--------+--------+\n|WORD  |0x20000002|    0x03|\n|  2   |          |        |\n+------+----------+--------+\n";
+
+        assert_eq!(expected, table.format());
+    }
+
+    #[test]
+    fn test_unaligned_base_address() {
+        let data = vec![1, 2, 3, 4, 5, 6];
+        let mut table: ByteList = ByteList::from_bytes(&data);
+        table.notations = vec![Notation::Hex];
+        table.row_width = RowWidth::Dword;
+        table.interpretations = vec![Interpretation::new(NumberType::U16, Endianness::Big)];
+        table.offset_column = Some(OffsetColumn::new(OffsetFormat::Decimal, 4));
+        table.base_address = 13;
+
+        let expected = "       +------+--------+--------+--------+--------+\n Bytes |Offset| Byte 0 | Byte 1 | Byte 2 | Byte 3 |\n+------+------+--------+--------+--------+--------+\n|DWORD |    12|        |    0x01|    0x02|    0x03|\n|  1   |      |        |        |        |        |\n|u16 BE|      |        |        |       515       |\n+------+------+--------+--------+--------+--------+\n|DWORD |    16|    0x04|    0x05|    0x06|\n|  2   |      |        |        |        |\n|u16 BE|      |      1029       |        |\n+------+------+-----------------+--------+\n";
+
+        assert_eq!(expected, table.format());
+        assert!(table.to_json().contains("{\"row\":1,\"offset\":0,\"address\":13,\"bytes\":[1,2,3]},{\"row\":2,\"offset\":3,\"address\":16,\"bytes\":[4,5,6]}"));
+    }
+
+    #[test]
+    fn test_border_styles() {
+        let data = vec![1, 2, 3];
+        let mut table: ByteList = ByteList::from_bytes(&data);
+        table.markup = Markup::Plain;
+        table.notations = vec![Notation::Hex];
+        table.row_width = RowWidth::Word;
+        table.interpretations = vec![Interpretation::new(NumberType::U16, Endianness::Big)];
+        table.border_style = BorderStyle::Rounded;
+
+        let expected = "       ╭────────┬────────╮\n Bytes │ Byte 0 │ Byte 1 │\n╭──────┼────────┼────────┤\n│WORD  │    0x01│    0x02│\n│  1   │        │        │\n│u16 BE│       258       │\n├──────┼────────┬────────╯\n│WORD  │    0x03│\n│  2   │        │\n│u16 BE│        │\n╰──────┴────────╯\n";
+
+        assert_eq!(expected, table.format());
+
+        table.border_style = BorderStyle::None;
+
+        let expected = " Bytes   Byte 0   Byte 1  \n WORD       0x01     0x02 \n   1                      \n u16 BE        258        \n WORD       0x03 \n   2             \n u16 BE          \n";
+
+        assert_eq!(expected, table.format());
+    }
+
+    #[test]
+    fn test_bit_numbering() {
+        let data = vec![0x81, 0x0f];
+        let mut table: ByteList = ByteList::from_bytes(&data);
+        table.markup = Markup::Plain;
+        table.notations = vec![Notation::Binary];
+        table.row_width = RowWidth::Word;
+        table.bit_numbering = Some(BitNumbering::new(BitOrder::Lsb0, BitSpan::Row));
+
+        let expected = "       +--------+--------+\n Bytes | Byte 0 | Byte 1 |\n Bits  |54321098|76543210|\n+------+--------+--------+\n|WORD  |10000001|00001111|\n|  1   |        |        |\n+------+--------+--------+\n";
+
+        assert_eq!(expected, table.format());
+
+        table.bit_numbering = Some(BitNumbering::new(BitOrder::Msb0, BitSpan::Byte));
+
+        assert!(table.format().contains("\n Bits  |01234567|01234567|\n"));
+    }
+
+    #[test]
+    fn test_documents() {
+        let data = vec![0x81, 0x0f, 0x41];
+        let mut table: ByteList = ByteList::from_bytes(&data);
+        table.notations = vec![Notation::Hex, Notation::Ascii];
+        table.row_width = RowWidth::Word;
+        table.interpretations = vec![Interpretation::new(NumberType::U16, Endianness::Big)];
+
+        let expected = "| Row                  | Byte 0             | Byte 1    |\n| -------------------- | ------------------ | --------- |\n| WORD 1<br><br>u16 BE | 0x81<br>.<br>33039 | 0x0f<br>. |\n| WORD 2<br><br>u16 BE | 0x41<br>'A'        |           |\n";
+
+        assert_eq!(expected, table.to_markdown());
+
+        table.notations = vec![Notation::Hex];
+        table.base_address = 1;
+
+        let expected = "+--------+--------+--------+\n| Row    | Byte 0 | Byte 1 |\n+========+========+========+\n| WORD 1 |        | 0x81   |\n| u16 BE |        |        |\n+--------+--------+--------+\n| WORD 2 | 0x0f   | 0x41   |\n| u16 BE | 3905   |        |\n+--------+--------+--------+\n";
+
+        assert_eq!(expected, table.to_rst());
+    }
+
+    #[test]
+    fn test_theme_and_value_styles() {
+        let data = vec![0x00, 0x41, 0xff, 0x80];
+        let mut table: ByteList = ByteList::from_bytes(&data);
+        table.markup = Markup::Html;
+        table.notations = vec![Notation::Hex];
+        table.row_width = RowWidth::Dword;
+        table.theme = Theme::plain();
+        table.theme.border = Style::new(Color::Blue);
+        table.theme.byte_value = Style::default().bold();
+        table.value_styles = vec![ValueStyle::zero(), ValueStyle::printable(), ValueStyle::max()];
+        table.annotations = vec![Annotation::bytes("flags", 2..3)];
+        let formatted = table.format();
+
+        assert!(formatted.contains("<span style=\"color:#0000ee\">|</span>"));
+        assert!(formatted.contains("    <span style=\"opacity:0.6\">0x00</span>"));
+        assert!(formatted.contains("    <span style=\"color:#00cd00\">0x41</span>"));
+        // Annotations are drawn over value styles
+        assert!(formatted.contains("    <span style=\"color:#cdcd00\">0xff</span>"));
+        assert!(formatted.contains("    <span style=\"font-weight:bold\">0x80</span>"));
+    }
+
+    #[test]
+    fn test_max_width() {
+        let data: Vec<u8> = (1..=8).collect();
+        let mut table: ByteList = ByteList::from_bytes(&data);
+        table.markup = Markup::Plain;
+        table.notations = vec![Notation::Hex];
+        table.interpretations = vec![
+            Interpretation::new(NumberType::U16, Endianness::Big),
+            Interpretation::new(NumberType::U64, Endianness::Big),
+        ];
+        table.max_width = Some(50);
+
+        let expected = "       +--------+--------+--------+--------+\n Bytes | Byte 0 | Byte 1 | Byte 2 | Byte 3 |\n+------+--------+--------+--------+--------+\n|DWORD |    0x01|    0x02|    0x03|    0x04|\n|  1   |        |        |        |        |\n|u16 BE|       258       |       772       |\n+------+--------+--------+--------+--------+\n|DWORD |    0x05|    0x06|    0x07|    0x08|\n|  2   |        |        |        |        |\n|u16 BE|      1286       |      1800       |\n+------+-----------------+-----------------+\n";
+
+        assert_eq!(expected, table.format());
+        assert!(table.to_string().lines().all(|line| line.len() <= 50));
+
+        // A table that already fits is unchanged
+        table.max_width = Some(80);
+
+        assert!(table.format().starts_with("       +--------+--------+--------+--------+--------+--------+--------+--------+\n"));
+    }
+
+    fn notation() -> impl Strategy<Value = Notation> {
+        prop_oneof![
+            Just(Notation::Binary),
+            Just(Notation::Hex),
+            Just(Notation::Octal),
+            Just(Notation::SignedDecimal),
+            Just(Notation::UnsignedDecimal),
+            Just(Notation::Ascii),
+        ]
+    }
+
+    fn interpretation() -> impl Strategy<Value = Interpretation> {
+        let number_type = prop_oneof![
+            Just(NumberType::U16),
+            Just(NumberType::U32),
+            Just(NumberType::U64),
+            Just(NumberType::I16),
+            Just(NumberType::I32),
+            Just(NumberType::I64),
+            Just(NumberType::F32),
+            Just(NumberType::F64),
+        ];
+        let endianness = prop_oneof![Just(Endianness::Big), Just(Endianness::Little)];
+        (number_type, endianness).prop_map(|(number_type, endianness)| Interpretation::new(number_type, endianness))
+    }
+
+    /// The options of a table: row width, notations, interpretations, offset column, base address,
+    /// bit numbering and maximum width.
+    type Options = (usize, Vec<Notation>, Vec<Interpretation>, Option<(bool, u8)>, u64, Option<(bool, bool)>, Option<usize>);
+
+    fn options() -> impl Strategy<Value = Options> {
+        (
+            1..20usize,
+            prop::collection::vec(notation(), 1..4),
+            prop::collection::vec(interpretation(), 0..3),
+            any::<Option<(bool, u8)>>(),
+            any::<u64>(),
+            any::<Option<(bool, bool)>>(),
+            prop::option::of(10..200usize),
+        )
+    }
+
+    /// Builds a plain table of data with the given options.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The bytes in the table.
+    /// * `options` - The options of the table.
+    fn table(data: &[u8], options: Options) -> ByteList<'_> {
+        let (row_width, notations, interpretations, offset_column, base_address, bit_numbering, max_width) = options;
+        let mut table = ByteList::from_bytes(data);
+        table.markup = Markup::Plain;
+        table.row_width = RowWidth::Custom(row_width);
+        table.notations = notations;
+        table.interpretations = interpretations;
+        table.offset_column = offset_column.map(|(hex, digits)| {
+            let format = if hex { OffsetFormat::Hex } else { OffsetFormat::Decimal };
+            OffsetColumn::new(format, usize::from(digits % 20))
+        });
+        table.base_address = base_address;
+        table.bit_numbering = bit_numbering.map(|(lsb0, per_byte)| {
+            let order = if lsb0 { BitOrder::Lsb0 } else { BitOrder::Msb0 };
+            let span = if per_byte { BitSpan::Byte } else { BitSpan::Row };
+            BitNumbering::new(order, span)
+        });
+        table.max_width = max_width;
+        table
+    }
+
+    /// Picks a border style whose walls can't be mistaken for the `|` of an ASCII cell.
+    fn border_style() -> impl Strategy<Value = BorderStyle> {
+        prop_oneof![
+            Just(BorderStyle::Light),
+            Just(BorderStyle::Heavy),
+            Just(BorderStyle::Double),
+            Just(BorderStyle::Rounded),
+        ]
+    }
+
+    /// Checks that the walls of a rendered table line up, and that every line between its header
+    /// and its last row is as wide as the header (only the last row may stop short).
+    ///
+    /// # Arguments
+    ///
+    /// * `border_style` - The style the table was drawn in.
+    /// * `text` - The rendered table, without markup.
+    fn table_aligned(border_style: BorderStyle, text: &str) -> bool {
+        // Every line but the rules holds walls
+        let lines: Vec<&str> = text.lines().collect();
+        let rows: Vec<&[&str]> = lines
+            .split(|line| !line.contains(border_style.vertical()))
+            .filter(|row| !row.is_empty())
+            .collect();
+        let width = |line: &&str| line.chars().count();
+        let header_width = rows.first().and_then(|header| header.first()).map_or(0, width);
+        border_style.rows_aligned(text)
+            && rows.iter().enumerate().skip(1).all(|(i, row)| {
+                row.iter().all(|line| if i + 1 < rows.len() { width(line) == header_width } else { width(line) <= header_width })
+            })
+    }
+
+    proptest! {
+        /// Tests that tables of arbitrary data never panic and that their walls line up.
+        #[test]
+        fn prop_rows_aligned(data in prop::collection::vec(any::<u8>(), 0..80), options in options(), border_style in border_style()) {
+            let mut table = table(&data, options);
+            table.border_style = border_style;
+            let formatted = table.format();
+
+            prop_assert!(table_aligned(border_style, &formatted), "{}", formatted);
+        }
+
+        /// Tests that streamed tables (with and without a known length) line up like lists do.
+        #[cfg(feature = "std")]
+        #[test]
+        fn prop_streamed_rows_aligned(data in prop::collection::vec(any::<u8>(), 0..80), options in options(), border_style in border_style(), offset in 0..1000u64, known_length: bool) {
+            let mut table = table(&[], options);
+            table.border_style = border_style;
+            let mut stream = ByteStream::new(&table);
+            stream.offset = offset;
+            stream.length = if known_length { Some(data.len() as u64) } else { None };
+            let mut streamed = Vec::new();
+            stream.write(data.as_slice(), &mut streamed).unwrap();
+            let streamed = String::from_utf8(streamed).unwrap();
+
+            prop_assert!(table_aligned(border_style, &streamed), "{}", streamed);
+        }
+
+        /// Tests that annotations anywhere (even past the end of the data) never panic.
+        #[test]
+        fn prop_annotations_never_panic(data in prop::collection::vec(any::<u8>(), 0..40), bits in prop::collection::vec((0..400usize, 0..400usize), 0..4), inline: bool, layout in 0..3u8) {
+            let mut table = ByteList::from_bytes(&data);
+            table.markup = Markup::Plain;
+            table.annotations = bits.iter().map(|&(start, end)| Annotation::bits("label", start..end)).collect();
+            table.annotation_labels = if inline { AnnotationLabels::Inline } else { AnnotationLabels::Legend };
+            table.layout = match layout {
+                0 => Layout::Table,
+                1 => Layout::Xxd { collapse: inline },
+                _ => Layout::Hexdump { collapse: inline },
+            };
+
+            table.format();
+        }
+    }
 }
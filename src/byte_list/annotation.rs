@@ -0,0 +1,184 @@
+use core::ops::Range;
+use core::str::FromStr;
+use crate::color::Color;
+use crate::prelude::*;
+use crate::theme::Style;
+
+/// The colours given to annotations without a colour of their own (in order).
+const PALETTE: [Color; 6] = [Color::Yellow, Color::Cyan, Color::Magenta, Color::Green, Color::Red, Color::Blue];
+
+/// A labelled range of bits highlighted in a `ByteList`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    pub label: String,
+    /// The annotated bits, numbered from the most significant bit of the first byte.
+    pub bits: Range<usize>,
+    /// The style of the annotated cells (a colour picked from a palette when `None`).
+    pub style: Option<Style>,
+}
+
+/// Where annotation labels are shown.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum AnnotationLabels {
+    /// A legend beneath the table listing each label and its range.
+    #[default]
+    Legend,
+    /// A line beneath each row marking the annotated bytes with their labels.
+    Inline,
+}
+
+impl Annotation {
+    /// Creates an annotation over a range of bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label describing the bytes.
+    /// * `bytes` - The annotated bytes.
+    pub fn bytes(label: &str, bytes: Range<usize>) -> Annotation {
+        Annotation::bits(label, bytes.start * 8..bytes.end * 8)
+    }
+
+    /// Creates an annotation over a range of bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `label` - The label describing the bits.
+    /// * `bits` - The annotated bits, numbered from the most significant bit of the first byte.
+    pub fn bits(label: &str, bits: Range<usize>) -> Annotation {
+        Annotation {
+            label: String::from(label),
+            bits,
+            style: None,
+        }
+    }
+
+    /// Gets the bytes containing any of the annotated bits.
+    pub(crate) fn byte_range(&self) -> Range<usize> {
+        self.bits.start / 8..self.bits.end.div_ceil(8)
+    }
+
+    /// Checks whether a bit is annotated.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit` - The bit, numbered from the most significant bit of the first byte.
+    pub(crate) fn contains(&self, bit: usize) -> bool {
+        self.bits.contains(&bit)
+    }
+
+    /// Gets the style of the annotation, falling back to a colour from the palette.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The position of the annotation in the list of annotations.
+    pub(crate) fn style_or_default(&self, index: usize) -> Style {
+        self.style.unwrap_or_else(|| Style::new(PALETTE[index % PALETTE.len()]))
+    }
+
+    /// Describes the annotated range (e.g. `bytes 4-7` or `bit 100`).
+    pub(crate) fn describe_range(&self) -> String {
+        let (unit, range) = if self.bits.start.is_multiple_of(8) && self.bits.end.is_multiple_of(8) {
+            ("byte", self.bits.start / 8..self.bits.end / 8)
+        } else {
+            ("bit", self.bits.clone())
+        };
+        match range.len() {
+            0 => format!("no {}s", unit),
+            1 => format!("{} {}", unit, range.start),
+            _ => format!("{}s {}-{}", unit, range.start, range.end - 1),
+        }
+    }
+}
+
+impl FromStr for Annotation {
+    type Err = String;
+
+    /// Parses an annotation written as `RANGE=LABEL` with an optional `@STYLE` suffix, where the
+    /// range is a byte (`12`) or range of bytes (`4..8`) or, with a `bits:` prefix, of bits, and the
+    /// style is a colour and attributes joined by `+` (e.g. `4..8=CRC@red+bold` or
+    /// `bits:100..103=flags@underline`).
+    fn from_str(text: &str) -> Result<Annotation, String> {
+        let error = || format!("'{}' is not an annotation. Use RANGE=LABEL[@STYLE] (e.g. 4..8=CRC@red+bold or bits:100..103=flags).", text);
+        let (range, rest) = text.split_once('=').ok_or_else(error)?;
+        let (label, style) = match rest.rsplit_once('@') {
+            Some((label, style)) => (label, Some(parse_style(style)?)),
+            None => (rest, None),
+        };
+        let (is_bits, range) = match range.trim().strip_prefix("bits:") {
+            Some(range) => (true, range),
+            None => (false, range.trim()),
+        };
+        let parse = |number: &str| number.trim().parse::<usize>().map_err(|_| error());
+        let range = match range.split_once("..") {
+            Some((start, end)) => parse(start)?..parse(end)?,
+            None => parse(range).map(|start| start..start + 1)?,
+        };
+        let mut annotation = if is_bits {
+            Annotation::bits(label, range)
+        } else {
+            Annotation::bytes(label, range)
+        };
+        annotation.style = style;
+        Ok(annotation)
+    }
+}
+
+/// Parses a style written as a colour and attributes joined by `+` (e.g. `red+bold` or `underline`).
+///
+/// # Arguments
+///
+/// * `text` - The style.
+fn parse_style(text: &str) -> Result<Style, String> {
+    let mut style = Style::default();
+    for part in text.split('+') {
+        match part.trim() {
+            "bold" => style.bold = true,
+            "dim" => style.dim = true,
+            "underline" => style.underline = true,
+            color => {
+                let color = Color::from_str(&color.replace(['-', '_'], " "))
+                    .map_err(|_| format!("'{}' is not a colour or an attribute (bold, dim or underline).", color))?;
+                style.color = Some(color);
+            }
+        }
+    }
+    Ok(style)
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ranges() {
+        let crc = Annotation::bytes("CRC", 4..8);
+        let flags = Annotation::bits("flags", 9..12);
+
+        assert_eq!(32..64, crc.bits);
+        assert_eq!(4..8, crc.byte_range());
+        assert_eq!(1..2, flags.byte_range());
+        assert_eq!("bytes 4-7", crc.describe_range());
+        assert_eq!("byte 12", Annotation::bytes("bad", 12..13).describe_range());
+        assert_eq!("bits 9-11", flags.describe_range());
+    }
+
+    #[test]
+    fn test_from_str() {
+        let mut crc = Annotation::bytes("CRC", 4..8);
+        crc.style = Some(Style::new(Color::BrightRed));
+        let mut flags = Annotation::bits("flags", 0..3);
+        flags.style = Some(Style::new(Color::Red).bold().underline());
+
+        assert_eq!(Ok(crc), "4..8=CRC@bright-red".parse());
+        assert_eq!(Ok(flags), "bits:0..3=flags@red+bold+underline".parse());
+        assert_eq!(Some(Style::default().dim()), "1=pad@dim".parse::<Annotation>().unwrap().style);
+        assert_eq!(Ok(Annotation::bytes("bad", 12..13)), "12=bad".parse());
+        assert_eq!(Ok(Annotation::bits("flags", 100..103)), "bits:100..103=flags".parse());
+        assert!("4..8".parse::<Annotation>().is_err());
+        assert!("4..8=CRC@plaid".parse::<Annotation>().is_err());
+    }
+}
+
+// #endregion Unit tests
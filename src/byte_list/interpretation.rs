@@ -0,0 +1,205 @@
+use core::convert::TryInto;
+use core::fmt;
+use core::str::FromStr;
+use crate::prelude::*;
+
+/// A number type that a group of bytes can be interpreted as.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumberType {
+    U16,
+    U32,
+    U64,
+    I16,
+    I32,
+    I64,
+    F32,
+    F64,
+}
+
+/// The order of the bytes within a multi-byte number.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Endianness {
+    /// Most significant byte first (network order).
+    Big,
+    /// Least significant byte first.
+    Little,
+}
+
+/// An interpretation of each group of bytes in a row as a number.
+///
+/// Each interpretation adds a line to every row, with each value shown beneath the bytes it was
+/// read from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interpretation {
+    pub number_type: NumberType,
+    pub endianness: Endianness,
+}
+
+impl NumberType {
+    /// Gets the number of bytes in a number of this type.
+    pub fn num_bytes(self) -> usize {
+        match self {
+            NumberType::U16 | NumberType::I16 => 2,
+            NumberType::U32 | NumberType::I32 | NumberType::F32 => 4,
+            NumberType::U64 | NumberType::I64 | NumberType::F64 => 8,
+        }
+    }
+
+    /// Gets the name of this type (e.g. `u32`).
+    pub fn name(self) -> &'static str {
+        match self {
+            NumberType::U16 => "u16",
+            NumberType::U32 => "u32",
+            NumberType::U64 => "u64",
+            NumberType::I16 => "i16",
+            NumberType::I32 => "i32",
+            NumberType::I64 => "i64",
+            NumberType::F32 => "f32",
+            NumberType::F64 => "f64",
+        }
+    }
+}
+
+impl Interpretation {
+    pub fn new(number_type: NumberType, endianness: Endianness) -> Interpretation {
+        Interpretation { number_type, endianness }
+    }
+
+    /// Gets the label shown at the start of the interpretation's line (e.g. `u32 BE`).
+    pub fn label(self) -> String {
+        format!(
+            "{} {}",
+            self.number_type.name(),
+            match self.endianness {
+                Endianness::Big => "BE",
+                Endianness::Little => "LE",
+            }
+        )
+    }
+
+    /// Formats a group of bytes as a number.
+    ///
+    /// Floating point values too wide for `width` characters are shown in exponent notation.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The group of bytes (exactly `number_type.num_bytes()` of them).
+    /// * `width` - The number of characters available.
+    pub fn format_group(self, bytes: &[u8], width: usize) -> String {
+        let mut ordered: Vec<u8> = bytes.to_vec();
+        if self.endianness == Endianness::Little {
+            ordered.reverse();
+        }
+        match self.number_type {
+            NumberType::U16 => u16::from_be_bytes(array(&ordered)).to_string(),
+            NumberType::U32 => u32::from_be_bytes(array(&ordered)).to_string(),
+            NumberType::U64 => u64::from_be_bytes(array(&ordered)).to_string(),
+            NumberType::I16 => i16::from_be_bytes(array(&ordered)).to_string(),
+            NumberType::I32 => i32::from_be_bytes(array(&ordered)).to_string(),
+            NumberType::I64 => i64::from_be_bytes(array(&ordered)).to_string(),
+            NumberType::F32 => fit_float(f32::from_be_bytes(array(&ordered)), width),
+            NumberType::F64 => fit_float(f64::from_be_bytes(array(&ordered)), width),
+        }
+    }
+}
+
+impl FromStr for Interpretation {
+    type Err = String;
+
+    /// Parses an interpretation from a type name with an optional `be` or `le` suffix
+    /// (e.g. `u32`, `u32le` or `f64-be`). Big-endian is assumed when no suffix is given.
+    fn from_str(text: &str) -> Result<Interpretation, String> {
+        let lower = text.to_ascii_lowercase();
+        let (name, endianness) = if let Some(name) = lower.strip_suffix("le") {
+            (name.trim_end_matches(['-', '_']), Endianness::Little)
+        } else if let Some(name) = lower.strip_suffix("be") {
+            (name.trim_end_matches(['-', '_']), Endianness::Big)
+        } else {
+            (lower.as_str(), Endianness::Big)
+        };
+        let number_type = match name {
+            "u16" => NumberType::U16,
+            "u32" => NumberType::U32,
+            "u64" => NumberType::U64,
+            "i16" => NumberType::I16,
+            "i32" => NumberType::I32,
+            "i64" => NumberType::I64,
+            "f32" => NumberType::F32,
+            "f64" => NumberType::F64,
+            _ => return Err(format!("'{}' is not a number type. Use u16, u32, u64, i16, i32, i64, f32 or f64 with an optional be or le suffix.", text)),
+        };
+        Ok(Interpretation::new(number_type, endianness))
+    }
+}
+
+impl fmt::Display for Interpretation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.label().replace(' ', "").to_ascii_lowercase())
+    }
+}
+
+/// Copies bytes into a fixed size array.
+fn array<const N: usize>(bytes: &[u8]) -> [u8; N] {
+    bytes.try_into().unwrap_or([0; N])
+}
+
+/// Formats a floating point value, switching to exponent notation when it is too wide.
+///
+/// # Arguments
+///
+/// * `value` - The value to format.
+/// * `width` - The number of characters available.
+fn fit_float<T: fmt::Display + fmt::LowerExp>(value: T, width: usize) -> String {
+    let result = value.to_string();
+    if result.len() <= width {
+        result
+    } else {
+        format!("{:e}", value)
+    }
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_integers() {
+        let bytes = [0xff, 0xfe];
+        assert_eq!("65534", Interpretation::new(NumberType::U16, Endianness::Big).format_group(&bytes, 17));
+        assert_eq!("65279", Interpretation::new(NumberType::U16, Endianness::Little).format_group(&bytes, 17));
+        assert_eq!("-2", Interpretation::new(NumberType::I16, Endianness::Big).format_group(&bytes, 17));
+        assert_eq!(
+            "16909060",
+            Interpretation::new(NumberType::U32, Endianness::Big).format_group(&[1, 2, 3, 4], 35)
+        );
+        assert_eq!(
+            "-1",
+            Interpretation::new(NumberType::I64, Endianness::Little).format_group(&[0xff; 8], 71)
+        );
+    }
+
+    #[test]
+    fn test_format_floats() {
+        assert_eq!("1", Interpretation::new(NumberType::F32, Endianness::Big).format_group(&[0x3f, 0x80, 0, 0], 35));
+        assert_eq!("-2.5", Interpretation::new(NumberType::F64, Endianness::Little).format_group(&[0, 0, 0, 0, 0, 0, 0x04, 0xc0], 71));
+        assert_eq!("1e-45", Interpretation::new(NumberType::F32, Endianness::Big).format_group(&[0, 0, 0, 1], 35));
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(Ok(Interpretation::new(NumberType::U32, Endianness::Big)), "u32".parse());
+        assert_eq!(Ok(Interpretation::new(NumberType::U32, Endianness::Little)), "u32le".parse());
+        assert_eq!(Ok(Interpretation::new(NumberType::F64, Endianness::Big)), "F64-BE".parse());
+        assert!("u8".parse::<Interpretation>().is_err());
+    }
+
+    #[test]
+    fn test_label() {
+        assert_eq!("i16 LE", Interpretation::new(NumberType::I16, Endianness::Little).label());
+        assert_eq!("i16le", Interpretation::new(NumberType::I16, Endianness::Little).to_string());
+    }
+}
+
+// #endregion Unit tests
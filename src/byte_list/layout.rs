@@ -0,0 +1,313 @@
+use core::fmt;
+use crate::prelude::*;
+
+/// The overall layout used to render a `ByteList`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Layout {
+    /// A table with a row per `row_width` bytes and a line per notation.
+    #[default]
+    Table,
+    /// The output of `xxd` (16 bytes per line in groups of two, then an ASCII gutter).
+    ///
+    /// When `collapse` is set, runs of all-zero lines are replaced with `*` as `xxd -a` does.
+    Xxd { collapse: bool },
+    /// The output of `hexdump -C` (16 bytes per line in groups of eight, then an ASCII gutter).
+    ///
+    /// When `collapse` is set (as `hexdump -C` does by default), lines repeating the previous line
+    /// are replaced with `*`. Clearing it matches `hexdump -Cv`.
+    Hexdump { collapse: bool },
+}
+
+/// The number of bytes shown on each line of a dump.
+pub(crate) const LINE_LEN: usize = 16;
+
+/// Formats a dump a line at a time, so that dumps of any length can be streamed.
+///
+/// Every line but the last must hold `LINE_LEN` bytes.
+pub(crate) struct Dump {
+    /// Whether to format as `hexdump -C` does (otherwise as `xxd` does).
+    hexdump: bool,
+    base_address: u64,
+    collapse: bool,
+    /// The number of lines formatted so far.
+    num_lines: usize,
+    /// The number of bytes formatted so far.
+    num_bytes: u64,
+    /// The previous line (compared by `hexdump -C` to collapse repeated lines).
+    previous: Vec<u8>,
+    /// Whether the previous line was replaced with `*`.
+    collapsed: bool,
+    /// The index of the first line in the current run of all-zero lines (used by `xxd -a`).
+    zero_run_start: usize,
+    /// The number of lines in the current run of all-zero lines.
+    zero_run: usize,
+}
+
+impl Dump {
+    /// Creates a dump for a layout, or `None` for the table layout.
+    ///
+    /// # Arguments
+    ///
+    /// * `layout` - The layout of the dump.
+    /// * `base_address` - The offset shown for the first byte.
+    pub(crate) fn new(layout: Layout, base_address: u64) -> Option<Dump> {
+        let (hexdump, collapse) = match layout {
+            Layout::Table => return None,
+            Layout::Xxd { collapse } => (false, collapse),
+            Layout::Hexdump { collapse } => (true, collapse),
+        };
+        Some(Dump {
+            hexdump,
+            base_address,
+            collapse,
+            num_lines: 0,
+            num_bytes: 0,
+            previous: Vec::with_capacity(LINE_LEN),
+            collapsed: false,
+            zero_run_start: 0,
+            zero_run: 0,
+        })
+    }
+
+    /// Formats the next line of bytes (writing nothing if it is collapsed).
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the line.
+    /// * `line` - The bytes on the line.
+    pub(crate) fn write_line<W: fmt::Write>(&mut self, out: &mut W, line: &[u8]) -> fmt::Result {
+        let index = self.num_lines;
+        self.num_lines += 1;
+        self.num_bytes += line.len() as u64;
+        if self.hexdump {
+            self.write_hexdump_line(out, line, index)
+        } else {
+            self.write_xxd_line(out, line, index)
+        }
+    }
+
+    /// Formats anything left at the end of the dump.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the end of the dump.
+    pub(crate) fn finish<W: fmt::Write>(&mut self, out: &mut W) -> fmt::Result {
+        if self.hexdump {
+            // The final line shows the length of the data
+            match self.num_bytes {
+                0 => Ok(()),
+                len => writeln!(out, "{:08x}", self.base_address.wrapping_add(len)),
+            }
+        } else {
+            self.end_zero_run(out, true)
+        }
+    }
+
+    /// Formats a line as `xxd` does, holding back all-zero lines until the end of their run.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the line.
+    /// * `line` - The bytes on the line.
+    /// * `index` - The index of the line.
+    fn write_xxd_line<W: fmt::Write>(&mut self, out: &mut W, line: &[u8], index: usize) -> fmt::Result {
+        if self.collapse && line.len() == LINE_LEN && line.iter().all(|byte| *byte == 0) {
+            self.zero_run += 1;
+            if self.zero_run > 1 {
+                return Ok(());
+            }
+            self.zero_run_start = index;
+        } else {
+            self.end_zero_run(out, false)?;
+        }
+        write_xxd_line(out, line, address(self.base_address, index))
+    }
+
+    /// Formats the lines held back from a run of all-zero lines (the first line is already shown).
+    ///
+    /// xxd only hides lines when it can show the first and either the last line or a `*`: a run of
+    /// at least three followed by other lines hides all but the first, while a run of at least four
+    /// at the end hides all but the first and last.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the lines.
+    /// * `at_end` - Whether the run ends the data.
+    fn end_zero_run<W: fmt::Write>(&mut self, out: &mut W, at_end: bool) -> fmt::Result {
+        let run = self.zero_run;
+        self.zero_run = 0;
+        if run == 0 {
+            return Ok(());
+        }
+        let shown = match (at_end, run) {
+            (false, 3..) => 0,
+            (true, 4..) => 1,
+            _ => run - 1,
+        };
+        if shown < run - 1 {
+            out.write_str("*\n")?;
+        }
+        for index in self.zero_run_start + run - shown..self.zero_run_start + run {
+            write_xxd_line(out, &[0; LINE_LEN], address(self.base_address, index))?;
+        }
+        Ok(())
+    }
+
+    /// Formats a line as `hexdump -C` does.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the line.
+    /// * `line` - The bytes on the line.
+    /// * `index` - The index of the line.
+    fn write_hexdump_line<W: fmt::Write>(&mut self, out: &mut W, line: &[u8], index: usize) -> fmt::Result {
+        if self.collapse && index > 0 && self.previous == line {
+            if self.collapsed {
+                return Ok(());
+            }
+            self.collapsed = true;
+            return out.write_str("*\n");
+        }
+        self.collapsed = false;
+        self.previous.clear();
+        self.previous.extend_from_slice(line);
+
+        write!(out, "{:08x} ", address(self.base_address, index))?;
+        for (j, column) in (0..LINE_LEN).map(|j| line.get(j)).enumerate() {
+            if j % 8 == 0 {
+                out.write_char(' ')?;
+            }
+            match column {
+                Some(byte) => write!(out, "{:02x} ", byte)?,
+                None => out.write_str("   ")?,
+            }
+        }
+        out.write_str(" |")?;
+        write_gutter(out, line)?;
+        out.write_str("|\n")
+    }
+}
+
+/// Formats a line of `xxd` output.
+///
+/// # Arguments
+///
+/// * `out` - The writer receiving the line.
+/// * `line` - The bytes on the line.
+/// * `address` - The offset shown for the first byte.
+fn write_xxd_line<W: fmt::Write>(out: &mut W, line: &[u8], address: u64) -> fmt::Result {
+    write!(out, "{:08x}: ", address)?;
+    for j in 0..LINE_LEN {
+        match line.get(j) {
+            Some(byte) => write!(out, "{:02x}", byte)?,
+            None => out.write_str("  ")?,
+        }
+        if j % 2 == 1 {
+            out.write_char(' ')?;
+        }
+    }
+    out.write_char(' ')?;
+    write_gutter(out, line)?;
+    out.write_char('\n')
+}
+
+/// Gets the address of the first byte on a line.
+///
+/// # Arguments
+///
+/// * `base_address` - The address of the first byte.
+/// * `line` - The index of the line.
+fn address(base_address: u64, line: usize) -> u64 {
+    base_address.wrapping_add((line * LINE_LEN) as u64)
+}
+
+/// Formats the ASCII gutter of a line, showing unprintable bytes as `.`.
+///
+/// # Arguments
+///
+/// * `out` - The writer receiving the gutter.
+/// * `line` - The bytes on the line.
+fn write_gutter<W: fmt::Write>(out: &mut W, line: &[u8]) -> fmt::Result {
+    for byte in line {
+        out.write_char(match byte {
+            0x20..=0x7e => *byte as char,
+            _ => '.',
+        })?;
+    }
+    Ok(())
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn xxd(data: &[u8], base_address: u64, collapse: bool) -> String {
+        format_dump(Layout::Xxd { collapse }, data, base_address)
+    }
+
+    fn hexdump(data: &[u8], base_address: u64, collapse: bool) -> String {
+        format_dump(Layout::Hexdump { collapse }, data, base_address)
+    }
+
+    fn format_dump(layout: Layout, data: &[u8], base_address: u64) -> String {
+        let mut dump = Dump::new(layout, base_address).unwrap();
+        let mut result = String::new();
+        for line in data.chunks(LINE_LEN) {
+            dump.write_line(&mut result, line).unwrap();
+        }
+        dump.finish(&mut result).unwrap();
+        result
+    }
+
+    #[test]
+    fn test_xxd() {
+        let expected = "00000000: 6865 6c6c 6f20 776f 726c 640a            hello world.\n";
+
+        assert_eq!(expected, xxd(b"hello world\n", 0, false));
+        assert_eq!("", xxd(b"", 0, true));
+    }
+
+    #[test]
+    fn test_xxd_collapse() {
+        let zeros = "0000 0000 0000 0000 0000 0000 0000 0000  ................\n";
+        let mut data = vec![0; 80];
+        data.extend_from_slice(b"abc");
+
+        assert_eq!(
+            format!("00000010: {}*\n00000060: 6162 63                                  abc\n", zeros),
+            xxd(&data, 0x10, true)
+        );
+        assert_eq!(format!("00000000: {}*\n00000030: {}", zeros, zeros), xxd(&[0; 64], 0, true));
+        assert_eq!(format!("00000000: {}00000010: {}", zeros, zeros), xxd(&[0; 32], 0, true));
+        assert_eq!(
+            format!("00000000: {}00000010: {}00000020: 00                                       .\n", zeros, zeros),
+            xxd(&[0; 33], 0, true)
+        );
+    }
+
+    #[test]
+    fn test_hexdump() {
+        let expected = "00000000  68 65 6c 6c 6f 20 77 6f  72 6c 64 0a              |hello world.|\n0000000c\n";
+
+        assert_eq!(expected, hexdump(b"hello world\n", 0, true));
+        assert_eq!("", hexdump(b"", 0, true));
+    }
+
+    #[test]
+    fn test_hexdump_collapse() {
+        let mut data = vec![0x41; 48];
+        data.push(0x42);
+        let line = "41 41 41 41 41 41 41 41  41 41 41 41 41 41 41 41  |AAAAAAAAAAAAAAAA|\n";
+        let last = "00000030  42                                                |B|\n00000031\n";
+
+        assert_eq!(format!("00000000  {}*\n{}", line, last), hexdump(&data, 0, true));
+        assert_eq!(
+            format!("00000000  {}00000010  {}00000020  {}{}", line, line, line, last),
+            hexdump(&data, 0, false)
+        );
+    }
+}
+
+// #endregion Unit tests
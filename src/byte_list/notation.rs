@@ -0,0 +1,98 @@
+use core::fmt;
+use crate::prelude::*;
+
+/// A notation used to show the value of each byte in a `ByteList`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Notation {
+    /// Binary digits (e.g. `10000001`).
+    Binary,
+    /// Hexadecimal (e.g. `0x81`).
+    Hex,
+    /// Octal (e.g. `0o201`).
+    Octal,
+    /// Two's complement signed decimal (e.g. `(-127)`).
+    SignedDecimal,
+    /// Unsigned decimal (e.g. `(129)`).
+    UnsignedDecimal,
+    /// The ASCII character (e.g. `'a'`), with non-printable bytes shown as `.`.
+    Ascii,
+}
+
+impl Notation {
+    /// Formats a byte using this notation.
+    ///
+    /// # Arguments
+    ///
+    /// * `byte` - The byte to format.
+    pub fn format_byte(self, byte: u8) -> String {
+        let mut text = String::new();
+        self.format_byte_to(&mut text, byte).expect("Writing to a string cannot fail.");
+        text
+    }
+
+    /// Writes a byte using this notation straight into a writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the formatted byte.
+    /// * `byte` - The byte to format.
+    pub fn format_byte_to<W: fmt::Write>(self, out: &mut W, byte: u8) -> fmt::Result {
+        match self {
+            Notation::Binary => write!(out, "{:0>8b}", byte),
+            Notation::Hex => write!(out, "{:#04x}", byte),
+            Notation::Octal => write!(out, "{:#o}", byte),
+            Notation::SignedDecimal => write!(out, "({})", byte as i8),
+            Notation::UnsignedDecimal => write!(out, "({})", byte),
+            Notation::Ascii => match byte {
+                b'\0' => out.write_str("'\\0'"),
+                b'\t' => out.write_str("'\\t'"),
+                b'\n' => out.write_str("'\\n'"),
+                b'\r' => out.write_str("'\\r'"),
+                b' '..=b'~' => write!(out, "'{}'", byte as char),
+                _ => out.write_char('.'),
+            },
+        }
+    }
+}
+
+/// The most characters a notation writes (the width of a cell).
+pub(crate) const CELL_WIDTH: usize = 8;
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_binary() {
+        assert_eq!("00000101", Notation::Binary.format_byte(5));
+    }
+
+    #[test]
+    fn test_format_hex() {
+        assert_eq!("0x05", Notation::Hex.format_byte(5));
+        assert_eq!("0xff", Notation::Hex.format_byte(255));
+    }
+
+    #[test]
+    fn test_format_octal() {
+        assert_eq!("0o201", Notation::Octal.format_byte(129));
+    }
+
+    #[test]
+    fn test_format_decimal() {
+        assert_eq!("(129)", Notation::UnsignedDecimal.format_byte(129));
+        assert_eq!("(-127)", Notation::SignedDecimal.format_byte(129));
+        assert_eq!("(5)", Notation::SignedDecimal.format_byte(5));
+    }
+
+    #[test]
+    fn test_format_ascii() {
+        assert_eq!("'a'", Notation::Ascii.format_byte(b'a'));
+        assert_eq!("'\\n'", Notation::Ascii.format_byte(b'\n'));
+        assert_eq!(".", Notation::Ascii.format_byte(0x80));
+    }
+}
+
+// #endregion Unit tests
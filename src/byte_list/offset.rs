@@ -0,0 +1,106 @@
+use crate::prelude::*;
+
+/// The radix used to show the offset of each row of a `ByteList`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OffsetFormat {
+    /// Hexadecimal with a `0x` prefix (e.g. `0x00000010`).
+    Hex,
+    /// Decimal (e.g. `16`).
+    Decimal,
+}
+
+/// The offset column shown between the row label and the bytes of a `ByteList`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OffsetColumn {
+    pub format: OffsetFormat,
+    /// The minimum number of digits shown (hexadecimal offsets are padded with zeros).
+    pub digits: usize,
+}
+
+impl OffsetColumn {
+    /// Creates an offset column.
+    ///
+    /// # Arguments
+    ///
+    /// * `format` - The radix used to show each offset.
+    /// * `digits` - The minimum number of digits shown.
+    pub fn new(format: OffsetFormat, digits: usize) -> OffsetColumn {
+        OffsetColumn { format, digits }
+    }
+
+    /// Gets the width of the column (wide enough for its `Offset` heading).
+    pub(crate) fn width(self) -> usize {
+        match self.format {
+            OffsetFormat::Hex => self.digits + 2,
+            OffsetFormat::Decimal => self.digits,
+        }
+        .max(6)
+    }
+
+    /// Widens the column (if needed) so that every address in a range fits.
+    ///
+    /// # Arguments
+    ///
+    /// * `first` - The first address shown.
+    /// * `len` - The number of bytes from the first address.
+    pub(crate) fn fitting(self, first: u64, len: u64) -> OffsetColumn {
+        let last = first.saturating_add(len);
+        let digits = match self.format {
+            OffsetFormat::Hex => last.checked_ilog2().map_or(1, |bits| bits as usize / 4 + 1),
+            OffsetFormat::Decimal => last.checked_ilog10().map_or(1, |digits| digits as usize + 1),
+        };
+        OffsetColumn::new(self.format, self.digits.max(digits))
+    }
+
+    /// Formats an address for display in the column.
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address of the first byte in a row.
+    pub(crate) fn format_address(self, address: u64) -> String {
+        match self.format {
+            OffsetFormat::Hex => format!("{:>1$}", format!("0x{:01$x}", address, self.digits), self.width()),
+            OffsetFormat::Decimal => format!("{:>1$}", address, self.width()),
+        }
+    }
+}
+
+impl Default for OffsetColumn {
+    /// Eight hexadecimal digits (e.g. `0x00000010`).
+    fn default() -> OffsetColumn {
+        OffsetColumn::new(OffsetFormat::Hex, 8)
+    }
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_offsets() {
+        let column = OffsetColumn::default();
+
+        assert_eq!(10, column.width());
+        assert_eq!("0x20000010", column.format_address(0x2000_0010));
+        assert_eq!("0x123456789", column.format_address(0x1_2345_6789));
+    }
+
+    #[test]
+    fn test_decimal_offsets() {
+        let column = OffsetColumn::new(OffsetFormat::Decimal, 4);
+
+        assert_eq!(6, column.width());
+        assert_eq!("    16", column.format_address(16));
+    }
+
+    #[test]
+    fn test_fitting() {
+        assert_eq!(OffsetColumn::default(), OffsetColumn::default().fitting(0x10, 32));
+        assert_eq!(9, OffsetColumn::default().fitting(0xffff_fff0, 32).digits);
+        assert_eq!(20, OffsetColumn::new(OffsetFormat::Decimal, 4).fitting(u64::MAX - 1, 2).digits);
+    }
+}
+
+// #endregion Unit tests
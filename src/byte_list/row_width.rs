@@ -0,0 +1,119 @@
+use core::fmt;
+use core::str::FromStr;
+use crate::prelude::*;
+
+/// The number of bytes shown on each row of a `ByteList`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RowWidth {
+    /// 1 byte (8 bits) per row.
+    Byte,
+    /// 2 bytes (16 bits) per row.
+    Word,
+    /// 4 bytes (32 bits) per row.
+    Dword,
+    /// 8 bytes (64 bits) per row.
+    Qword,
+    /// 16 bytes (128 bits) per row.
+    Oword,
+    /// Any other number of bytes per row.
+    Custom(usize),
+}
+
+impl RowWidth {
+    /// Gets the number of bytes in each row (at least 1).
+    pub fn num_bytes(self) -> usize {
+        match self {
+            RowWidth::Byte => 1,
+            RowWidth::Word => 2,
+            RowWidth::Dword => 4,
+            RowWidth::Qword => 8,
+            RowWidth::Oword => 16,
+            RowWidth::Custom(num_bytes) => num_bytes.max(1),
+        }
+    }
+
+    /// Gets the label shown at the start of each row.
+    pub fn label(self) -> &'static str {
+        match self {
+            RowWidth::Byte => "BYTE",
+            RowWidth::Word => "WORD",
+            RowWidth::Dword => "DWORD",
+            RowWidth::Qword => "QWORD",
+            RowWidth::Oword => "OWORD",
+            RowWidth::Custom(_) => "ROW",
+        }
+    }
+}
+
+impl From<usize> for RowWidth {
+    fn from(num_bytes: usize) -> RowWidth {
+        match num_bytes {
+            1 => RowWidth::Byte,
+            2 => RowWidth::Word,
+            4 => RowWidth::Dword,
+            8 => RowWidth::Qword,
+            16 => RowWidth::Oword,
+            _ => RowWidth::Custom(num_bytes),
+        }
+    }
+}
+
+impl FromStr for RowWidth {
+    type Err = String;
+
+    /// Parses a row width from its name (e.g. `dword`) or a number of bytes (e.g. `4`).
+    fn from_str(text: &str) -> Result<RowWidth, String> {
+        match text.to_ascii_lowercase().as_str() {
+            "byte" => Ok(RowWidth::Byte),
+            "word" => Ok(RowWidth::Word),
+            "dword" => Ok(RowWidth::Dword),
+            "qword" => Ok(RowWidth::Qword),
+            "oword" => Ok(RowWidth::Oword),
+            number => match number.parse::<usize>() {
+                Ok(num_bytes) if num_bytes > 0 => Ok(RowWidth::from(num_bytes)),
+                _ => Err(format!("'{}' is not a row width. Use byte, word, dword, qword, oword or a number of bytes.", text)),
+            },
+        }
+    }
+}
+
+impl fmt::Display for RowWidth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RowWidth::Custom(num_bytes) => write!(f, "{}", num_bytes),
+            _ => write!(f, "{}", self.label().to_ascii_lowercase()),
+        }
+    }
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_num_bytes() {
+        assert_eq!(1, RowWidth::Byte.num_bytes());
+        assert_eq!(16, RowWidth::Oword.num_bytes());
+        assert_eq!(3, RowWidth::Custom(3).num_bytes());
+        assert_eq!(1, RowWidth::Custom(0).num_bytes());
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(Ok(RowWidth::Dword), "dword".parse());
+        assert_eq!(Ok(RowWidth::Dword), "4".parse());
+        assert_eq!(Ok(RowWidth::Custom(12)), "12".parse());
+        assert!("0".parse::<RowWidth>().is_err());
+        assert!("nibble".parse::<RowWidth>().is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("qword", RowWidth::Qword.to_string());
+        assert_eq!("12", RowWidth::Custom(12).to_string());
+    }
+}
+
+// #endregion Unit tests
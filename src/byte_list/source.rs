@@ -0,0 +1,129 @@
+use core::fmt;
+use super::layout::{Dump, LINE_LEN};
+use super::{AnnotationLabels, ByteList};
+use crate::markup::{EscapeHtml, Markup};
+use crate::prelude::*;
+
+/// A source of the bytes rendered by a `ByteList`, read a row (or dump line) at a time.
+pub(crate) trait Source {
+    /// The error returned when reading or rendering fails.
+    type Error;
+
+    /// Reads until a buffer is full or the source is exhausted, returning the number of bytes read.
+    ///
+    /// # Arguments
+    ///
+    /// * `buffer` - The buffer to fill.
+    fn read_full(&mut self, buffer: &mut [u8]) -> Result<usize, Self::Error>;
+
+    /// Converts an error from the text writer into the error of the source.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - The error returned by the text writer.
+    fn format_error(error: fmt::Error) -> Self::Error;
+}
+
+impl Source for &[u8] {
+    type Error = fmt::Error;
+
+    fn read_full(&mut self, buffer: &mut [u8]) -> Result<usize, fmt::Error> {
+        let len = buffer.len().min(self.len());
+        buffer[..len].copy_from_slice(&self[..len]);
+        *self = &self[len..];
+        Ok(len)
+    }
+
+    fn format_error(error: fmt::Error) -> fmt::Error {
+        error
+    }
+}
+
+impl<'a> ByteList<'a> {
+    /// Renders the bytes read from a source straight into a text writer, returning the number of
+    /// bytes rendered.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_address` - The address of the first byte read.
+    /// * `source` - The source of the bytes.
+    /// * `out` - The writer receiving the rendered bytes.
+    pub(crate) fn render_source<S: Source, W: fmt::Write>(self: &ByteList<'a>, base_address: u64, source: &mut S, out: &mut W) -> Result<u64, S::Error> {
+        out.write_str(self.markup.prefix()).map_err(S::format_error)?;
+        let num_bytes = match (Dump::new(self.layout, base_address), self.markup) {
+            (Some(dump), Markup::Html) => self.render_dump(dump, source, &mut EscapeHtml(out))?,
+            (Some(dump), _) => self.render_dump(dump, source, out)?,
+            (None, _) => self.render_table(base_address, source, out)?,
+        };
+        out.write_str(self.markup.suffix()).map_err(S::format_error)?;
+        Ok(num_bytes)
+    }
+
+    /// Renders the bytes as an `xxd` or `hexdump -C` dump, a line at a time.
+    ///
+    /// # Arguments
+    ///
+    /// * `dump` - The dump formatting each line.
+    /// * `source` - The source of the bytes.
+    /// * `out` - The writer receiving the formatted output.
+    fn render_dump<S: Source, W: fmt::Write>(self: &ByteList<'a>, mut dump: Dump, source: &mut S, out: &mut W) -> Result<u64, S::Error> {
+        let mut line = [0; LINE_LEN];
+        let mut num_bytes = 0;
+        loop {
+            let len = source.read_full(&mut line)?;
+            if len == 0 {
+                break;
+            }
+            dump.write_line(out, &line[..len]).map_err(S::format_error)?;
+            num_bytes += len as u64;
+            if len < LINE_LEN {
+                break;
+            }
+        }
+        dump.finish(out).map_err(S::format_error)?;
+        Ok(num_bytes)
+    }
+
+    /// Renders the bytes as a table, a row at a time.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_address` - The address of the first byte read.
+    /// * `source` - The source of the bytes.
+    /// * `out` - The writer receiving the formatted output.
+    fn render_table<S: Source, W: fmt::Write>(self: &ByteList<'a>, base_address: u64, source: &mut S, out: &mut W) -> Result<u64, S::Error> {
+        // Narrower rows are used when the table is wider than the list's maximum width
+        let list = self.fitted();
+        let row_width = list.row_width.num_bytes();
+        // Rows are aligned to the base address, so the first row may start part way through
+        let mut skipped = (base_address % row_width as u64) as usize;
+        let mut row = vec![0; row_width];
+        let mut offset = 0;
+
+        list.write_table_header(out).map_err(S::format_error)?;
+        let mut walls_above = list.header_walls();
+        for row_number in 1.. {
+            let wanted = row_width - skipped;
+            let len = source.read_full(&mut row[..wanted])?;
+            if len == 0 {
+                break;
+            }
+            let address = base_address.wrapping_add(offset as u64).wrapping_sub(skipped as u64);
+            let walls_below = list.walls(1..=skipped + len);
+            list.write_rule(out, &walls_above, &walls_below).map_err(S::format_error)?;
+            walls_above = list
+                .write_row(out, row_number, address, skipped, offset, &row[..len])
+                .map_err(S::format_error)?;
+            offset += len;
+            skipped = 0;
+            if len < wanted {
+                break;
+            }
+        }
+        list.write_rule(out, &walls_above, &[]).map_err(S::format_error)?;
+        if list.annotation_labels == AnnotationLabels::Legend {
+            list.write_legend(out).map_err(S::format_error)?;
+        }
+        Ok(offset as u64)
+    }
+}
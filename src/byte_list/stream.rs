@@ -0,0 +1,225 @@
+use std::fmt;
+use std::io::{self, ErrorKind, Read, Write};
+use super::source::Source;
+use super::ByteList;
+
+/// Formats bytes read from a stream, writing each row as soon as its bytes are read.
+///
+/// Only a row of bytes is held in memory at a time, so files of any size can be formatted. The
+/// formatting options are taken from a `ByteList`, whose own bytes are ignored.
+pub struct ByteStream<'l, 'a> {
+    /// The number of bytes to skip before the first formatted byte.
+    pub offset: u64,
+    /// The maximum number of bytes to format (every remaining byte when `None`). The offset and row
+    /// label columns are sized to fit this many bytes, so set it whenever the length is known (a
+    /// stream of unknown length is given columns wide enough for any 64-bit address).
+    pub length: Option<u64>,
+    list: &'l ByteList<'a>,
+}
+
+impl<'l, 'a> ByteStream<'l, 'a> {
+    /// Creates a stream formatter using the options of a list.
+    ///
+    /// # Arguments
+    ///
+    /// * `list` - The list whose options are used (e.g. `ByteList::empty()`).
+    pub fn new(list: &'l ByteList<'a>) -> ByteStream<'l, 'a> {
+        ByteStream {
+            offset: 0,
+            length: None,
+            list,
+        }
+    }
+
+    /// Reads bytes from a reader, writing them to a writer as they are formatted, and returns the
+    /// number of bytes formatted.
+    ///
+    /// The first formatted byte is shown at `base_address + offset`, while annotations are numbered
+    /// from the first formatted byte. Output is written a piece at a time, so wrap unbuffered
+    /// writers (such as files) in a `BufWriter`.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source of the bytes.
+    /// * `writer` - The destination of the formatted output.
+    pub fn write<R: Read, W: Write>(self: &ByteStream<'l, 'a>, reader: R, writer: W) -> io::Result<u64> {
+        let mut out = IoWriter { writer, error: None };
+        let result = self.render_to(reader, &mut out);
+        // Report the writer's own error rather than the formatting error it caused
+        match out.error {
+            Some(error) => Err(error),
+            None => result,
+        }
+    }
+
+    /// Reads bytes from a reader, rendering them straight into a text writer, and returns the
+    /// number of bytes formatted.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The source of the bytes.
+    /// * `out` - The writer receiving the formatted output (e.g. a `String`).
+    pub fn render_to<R: Read, W: fmt::Write>(self: &ByteStream<'l, 'a>, mut reader: R, out: &mut W) -> io::Result<u64> {
+        io::copy(&mut (&mut reader).take(self.offset), &mut io::sink())?;
+        let reader = reader.take(self.length.unwrap_or(u64::MAX));
+        let list = self.list.for_stream(self.list.base_address.wrapping_add(self.offset), self.length);
+        list.render_source(list.base_address, &mut ReadSource(reader), out)
+    }
+}
+
+/// Reads the bytes to format from an I/O reader.
+struct ReadSource<R: Read>(R);
+
+impl<R: Read> Source for ReadSource<R> {
+    type Error = io::Error;
+
+    fn read_full(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let mut len = 0;
+        while len < buffer.len() {
+            match self.0.read(&mut buffer[len..]) {
+                Ok(0) => break,
+                Ok(read) => len += read,
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(len)
+    }
+
+    fn format_error(error: fmt::Error) -> io::Error {
+        io::Error::other(error)
+    }
+}
+
+/// Passes formatted text on to an I/O writer, keeping the error that stopped it.
+struct IoWriter<W: Write> {
+    writer: W,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> fmt::Write for IoWriter<W> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.writer.write_all(text.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::byte_list::{Layout, Notation, OffsetColumn, OffsetFormat, RowWidth};
+    use crate::markup::Markup;
+
+    /// A reader returning at most three bytes from each read.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            let len = buffer.len().min(self.0.len()).min(3);
+            buffer[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn test_matches_format() {
+        let data: Vec<u8> = (0..=255).collect();
+        let mut list = ByteList::from_bytes(&data);
+        list.markup = Markup::Plain;
+        list.base_address = 3;
+        let mut stream = ByteStream::new(&list);
+        stream.length = Some(256);
+        let mut output = Vec::new();
+
+        let num_bytes = stream.write(Trickle(&data), &mut output).unwrap();
+
+        assert_eq!(256, num_bytes);
+        assert_eq!(list.format(), String::from_utf8(output).unwrap());
+    }
+
+    #[test]
+    fn test_column_widths() {
+        let mut list = ByteList::empty();
+        list.markup = Markup::Plain;
+        list.notations = vec![Notation::Hex];
+        list.row_width = RowWidth::Dword;
+        list.offset_column = Some(OffsetColumn::new(OffsetFormat::Decimal, 8));
+        list.base_address = 99_999_990;
+        let mut stream = ByteStream::new(&list);
+        stream.length = Some(16);
+        let mut output = Vec::new();
+
+        stream.write(&[0u8; 16][..], &mut output).unwrap();
+
+        // The offset of the last row needs a ninth digit
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("|DWORD |100000000|"), "{}", output);
+        // Every line has its walls where the header does (the last row is shorter)
+        let walls = |line: &str| -> Vec<usize> { line.match_indices('|').map(|(i, _)| i).filter(|i| *i > 0).collect() };
+        let header = walls(output.lines().nth(1).unwrap());
+        for line in output.lines().filter(|line| !line.trim_start().starts_with('+')) {
+            assert!(header.starts_with(&walls(line)), "{}", output);
+        }
+
+        // Two million rows need a wider row label column, as does a stream of unknown length
+        assert_eq!(7, list.for_stream(0, Some(16_000_000)).label_width());
+        assert_eq!(u64::MAX.div_ceil(4).to_string().len(), list.for_stream(0, None).label_width());
+        // The rules of those wider columns still meet the walls of the lines between them
+        stream.length = None;
+        let mut output = Vec::new();
+        stream.write(&[0u8; 16][..], &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let junctions = |line: &str| -> Vec<usize> { line.match_indices('+').map(|(i, _)| i).collect() };
+        let header = junctions(output.lines().next().unwrap());
+        assert_eq!(walls(output.lines().nth(1).unwrap()), header, "{}", output);
+    }
+
+    #[test]
+    fn test_window() {
+        let data: Vec<u8> = (0..100).collect();
+        let window = data[40..46].to_vec();
+        let mut list = ByteList::empty();
+        list.markup = Markup::Plain;
+        list.notations = vec![Notation::Hex];
+        list.row_width = RowWidth::Dword;
+        let mut stream = ByteStream::new(&list);
+        stream.offset = 40;
+        stream.length = Some(6);
+        let mut output = Vec::new();
+
+        let num_bytes = stream.write(data.as_slice(), &mut output).unwrap();
+
+        let mut expected = ByteList::from_bytes(&window);
+        expected.markup = Markup::Plain;
+        expected.notations = vec![Notation::Hex];
+        expected.row_width = RowWidth::Dword;
+        expected.base_address = 40;
+        assert_eq!(6, num_bytes);
+        assert_eq!(expected.format(), String::from_utf8(output).unwrap());
+    }
+
+    #[test]
+    fn test_dump() {
+        let mut data = vec![0; 80];
+        data.extend_from_slice(b"<abc>");
+        let mut list = ByteList::empty();
+        list.markup = Markup::Html;
+        list.layout = Layout::Xxd { collapse: true };
+        let mut output = Vec::new();
+
+        ByteStream::new(&list).write(Trickle(&data), &mut output).unwrap();
+
+        assert_eq!(
+            "<pre class=\"bitformat\">\n00000000: 0000 0000 0000 0000 0000 0000 0000 0000  ................\n*\n\
+             00000050: 3c61 6263 3e                             &lt;abc&gt;\n</pre>\n",
+            String::from_utf8(output).unwrap()
+        );
+    }
+}
+
+// #endregion Unit tests
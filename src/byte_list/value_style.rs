@@ -0,0 +1,51 @@
+use core::ops::RangeInclusive;
+use crate::color::Color;
+use crate::theme::Style;
+
+/// A style given to the cells of bytes whose values fall in a range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueStyle {
+    /// The values styled.
+    pub values: RangeInclusive<u8>,
+    /// The style of the cells holding the values.
+    pub style: Style,
+}
+
+impl ValueStyle {
+    /// Creates a style for a range of values.
+    ///
+    /// # Arguments
+    ///
+    /// * `values` - The values styled.
+    /// * `style` - The style (or just a colour) of the cells holding the values.
+    pub fn new<S: Into<Style>>(values: RangeInclusive<u8>, style: S) -> ValueStyle {
+        ValueStyle {
+            values,
+            style: style.into(),
+        }
+    }
+
+    /// Dims zero bytes, so that padding and unused space fade into the background.
+    pub fn zero() -> ValueStyle {
+        ValueStyle::new(0..=0, Style::default().dim())
+    }
+
+    /// Highlights printable ASCII characters (from space to `~`), so that text stands out.
+    pub fn printable() -> ValueStyle {
+        ValueStyle::new(0x20..=0x7e, Color::Green)
+    }
+
+    /// Marks bytes with every bit set (`0xff`), which often show erased flash or uninitialised memory.
+    pub fn max() -> ValueStyle {
+        ValueStyle::new(0xff..=0xff, Style::new(Color::Red).bold())
+    }
+
+    /// Checks whether a byte is styled.
+    ///
+    /// # Arguments
+    ///
+    /// * `byte` - The byte.
+    pub(crate) fn contains(&self, byte: u8) -> bool {
+        self.values.contains(&byte)
+    }
+}
@@ -0,0 +1,167 @@
+use std::io;
+use bytes::{Buf, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+use crate::websocket_frame::{FrameError, WebSocketFrame};
+
+/// Splits a byte stream (e.g. a `TcpStream` read with `FramedRead`) into WebSocket data frames, and
+/// writes frames back out as bytes, so that a connection can be tapped while it is relayed.
+///
+/// Decoding waits until a whole frame has arrived, so frames are never split across items.
+#[derive(Debug, Clone)]
+pub struct FrameCodec {
+    /// The longest frame accepted (in bytes). Longer frames are reported as invalid data rather
+    /// than buffered. Defaults to `WebSocketFrame::DEFAULT_MAX_LEN`; `None` buffers frames of any
+    /// length, so a peer can exhaust memory by declaring a huge payload length. Only use `None`
+    /// with trusted peers.
+    pub max_frame_len: Option<usize>,
+    /// Mirrors each decoded frame to the `tracing` sink as a debug event with its diagram and
+    /// fields (see `trace_frame!`).
+    pub mirror: bool,
+}
+
+/// The most space reserved in the read buffer at a time while waiting for the rest of a frame.
+///
+/// Frames declare their length up front, so the buffer grows as the bytes arrive rather than
+/// trusting the declared length.
+const MAX_RESERVE: usize = 8 * 1024;
+
+impl FrameCodec {
+    /// Creates a codec accepting frames up to `WebSocketFrame::DEFAULT_MAX_LEN` bytes long, without
+    /// mirroring.
+    pub fn new() -> FrameCodec {
+        FrameCodec::default()
+    }
+
+    /// Checks that a frame (or the part of it read so far) is not too long.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame_len` - The number of bytes needed so far.
+    fn check_length(&self, frame_len: usize) -> io::Result<()> {
+        match self.max_frame_len {
+            Some(max) if frame_len > max => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("The frame is longer than {} bytes (at least {} bytes).", max, frame_len),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Default for FrameCodec {
+    fn default() -> FrameCodec {
+        FrameCodec {
+            max_frame_len: Some(WebSocketFrame::DEFAULT_MAX_LEN),
+            mirror: false,
+        }
+    }
+}
+
+impl Decoder for FrameCodec {
+    type Item = WebSocketFrame<'static>;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<WebSocketFrame<'static>>> {
+        let frame = match WebSocketFrame::try_from_bytes(src) {
+            Ok(frame) => frame.into_owned(),
+            Err(FrameError::Truncated { expected, actual }) => {
+                // Wait for the rest of the frame, unless it is too long to buffer
+                self.check_length(expected)?;
+                src.reserve((expected - actual).min(MAX_RESERVE));
+                return Ok(None);
+            }
+        };
+        self.check_length(frame.frame_len)?;
+        src.advance(frame.frame_len);
+        if self.mirror {
+            crate::trace_frame!(tracing::Level::DEBUG, frame, "Decoded frame");
+        }
+        Ok(Some(frame))
+    }
+}
+
+impl Encoder<WebSocketFrame<'_>> for FrameCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, frame: WebSocketFrame<'_>, dst: &mut BytesMut) -> io::Result<()> {
+        dst.extend_from_slice(&frame.to_bytes());
+        Ok(())
+    }
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_partial_frames() {
+        let data = base64::decode("gYR7q0rdD845qQ==").unwrap();
+        let mut codec = FrameCodec::new();
+        let mut src = BytesMut::new();
+
+        // The frame arrives in pieces, followed by the start of a ping
+        src.extend_from_slice(&data[..1]);
+        assert!(codec.decode(&mut src).unwrap().is_none());
+        src.extend_from_slice(&data[1..7]);
+        assert!(codec.decode(&mut src).unwrap().is_none());
+        src.extend_from_slice(&data[7..]);
+        src.extend_from_slice(&[0x89]);
+
+        let frame = codec.decode(&mut src).unwrap().unwrap();
+
+        assert_eq!(10, frame.frame_len);
+        assert_eq!(1, frame.opcode());
+        assert_eq!(&[0x89], &src[..]);
+        assert!(codec.decode(&mut src).unwrap().is_none());
+        src.extend_from_slice(&[0x00]);
+        assert_eq!("Ping", codec.decode(&mut src).unwrap().unwrap().opcode_name());
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    fn test_max_frame_len() {
+        let mut codec = FrameCodec::new();
+        codec.max_frame_len = Some(8);
+
+        // The payload length is known before the payload arrives
+        let mut src = BytesMut::from(&[0x82, 0x7e, 0x01, 0x00][..]);
+        let error = codec.decode(&mut src).err().expect("The frame is too long.");
+
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+        assert_eq!("The frame is longer than 8 bytes (at least 260 bytes).", error.to_string());
+    }
+
+    #[test]
+    fn test_huge_declared_length() {
+        // A long frame header declaring the largest possible payload
+        let header = [0x82, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff];
+
+        // The default limit rejects it
+        let mut src = BytesMut::from(&header[..]);
+        let error = FrameCodec::new().decode(&mut src).err().expect("The frame is too long.");
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+
+        // Without a limit it waits for more bytes, reserving only a little space
+        let mut codec = FrameCodec::new();
+        codec.max_frame_len = None;
+        let mut src = BytesMut::from(&header[..]);
+        assert!(codec.decode(&mut src).unwrap().is_none());
+        assert!(src.capacity() <= header.len() + MAX_RESERVE);
+    }
+
+    #[test]
+    fn test_encode() {
+        let data = base64::decode("gYR7q0rdD845qQ==").unwrap();
+        let mut codec = FrameCodec::new();
+        let mut dst = BytesMut::new();
+
+        codec.encode(WebSocketFrame::from_bytes(&data), &mut dst).unwrap();
+        codec.encode(WebSocketFrame::from_bytes(&[0x8a, 0x00]), &mut dst).unwrap();
+
+        assert_eq!([&data[..], &[0x8a, 0x00]].concat(), dst.to_vec());
+    }
+}
+
+// #endregion Unit tests
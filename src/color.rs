@@ -0,0 +1,60 @@
+#[cfg(feature = "std")]
+pub use colored::Color;
+
+/// A terminal colour.
+///
+/// This has the same variants as `colored::Color`, which is used in its place when the `std`
+/// feature is enabled.
+#[cfg(not(feature = "std"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    TrueColor { r: u8, g: u8, b: u8 },
+}
+
+/// The names of the colours (as accepted by `colored`).
+#[cfg(not(feature = "std"))]
+const NAMES: [(&str, Color); 17] = [
+    ("black", Color::Black),
+    ("red", Color::Red),
+    ("green", Color::Green),
+    ("yellow", Color::Yellow),
+    ("blue", Color::Blue),
+    ("magenta", Color::Magenta),
+    ("purple", Color::Magenta),
+    ("cyan", Color::Cyan),
+    ("white", Color::White),
+    ("bright black", Color::BrightBlack),
+    ("bright red", Color::BrightRed),
+    ("bright green", Color::BrightGreen),
+    ("bright yellow", Color::BrightYellow),
+    ("bright blue", Color::BrightBlue),
+    ("bright magenta", Color::BrightMagenta),
+    ("bright cyan", Color::BrightCyan),
+    ("bright white", Color::BrightWhite),
+];
+
+#[cfg(not(feature = "std"))]
+impl core::str::FromStr for Color {
+    type Err = ();
+
+    /// Parses a colour name (e.g. `bright blue`), ignoring case.
+    fn from_str(name: &str) -> Result<Color, ()> {
+        NAMES.iter().find(|(known, _)| known.eq_ignore_ascii_case(name)).map(|(_, color)| *color).ok_or(())
+    }
+}
@@ -0,0 +1,477 @@
+mod frame_diff;
+
+use crate::color::Color;
+use crate::buffer::FixedBuffer;
+use crate::byte_list::{ByteList, Notation, CELL_WIDTH, WALL_CAPACITY};
+use crate::json;
+use crate::markup::Paint;
+use crate::theme::Style;
+use core::fmt::{self, Write};
+pub use frame_diff::{FieldDiff, FrameDiff};
+use crate::prelude::*;
+
+/// The largest alignment table built before falling back to comparing bytes position by position.
+const MAX_ALIGNMENT_CELLS: usize = 1 << 22;
+
+/// A step in the alignment of two byte buffers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiffOp {
+    /// A byte found in both buffers.
+    Equal(u8),
+    /// A byte in the expected buffer replaced by a different byte in the actual buffer.
+    Changed(u8, u8),
+    /// A byte only found in the expected buffer.
+    Deleted(u8),
+    /// A byte only found in the actual buffer.
+    Inserted(u8),
+}
+
+impl DiffOp {
+    /// Gets the byte from the expected buffer (`None` for inserted bytes).
+    pub fn expected(self) -> Option<u8> {
+        match self {
+            DiffOp::Equal(byte) | DiffOp::Changed(byte, _) | DiffOp::Deleted(byte) => Some(byte),
+            DiffOp::Inserted(_) => None,
+        }
+    }
+
+    /// Gets the byte from the actual buffer (`None` for deleted bytes).
+    pub fn actual(self) -> Option<u8> {
+        match self {
+            DiffOp::Equal(byte) | DiffOp::Changed(_, byte) | DiffOp::Inserted(byte) => Some(byte),
+            DiffOp::Deleted(_) => None,
+        }
+    }
+
+    /// Gets the number of bits that differ (every bit of a deleted or inserted byte differs).
+    pub fn differing_bits(self) -> u32 {
+        match self {
+            DiffOp::Equal(_) => 0,
+            DiffOp::Changed(expected, actual) => (expected ^ actual).count_ones(),
+            DiffOp::Deleted(_) | DiffOp::Inserted(_) => 8,
+        }
+    }
+}
+
+/// Aligns two byte buffers, pairing up the bytes they share.
+///
+/// Bytes are aligned using their longest common subsequence. Runs of deleted bytes followed by
+/// inserted bytes are paired up as changed bytes so their bits can be compared. Buffers too large
+/// to align are compared position by position after removing any common prefix and suffix.
+///
+/// # Arguments
+///
+/// * `expected` - The expected bytes.
+/// * `actual` - The actual bytes.
+pub fn align(expected: &[u8], actual: &[u8]) -> Vec<DiffOp> {
+    // Common prefix and suffix
+    let prefix = expected.iter().zip(actual).take_while(|(a, b)| a == b).count();
+    let suffix = expected[prefix..]
+        .iter()
+        .rev()
+        .zip(actual[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut ops: Vec<DiffOp> = expected[..prefix].iter().map(|byte| DiffOp::Equal(*byte)).collect();
+    let expected_middle = &expected[prefix..expected.len() - suffix];
+    let actual_middle = &actual[prefix..actual.len() - suffix];
+    if expected_middle.len().saturating_mul(actual_middle.len()) > MAX_ALIGNMENT_CELLS {
+        ops.extend(pair(expected_middle, actual_middle));
+    } else {
+        ops.extend(align_subsequence(expected_middle, actual_middle));
+    }
+    ops.extend(expected[expected.len() - suffix..].iter().map(|byte| DiffOp::Equal(*byte)));
+    ops
+}
+
+/// Aligns two byte buffers using their longest common subsequence.
+///
+/// # Arguments
+///
+/// * `expected` - The expected bytes.
+/// * `actual` - The actual bytes.
+fn align_subsequence(expected: &[u8], actual: &[u8]) -> Vec<DiffOp> {
+    let (n, m) = (expected.len(), actual.len());
+    // lengths[i * (m + 1) + j] is the length of the longest common subsequence of expected[i..] and actual[j..]
+    let mut lengths = vec![0u32; (n + 1) * (m + 1)];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i * (m + 1) + j] = if expected[i] == actual[j] {
+                lengths[(i + 1) * (m + 1) + j + 1] + 1
+            } else {
+                lengths[(i + 1) * (m + 1) + j].max(lengths[i * (m + 1) + j + 1])
+            };
+        }
+    }
+
+    let mut ops = Vec::new();
+    let (mut deleted, mut inserted) = (Vec::new(), Vec::new());
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && expected[i] == actual[j] {
+            ops.extend(pair(&deleted, &inserted));
+            deleted.clear();
+            inserted.clear();
+            ops.push(DiffOp::Equal(expected[i]));
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lengths[(i + 1) * (m + 1) + j] >= lengths[i * (m + 1) + j + 1]) {
+            deleted.push(expected[i]);
+            i += 1;
+        } else {
+            inserted.push(actual[j]);
+            j += 1;
+        }
+    }
+    ops.extend(pair(&deleted, &inserted));
+    ops
+}
+
+/// Pairs up bytes position by position, listing any extra bytes as deleted or inserted.
+///
+/// # Arguments
+///
+/// * `expected` - The expected bytes.
+/// * `actual` - The actual bytes.
+fn pair(expected: &[u8], actual: &[u8]) -> Vec<DiffOp> {
+    let mut ops: Vec<DiffOp> = expected
+        .iter()
+        .zip(actual)
+        .map(|(a, b)| if a == b { DiffOp::Equal(*a) } else { DiffOp::Changed(*a, *b) })
+        .collect();
+    ops.extend(expected.iter().skip(actual.len()).map(|byte| DiffOp::Deleted(*byte)));
+    ops.extend(actual.iter().skip(expected.len()).map(|byte| DiffOp::Inserted(*byte)));
+    ops
+}
+
+/// A table comparing two byte buffers.
+///
+/// Each row shows the expected bytes (`-`) above the actual bytes (`+`), with a line beneath
+/// marking the bits that differ and any deleted or inserted bytes.
+pub struct ByteDiff<'a> {
+    /// The options of the table: its markup, notations (a line each for the expected and actual
+    /// bytes), row width, borders, theme, value styles and maximum width. Its bytes, layout,
+    /// interpretations, annotations and offset column are ignored.
+    pub table: ByteList<'static>,
+    /// The style of expected bits that differ and of deleted bytes.
+    pub deleted_style: Style,
+    /// The style of actual bits that differ and of inserted bytes.
+    pub inserted_style: Style,
+    /// The style of the markers beneath bits that differ.
+    pub differ_style: Style,
+    expected: &'a [u8],
+    actual: &'a [u8],
+}
+
+impl<'a> ByteDiff<'a> {
+    /// Creates a comparison of two byte buffers.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected` - The expected bytes.
+    /// * `actual` - The actual bytes.
+    pub fn from_bytes(expected: &'a [u8], actual: &'a [u8]) -> ByteDiff<'a> {
+        let mut table = ByteList::empty();
+        table.notations = vec![Notation::Binary];
+        ByteDiff {
+            table,
+            deleted_style: Style::new(Color::Red),
+            inserted_style: Style::new(Color::Green),
+            differ_style: Style::default(),
+            expected,
+            actual,
+        }
+    }
+
+    /// Aligns the expected and actual bytes.
+    pub fn ops(self: &ByteDiff<'a>) -> Vec<DiffOp> {
+        align(self.expected, self.actual)
+    }
+
+    /// Formats the comparison as a table followed by a summary.
+    pub fn format(self: &ByteDiff<'a>) -> String {
+        self.to_string()
+    }
+
+    /// Renders the comparison straight into a writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the comparison (e.g. a `String` or `fmt::Formatter`).
+    pub fn render_to<W: fmt::Write>(self: &ByteDiff<'a>, out: &mut W) -> fmt::Result {
+        let ops = self.ops();
+        // The row labels hold a sign and an offset
+        let max_len = self.expected.len().max(self.actual.len());
+        let list = self.table.for_rows(max_len.to_string().len() + 1);
+        // Narrower rows are used when the table is wider than the maximum width
+        let list = list.fitted();
+        let row_width = list.row_width.num_bytes();
+        let label_width = list.label_width();
+        // The wall is painted once for the whole table
+        let mut storage = [0; WALL_CAPACITY];
+        let mut wall = FixedBuffer::new(&mut storage);
+        write!(wall, "{}", list.wall())?;
+        let wall = wall.as_str();
+
+        out.write_str(list.markup.prefix())?;
+        list.write_table_header(out)?;
+        let mut walls_above = list.header_walls();
+        let (mut expected_offset, mut actual_offset) = (0, 0);
+        for row in ops.chunks(row_width) {
+            let walls_below = list.walls(1..=row.len());
+            list.write_rule(out, &walls_above, &walls_below)?;
+            for (sign, offset) in [('-', expected_offset), ('+', actual_offset)] {
+                // A line per notation, with the offset on the first line
+                for line in 0..list.notations.len().max(1) {
+                    let label = match line {
+                        0 => format!("{}{:>2$}", sign, offset, label_width - 1),
+                        _ => String::new(),
+                    };
+                    write!(out, "{0}{1:<2$}{0}", wall, label.paint(list.theme.dword_title, list.markup), label_width)?;
+                    for op in row {
+                        match list.notations.get(line) {
+                            Some(notation) => self.write_cell(&list, out, *notation, *op, sign == '-')?,
+                            None => write!(out, "{:8}", "")?,
+                        }
+                        write!(out, "{}", wall)?;
+                    }
+                    out.write_char('\n')?;
+                }
+            }
+            if row.iter().any(|op| !matches!(op, DiffOp::Equal(_))) {
+                let notation = list.notations.first().copied().unwrap_or(Notation::Binary);
+                write!(out, "{0}{1:2$}{0}", wall, "", label_width)?;
+                for op in row {
+                    self.write_marker(&list, out, notation, *op)?;
+                    write!(out, "{}", wall)?;
+                }
+                out.write_char('\n')?;
+            }
+            walls_above = walls_below;
+            expected_offset += row.iter().filter(|op| op.expected().is_some()).count();
+            actual_offset += row.iter().filter(|op| op.actual().is_some()).count();
+        }
+        list.write_rule(out, &walls_above, &[])?;
+        writeln!(out, "{}", summarise(&ops).paint(list.theme.notes, list.markup))?;
+        out.write_str(list.markup.suffix())
+    }
+
+    /// Exports the comparison as a JSON object listing each aligned byte.
+    pub fn to_json(self: &ByteDiff<'a>) -> String {
+        let ops = self.ops();
+        let byte = |byte: Option<u8>| byte.map_or(String::from("null"), |byte| byte.to_string());
+        let entries: Vec<String> = ops
+            .iter()
+            .map(|op| {
+                format!(
+                    "{{\"op\":{},\"expected\":{},\"actual\":{},\"differing_bits\":{}}}",
+                    json::string(match op {
+                        DiffOp::Equal(_) => "equal",
+                        DiffOp::Changed(_, _) => "changed",
+                        DiffOp::Deleted(_) => "deleted",
+                        DiffOp::Inserted(_) => "inserted",
+                    }),
+                    byte(op.expected()),
+                    byte(op.actual()),
+                    op.differing_bits()
+                )
+            })
+            .collect();
+        format!(
+            "{{\"differing_bits\":{},\"ops\":[{}]}}",
+            ops.iter().map(|op| op.differing_bits()).sum::<u32>(),
+            entries.join(",")
+        )
+    }
+
+    /// Formats one side of an aligned byte in a cell, painting the bits that differ.
+    ///
+    /// Binary cells paint each bit that differs, while other notations paint the whole cell when
+    /// the bytes differ. Bytes that match take the style of the cell.
+    ///
+    /// # Arguments
+    ///
+    /// * `list` - The table holding the cell.
+    /// * `out` - The writer receiving the cell.
+    /// * `notation` - The notation used to show the byte.
+    /// * `op` - The aligned byte.
+    /// * `expected` - Whether to format the expected side (rather than the actual side).
+    fn write_cell<W: fmt::Write>(self: &ByteDiff<'a>, list: &ByteList, out: &mut W, notation: Notation, op: DiffOp, expected: bool) -> fmt::Result {
+        let (byte, other, style) = if expected {
+            (op.expected(), op.actual(), self.deleted_style)
+        } else {
+            (op.actual(), op.expected(), self.inserted_style)
+        };
+        let byte = match byte {
+            Some(byte) => byte,
+            None => return write!(out, "{:8}", ""),
+        };
+        let mut storage = [0; CELL_WIDTH];
+        let mut text = FixedBuffer::new(&mut storage);
+        notation.format_byte_to(&mut text, byte)?;
+        let text = text.as_str();
+        let cell_style = list.cell_style(notation, byte);
+        match (other, notation) {
+            (Some(other), _) if other == byte => write!(out, "{:>8}", text.paint(cell_style, list.markup)),
+            (Some(other), Notation::Binary) => {
+                // Paint each run of bits that differ (or match)
+                let mut run_start = 0;
+                for bit in 1..=text.len() {
+                    let differs = |bit: usize| (byte ^ other) & (0b1000_0000 >> bit) != 0;
+                    if bit == text.len() || differs(bit) != differs(run_start) {
+                        let run_style = if differs(run_start) { style } else { cell_style };
+                        list.write_run(out, &text[run_start..bit], run_style)?;
+                        run_start = bit;
+                    }
+                }
+                Ok(())
+            }
+            _ => write!(out, "{:>8}", text.paint(style, list.markup)),
+        }
+    }
+
+    /// Formats the marker beneath an aligned byte (`^` beneath bits that differ).
+    ///
+    /// # Arguments
+    ///
+    /// * `list` - The table holding the marker.
+    /// * `out` - The writer receiving the marker.
+    /// * `notation` - The notation of the cells above the marker.
+    /// * `op` - The aligned byte.
+    fn write_marker<W: fmt::Write>(self: &ByteDiff<'a>, list: &ByteList, out: &mut W, notation: Notation, op: DiffOp) -> fmt::Result {
+        match op {
+            DiffOp::Equal(_) => write!(out, "{:8}", ""),
+            DiffOp::Changed(expected, actual) => {
+                let marker = match notation {
+                    Notation::Binary => format!("{:08b}", expected ^ actual).replace('0', " ").replace('1', "^"),
+                    _ => "^".repeat(notation.format_byte(expected).len()),
+                };
+                write!(out, "{:>8}", marker.paint(self.differ_style, list.markup))
+            }
+            DiffOp::Deleted(_) => write!(out, "{:^8}", "deleted".paint(self.deleted_style, list.markup)),
+            DiffOp::Inserted(_) => write!(out, "{:^8}", "inserted".paint(self.inserted_style, list.markup)),
+        }
+    }
+}
+
+impl fmt::Display for ByteDiff<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render_to(f)
+    }
+}
+
+/// Summarises the differences between two aligned buffers.
+///
+/// # Arguments
+///
+/// * `ops` - The aligned bytes.
+fn summarise(ops: &[DiffOp]) -> String {
+    let count = |matches: fn(&DiffOp) -> bool| ops.iter().filter(|op| matches(op)).count();
+    let changed = count(|op| matches!(op, DiffOp::Changed(_, _)));
+    let deleted = count(|op| matches!(op, DiffOp::Deleted(_)));
+    let inserted = count(|op| matches!(op, DiffOp::Inserted(_)));
+    if changed + deleted + inserted == 0 {
+        return String::from("No differences");
+    }
+    format!(
+        "{} ({} changed, {} deleted, {} inserted), {}",
+        differ(changed + deleted + inserted, "byte"),
+        changed,
+        deleted,
+        inserted,
+        differ(ops.iter().map(|op| op.differing_bits() as usize).sum(), "bit")
+    )
+}
+
+/// States how many things differ (e.g. `1 bit differs` or `2 bits differ`).
+///
+/// # Arguments
+///
+/// * `count` - The number of things that differ.
+/// * `noun` - The name of one thing.
+pub(crate) fn differ(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("1 {} differs", noun),
+        _ => format!("{} {}s differ", count, noun),
+    }
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::border::BorderStyle;
+    use crate::byte_list::RowWidth;
+    use crate::markup::Markup;
+
+    #[test]
+    fn test_align() {
+        use DiffOp::*;
+
+        assert_eq!(vec![Equal(1), Changed(2, 5), Equal(3)], align(&[1, 2, 3], &[1, 5, 3]));
+        assert_eq!(vec![Equal(1), Inserted(9), Equal(2), Equal(3)], align(&[1, 2, 3], &[1, 9, 2, 3]));
+        assert_eq!(vec![Equal(1), Deleted(2), Equal(3), Equal(4)], align(&[1, 2, 3, 4], &[1, 3, 4]));
+        assert_eq!(vec![Changed(1, 7), Equal(2), Changed(3, 8), Inserted(9)], align(&[1, 2, 3], &[7, 2, 8, 9]));
+        assert_eq!(Vec::<DiffOp>::new(), align(&[], &[]));
+    }
+
+    #[test]
+    fn test_format() {
+        let expected = vec![0b1000_0001, 2, 3, 4];
+        let actual = vec![0b1000_0010, 2, 4, 5];
+        let mut diff = ByteDiff::from_bytes(&expected, &actual);
+        diff.table.markup = Markup::Plain;
+        diff.table.row_width = RowWidth::Word;
+
+        let formatted = "       +--------+--------+\n Bytes | Byte 0 | Byte 1 |\n+------+--------+--------+\n|-    0|10000001|00000010|\n|+    0|10000010|00000010|\n|      |      ^^|        |\n+------+--------+--------+\n|-    2|00000011|00000100|\n|+    2|        |00000100|\n|      |deleted |        |\n+------+--------+--------+\n|-    4|        |\n|+    3|00000101|\n|      |inserted|\n+------+--------+\n3 bytes differ (1 changed, 1 deleted, 1 inserted), 18 bits differ\n";
+
+        assert_eq!(formatted, diff.format());
+    }
+
+    #[test]
+    fn test_format_html_colours_differing_bits() {
+        let expected = vec![0b1000_0001];
+        let actual = vec![0b1000_0011];
+        let mut diff = ByteDiff::from_bytes(&expected, &actual);
+        diff.table.markup = Markup::Html;
+
+        let formatted = diff.format();
+
+        assert!(formatted.contains("|-    0|100000<span style=\"color:#cd0000\">0</span>1|\n"));
+        assert!(formatted.contains("|+    0|100000<span style=\"color:#00cd00\">1</span>1|\n"));
+        assert!(formatted.ends_with("1 byte differs (1 changed, 0 deleted, 0 inserted), 1 bit differs\n</pre>\n"));
+    }
+
+    #[test]
+    fn test_format_table_options() {
+        let expected = vec![0x0f, 2, 3];
+        let actual = vec![0x0e, 2, 3];
+        let mut diff = ByteDiff::from_bytes(&expected, &actual);
+        diff.table.markup = Markup::Plain;
+        diff.table.notations = vec![Notation::Hex, Notation::Ascii];
+        diff.table.border_style = BorderStyle::Light;
+        diff.table.row_width = RowWidth::Qword;
+        // Only two bytes per row fit
+        diff.table.max_width = Some(30);
+
+        let formatted = "       ┌────────┬────────┐\n Bytes │ Byte 0 │ Byte 1 │\n┌──────┼────────┼────────┤\n│-    0│    0x0f│    0x02│\n│      │       .│       .│\n│+    0│    0x0e│    0x02│\n│      │       .│       .│\n│      │    ^^^^│        │\n├──────┼────────┼────────┘\n│-    2│    0x03│\n│      │       .│\n│+    2│    0x03│\n│      │       .│\n└──────┴────────┘\n1 byte differs (1 changed, 0 deleted, 0 inserted), 1 bit differs\n";
+
+        assert_eq!(formatted, diff.format());
+        let mut rendered = String::new();
+        diff.render_to(&mut rendered).unwrap();
+        assert_eq!(formatted, rendered);
+    }
+
+    #[test]
+    fn test_to_json() {
+        let diff = ByteDiff::from_bytes(&[1, 2], &[1]);
+
+        let expected = "{\"differing_bits\":8,\"ops\":[{\"op\":\"equal\",\"expected\":1,\"actual\":1,\"differing_bits\":0},{\"op\":\"deleted\",\"expected\":2,\"actual\":null,\"differing_bits\":8}]}";
+
+        assert_eq!(expected, diff.to_json());
+    }
+}
+
+// #endregion Unit tests
@@ -0,0 +1,343 @@
+use crate::color::Color;
+use super::{align, differ};
+use crate::json;
+use crate::markup::{Markup, Paint};
+use crate::theme::Theme;
+use crate::websocket_frame::{FrameError, FrameField, WebSocketFrame};
+use crate::prelude::*;
+
+/// The comparison of a field found in either of two WebSocket frames.
+#[derive(Debug, PartialEq)]
+pub struct FieldDiff {
+    /// The name of the field.
+    pub name: String,
+    /// The value of the field in the expected frame, unmasked for payload bytes (`None` if the
+    /// frame has no such field).
+    pub expected: Option<u64>,
+    /// The value of the field in the actual frame, unmasked for payload bytes (`None` if the frame
+    /// has no such field).
+    pub actual: Option<u64>,
+    /// The number of bits that differ (every bit differs when the field is missing or resized).
+    pub differing_bits: u32,
+}
+
+/// A comparison of two WebSocket frames.
+///
+/// Both frame diagrams are drawn with the bits that differ highlighted, followed by a table of the
+/// fields that differ.
+pub struct FrameDiff<'a> {
+    pub markup: Markup,
+    /// The colour of expected values that differ.
+    pub expected_color: Color,
+    /// The colour of actual values that differ.
+    pub actual_color: Color,
+    /// The theme used to draw both frame diagrams.
+    pub theme: Theme,
+    expected: &'a [u8],
+    actual: &'a [u8],
+}
+
+impl<'a> FrameDiff<'a> {
+    /// Creates a comparison of two WebSocket frames, failing if either is incomplete.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected` - The bytes of the expected frame.
+    /// * `actual` - The bytes of the actual frame.
+    pub fn from_bytes(expected: &'a [u8], actual: &'a [u8]) -> Result<FrameDiff<'a>, FrameError> {
+        WebSocketFrame::try_from_bytes(expected)?;
+        WebSocketFrame::try_from_bytes(actual)?;
+        Ok(FrameDiff {
+            markup: Markup::default(),
+            expected_color: Color::Red,
+            actual_color: Color::Green,
+            theme: Theme::default(),
+            expected,
+            actual,
+        })
+    }
+
+    /// Compares each field found in either frame: the header fields in the order they appear, then
+    /// the payload bytes, aligned so that an inserted or deleted byte doesn't shift the bytes after
+    /// it. Payload bytes are compared once unmasked, so frames masked with different keys only
+    /// differ where their payloads do.
+    pub fn field_diffs(self: &FrameDiff<'a>) -> Vec<FieldDiff> {
+        let (expected_fields, actual_fields) = self.fields();
+        field_pairs(&expected_fields, &actual_fields)
+            .into_iter()
+            .map(|(expected, actual)| FieldDiff {
+                name: expected.or(actual).map(|field| field.name.clone()).unwrap_or_default(),
+                expected: expected.map(compared_value),
+                actual: actual.map(compared_value),
+                differing_bits: match (expected, actual) {
+                    (Some(a), Some(b)) if a.bit_len == b.bit_len => (compared_value(a) ^ compared_value(b)).count_ones(),
+                    _ => expected.or(actual).map_or(0, |field| field.bit_len as u32),
+                },
+            })
+            .collect()
+    }
+
+    /// Formats both frame diagrams with the differing bits highlighted, then the fields that differ.
+    pub fn format(self: &FrameDiff<'a>) -> String {
+        let (expected_fields, actual_fields) = self.fields();
+        let mut expected = self.frame(self.expected);
+        let mut actual = self.frame(self.actual);
+        for (expected_field, actual_field) in field_pairs(&expected_fields, &actual_fields) {
+            let (expected_bits, actual_bits) = differing_bits(expected_field, actual_field);
+            expected.highlighted_bits.extend(expected_bits);
+            actual.highlighted_bits.extend(actual_bits);
+        }
+
+        let mut result = String::from("Expected\n");
+        expected.render_diagram(&mut result).expect("Writing to a string cannot fail.");
+        result.push_str("\nActual\n");
+        actual.render_diagram(&mut result).expect("Writing to a string cannot fail.");
+        result.push('\n');
+        result.push_str(&self.format_fields());
+        self.markup.wrap(result)
+    }
+
+    /// Exports the comparison as a JSON object listing each field.
+    pub fn to_json(self: &FrameDiff<'a>) -> String {
+        let value = |value: Option<u64>| value.map_or(String::from("null"), |value| value.to_string());
+        let diffs = self.field_diffs();
+        let fields: Vec<String> = diffs
+            .iter()
+            .map(|diff| {
+                format!(
+                    "{{\"name\":{},\"expected\":{},\"actual\":{},\"differing_bits\":{}}}",
+                    json::string(&diff.name),
+                    value(diff.expected),
+                    value(diff.actual),
+                    diff.differing_bits
+                )
+            })
+            .collect();
+        format!(
+            "{{\"differing_bits\":{},\"fields\":[{}]}}",
+            diffs.iter().map(|diff| diff.differing_bits).sum::<u32>(),
+            fields.join(",")
+        )
+    }
+
+    /// Formats a table of the fields that differ, followed by a summary.
+    fn format_fields(self: &FrameDiff<'a>) -> String {
+        let diffs: Vec<FieldDiff> = self.field_diffs().into_iter().filter(|diff| diff.differing_bits > 0).collect();
+        if diffs.is_empty() {
+            return String::from("No differences\n");
+        }
+        let value = |value: Option<u64>| value.map_or(String::from("-"), |value| value.to_string());
+        let name_width = diffs.iter().map(|diff| diff.name.len()).max().unwrap_or(0).max(5);
+        let value_width = diffs
+            .iter()
+            .flat_map(|diff| vec![value(diff.expected).len(), value(diff.actual).len()])
+            .max()
+            .unwrap_or(0)
+            .max(8);
+        let border = format!("+{}+{}+{}+------+\n", "-".repeat(name_width + 2), "-".repeat(value_width + 2), "-".repeat(value_width + 2));
+
+        let mut result = border.clone();
+        result.push_str(&format!("| {:<3$} | {:>4$} | {:>4$} | Bits |\n", "Field", "Expected", "Actual", name_width, value_width));
+        result.push_str(&border);
+        for diff in &diffs {
+            result.push_str(&format!(
+                "| {name:<name_width$} | {expected:>value_width$} | {actual:>value_width$} | {bits:>4} |\n",
+                name = diff.name,
+                expected = value(diff.expected).paint(self.expected_color, self.markup),
+                actual = value(diff.actual).paint(self.actual_color, self.markup),
+                bits = diff.differing_bits,
+            ));
+        }
+        result.push_str(&border);
+        result.push_str(&format!(
+            "{}, {}\n",
+            differ(diffs.len(), "field"),
+            differ(diffs.iter().map(|diff| diff.differing_bits as usize).sum(), "bit")
+        ));
+        result
+    }
+
+    /// Parses a frame for formatting.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The bytes of the frame.
+    fn frame(self: &FrameDiff<'a>, data: &'a [u8]) -> WebSocketFrame<'a> {
+        let mut frame = WebSocketFrame::from_bytes(data);
+        frame.markup = self.markup;
+        frame.theme = self.theme.clone();
+        frame
+    }
+
+    /// Lists the fields of the expected and actual frames.
+    fn fields(self: &FrameDiff<'a>) -> (Vec<FrameField>, Vec<FrameField>) {
+        (
+            WebSocketFrame::from_bytes(self.expected).fields(),
+            WebSocketFrame::from_bytes(self.actual).fields(),
+        )
+    }
+}
+
+/// Gets the value of a field as compared: the unmasked value of a payload byte, or the bits of any
+/// other field.
+///
+/// # Arguments
+///
+/// * `field` - The field.
+fn compared_value(field: &FrameField) -> u64 {
+    field.unmasked_value.map_or(field.value, u64::from)
+}
+
+/// Pairs up the header fields with the same name (listing the expected frame's fields first), then
+/// the payload bytes as aligned by their unmasked values.
+///
+/// # Arguments
+///
+/// * `expected` - The fields of the expected frame.
+/// * `actual` - The fields of the actual frame.
+fn field_pairs<'f>(expected: &'f [FrameField], actual: &'f [FrameField]) -> Vec<(Option<&'f FrameField>, Option<&'f FrameField>)> {
+    let (expected_payload, expected_header): (Vec<&FrameField>, Vec<&FrameField>) = expected.iter().partition(|field| field.unmasked_value.is_some());
+    let (actual_payload, actual_header): (Vec<&FrameField>, Vec<&FrameField>) = actual.iter().partition(|field| field.unmasked_value.is_some());
+
+    let mut pairs: Vec<(Option<&FrameField>, Option<&FrameField>)> = expected_header
+        .iter()
+        .map(|field| (Some(*field), actual_header.iter().find(|other| other.name == field.name).copied()))
+        .collect();
+    pairs.extend(
+        actual_header
+            .iter()
+            .filter(|field| !expected_header.iter().any(|other| other.name == field.name))
+            .map(|field| (None, Some(*field))),
+    );
+
+    let unmasked = |fields: &[&FrameField]| -> Vec<u8> { fields.iter().filter_map(|field| field.unmasked_value).collect() };
+    let (mut expected_payload_fields, mut actual_payload_fields) = (expected_payload.iter(), actual_payload.iter());
+    for op in align(&unmasked(&expected_payload), &unmasked(&actual_payload)) {
+        pairs.push((
+            op.expected().and_then(|_| expected_payload_fields.next().copied()),
+            op.actual().and_then(|_| actual_payload_fields.next().copied()),
+        ));
+    }
+    pairs
+}
+
+/// Lists the bits of a pair of fields that differ, as offsets into each frame.
+///
+/// # Arguments
+///
+/// * `expected` - The field in the expected frame.
+/// * `actual` - The field in the actual frame.
+fn differing_bits(expected: Option<&FrameField>, actual: Option<&FrameField>) -> (Vec<usize>, Vec<usize>) {
+    let all_bits = |field: Option<&FrameField>| field.map_or(Vec::new(), |field| (field.bit_offset..field.bit_offset + field.bit_len).collect());
+    match (expected, actual) {
+        (Some(a), Some(b)) if a.bit_len == b.bit_len => {
+            let differences = compared_value(a) ^ compared_value(b);
+            let differing: Vec<usize> = (0..a.bit_len).filter(|i| (differences >> (a.bit_len - 1 - i)) & 1 == 1).collect();
+            (
+                differing.iter().map(|i| a.bit_offset + i).collect(),
+                differing.iter().map(|i| b.bit_offset + i).collect(),
+            )
+        }
+        _ => (all_bits(expected), all_bits(actual)),
+    }
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_diffs() {
+        // "Hello" and "Hellp" as masked text frames with the same masking key
+        let expected = [0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58];
+        let actual = [0x82, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x47];
+
+        let diffs: Vec<FieldDiff> = FrameDiff::from_bytes(&expected, &actual)
+            .unwrap()
+            .field_diffs()
+            .into_iter()
+            .filter(|diff| diff.differing_bits > 0)
+            .collect();
+
+        assert_eq!(
+            vec![
+                FieldDiff { name: String::from("Opcode"), expected: Some(1), actual: Some(2), differing_bits: 2 },
+                FieldDiff { name: String::from("Payload byte 4"), expected: Some(u64::from(b'o')), actual: Some(u64::from(b'p')), differing_bits: 5 },
+            ],
+            diffs
+        );
+    }
+
+    #[test]
+    fn test_different_masking_keys() {
+        // "hello" masked with two different keys
+        let key_a = [0x37, 0xfa, 0x21, 0x3d];
+        let key_b = [0x01, 0x02, 0x03, 0x04];
+        let masked = |key: [u8; 4]| -> Vec<u8> {
+            let mut frame = vec![0x81, 0x85];
+            frame.extend_from_slice(&key);
+            frame.extend(b"hello".iter().enumerate().map(|(i, byte)| byte ^ key[i % 4]));
+            frame
+        };
+        let (expected, actual) = (masked(key_a), masked(key_b));
+
+        let diffs = FrameDiff::from_bytes(&expected, &actual).unwrap().field_diffs();
+
+        let differing: Vec<&str> = diffs.iter().filter(|diff| diff.differing_bits > 0).map(|diff| diff.name.as_str()).collect();
+        assert_eq!(vec!["Masking-key"], differing);
+    }
+
+    #[test]
+    fn test_inserted_payload_byte() {
+        // "hello" and "hXello" (unmasked, so the payload length differs too)
+        let expected = [0x81, 0x05, b'h', b'e', b'l', b'l', b'o'];
+        let actual = [0x81, 0x06, b'h', b'X', b'e', b'l', b'l', b'o'];
+
+        let diffs = FrameDiff::from_bytes(&expected, &actual).unwrap().field_diffs();
+
+        let differing: Vec<&FieldDiff> = diffs.iter().filter(|diff| diff.differing_bits > 0).collect();
+        assert_eq!(2, differing.len());
+        assert_eq!("Payload length", differing[0].name);
+        assert_eq!(FieldDiff { name: String::from("Payload byte 1"), expected: None, actual: Some(u64::from(b'X')), differing_bits: 8 }, *differing[1]);
+    }
+
+    #[test]
+    fn test_missing_fields() {
+        let diff = FrameDiff::from_bytes(&[0x81, 0x01, 0x41], &[0x81, 0x00]).unwrap();
+
+        let diffs = diff.field_diffs();
+
+        assert_eq!(FieldDiff { name: String::from("Payload byte 0"), expected: Some(0x41), actual: None, differing_bits: 8 }, diffs[diffs.len() - 1]);
+        assert!(diff.to_json().starts_with("{\"differing_bits\":9,"));
+    }
+
+    #[test]
+    fn test_format() {
+        let expected = [0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58];
+        let actual = [0x82, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x47];
+        let mut diff = FrameDiff::from_bytes(&expected, &actual).unwrap();
+        diff.markup = Markup::Plain;
+
+        let formatted = diff.format();
+
+        assert!(formatted.starts_with("Expected\n"));
+        assert!(formatted.contains("\nActual\n"));
+        assert!(formatted.ends_with("| Opcode         |        1 |        2 |    2 |\n| Payload byte 4 |      111 |      112 |    5 |\n+----------------+----------+----------+------+\n2 fields differ, 7 bits differ\n"));
+        assert!(FrameDiff::from_bytes(&expected, &[0x81]).is_err());
+    }
+
+    #[test]
+    fn test_format_highlights_differing_bits() {
+        let mut diff = FrameDiff::from_bytes(&[0x81, 0x02, 0x41, 0x42], &[0x82, 0x02, 0x41, 0x42]).unwrap();
+        diff.markup = Markup::Html;
+
+        let formatted = diff.format();
+
+        // Bits 6 and 7 (the last two opcode bits) differ
+        assert!(formatted.contains("<span style=\"color:#e5e5e5\">0</span> <span style=\"color:#e5e5e5\">0</span> <span style=\"color:#ff0000\">0</span> <span style=\"color:#ff0000\">1</span>"));
+    }
+}
+
+// #endregion Unit tests
@@ -0,0 +1,149 @@
+use crate::prelude::*;
+
+/// A table of plain text for documentation, rendered as a GitHub-flavoured Markdown table or a
+/// reStructuredText grid table. Each cell may hold several lines.
+pub(crate) struct DocTable {
+    header: Vec<String>,
+    rows: Vec<Vec<Vec<String>>>,
+}
+
+impl DocTable {
+    /// Creates a table without any rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `header` - The title of each column.
+    pub(crate) fn new(header: Vec<String>) -> DocTable {
+        DocTable { header, rows: Vec::new() }
+    }
+
+    /// Appends a row, filling any missing cells with blanks.
+    ///
+    /// # Arguments
+    ///
+    /// * `cells` - The lines of each cell.
+    pub(crate) fn push_row(&mut self, mut cells: Vec<Vec<String>>) {
+        cells.resize(self.header.len(), Vec::new());
+        self.rows.push(cells);
+    }
+
+    /// Renders the table in GitHub-flavoured Markdown, joining the lines of each cell with `<br>`.
+    ///
+    /// Columns are padded to a fixed width, so the source lines up as well as the rendered table.
+    pub(crate) fn to_markdown(&self) -> String {
+        let escape = |text: &str| text.replace('|', "\\|");
+        let header: Vec<String> = self.header.iter().map(|title| escape(title)).collect();
+        let rows: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|lines| escape(&lines.join("<br>"))).collect())
+            .collect();
+        // The delimiter row needs at least three dashes in each column
+        let widths = column_widths(core::iter::once(&header).chain(&rows), 3);
+
+        let mut result = padded_row(&header, &widths);
+        let delimiters: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+        result.push_str(&padded_row(&delimiters, &widths));
+        for row in &rows {
+            result.push_str(&padded_row(row, &widths));
+        }
+        result
+    }
+
+    /// Renders the table as a reStructuredText grid table.
+    pub(crate) fn to_rst(&self) -> String {
+        // Each line of a cell is padded to the width of its column
+        let lines: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .flat_map(|row| {
+                (0..num_lines(row)).map(move |line| row.iter().map(|lines| lines.get(line).cloned().unwrap_or_default()).collect())
+            })
+            .collect();
+        let widths = column_widths(core::iter::once(&self.header).chain(&lines), 1);
+
+        let rule = |fill: char| {
+            let cells: Vec<String> = widths.iter().map(|width| fill.to_string().repeat(width + 2)).collect();
+            format!("+{}+\n", cells.join("+"))
+        };
+        let mut result = rule('-');
+        result.push_str(&padded_row(&self.header, &widths));
+        result.push_str(&rule('='));
+        let mut lines = lines.iter();
+        for row in &self.rows {
+            for line in lines.by_ref().take(num_lines(row)) {
+                result.push_str(&padded_row(line, &widths));
+            }
+            result.push_str(&rule('-'));
+        }
+        result
+    }
+}
+
+/// Gets the number of characters in the widest cell of each column.
+///
+/// # Arguments
+///
+/// * `rows` - The text of each cell.
+/// * `min_width` - The narrowest a column may be.
+fn column_widths<'r, I: IntoIterator<Item = &'r Vec<String>>>(rows: I, min_width: usize) -> Vec<usize> {
+    let mut widths: Vec<usize> = Vec::new();
+    for row in rows {
+        widths.resize(widths.len().max(row.len()), min_width);
+        for (width, text) in widths.iter_mut().zip(row) {
+            *width = (*width).max(text.chars().count());
+        }
+    }
+    widths
+}
+
+/// Gets the number of lines in a row (at least one, so that blank rows are kept).
+///
+/// # Arguments
+///
+/// * `row` - The lines of each cell.
+fn num_lines(row: &[Vec<String>]) -> usize {
+    row.iter().map(Vec::len).max().unwrap_or(0).max(1)
+}
+
+/// Formats a line of a table with the cells padded to the width of their columns and separated by
+/// walls (a row of a Markdown table, or a line of a grid table).
+///
+/// # Arguments
+///
+/// * `cells` - The text of each cell.
+/// * `widths` - The width of each column.
+fn padded_row(cells: &[String], widths: &[usize]) -> String {
+    let padded: Vec<String> = cells.iter().zip(widths).map(|(text, width)| format!("{:1$}", text, width)).collect();
+    format!("| {} |\n", padded.join(" | "))
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> DocTable {
+        let mut table = DocTable::new(vec![String::from("Row"), String::from("Byte 0")]);
+        table.push_row(vec![vec![String::from("WORD 1")], vec![String::from("0x7c"), String::from("'|'")]]);
+        table.push_row(vec![vec![String::from("WORD 2")]]);
+        table
+    }
+
+    #[test]
+    fn test_markdown() {
+        let expected = "| Row    | Byte 0       |\n| ------ | ------------ |\n| WORD 1 | 0x7c<br>'\\|' |\n| WORD 2 |              |\n";
+
+        assert_eq!(expected, table().to_markdown());
+    }
+
+    #[test]
+    fn test_rst() {
+        let expected = "+--------+--------+\n| Row    | Byte 0 |\n+========+========+\n| WORD 1 | 0x7c   |\n|        | '|'    |\n+--------+--------+\n| WORD 2 |        |\n+--------+--------+\n";
+
+        assert_eq!(expected, table().to_rst());
+    }
+}
+
+// #endregion Unit tests
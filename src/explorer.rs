@@ -0,0 +1,502 @@
+use std::io::{self, Write};
+
+use bitformat::{BitNumbering, ByteList, FrameField, Markup, RowWidth, WebSocketFrame};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Print, PrintStyledContent, ResetColor, Stylize};
+use crossterm::terminal::{self, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{cursor, execute, queue};
+
+/// The position of a bit within the formatted lines of a page.
+#[derive(Debug, PartialEq)]
+struct Cell {
+    line: usize,
+    column: usize,
+}
+
+/// A formatted table that can be explored one bit at a time.
+pub struct Page {
+    /// The title shown above the table.
+    title: String,
+    /// The formatted lines of the table (as displayed).
+    lines: Vec<String>,
+    /// The formatted lines of the table without markup (used to locate bits).
+    plain_lines: Vec<String>,
+    /// The fields that the bits belong to.
+    fields: Vec<FrameField>,
+    /// The positions of the bits, in the order they appear in the data.
+    cells: Vec<Cell>,
+    /// Whether the payload is masked (so unmasked payload bits are drawn beneath the masked bits).
+    masked: bool,
+    /// How bits are numbered within their bytes in the status line.
+    bit_numbering: BitNumbering,
+}
+
+impl Page {
+    /// Builds a page exploring a WebSocket data frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - The title shown above the frame.
+    /// * `frame` - The frame to explore.
+    /// * `markup` - The markup used to display the frame.
+    pub fn from_frame(title: String, mut frame: WebSocketFrame, markup: Markup) -> Page {
+        frame.markup = Markup::Plain;
+        let plain = frame.format();
+        frame.markup = markup;
+        let formatted = frame.format();
+        Page::new(title, &formatted, &plain, "DWORD", frame.fields(), frame.bit_numbering, frame.is_payload_masked)
+    }
+
+    /// Builds a page exploring a list of bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `title` - The title shown above the table.
+    /// * `data` - The bytes to explore.
+    /// * `markup` - The markup used to display the bytes.
+    /// * `row_width` - The number of bytes shown on each row.
+    /// * `bit_numbering` - How the bits are numbered (in tick marks above the bytes), if at all.
+    pub fn from_bytes(title: String, data: &[u8], markup: Markup, row_width: RowWidth, bit_numbering: Option<BitNumbering>) -> Page {
+        let mut table = ByteList::from_bytes(data);
+        table.row_width = row_width;
+        table.bit_numbering = bit_numbering;
+        table.markup = Markup::Plain;
+        let plain = table.format();
+        table.markup = markup;
+        let formatted = table.format();
+        let fields = data
+            .iter()
+            .enumerate()
+            .map(|(i, byte)| FrameField {
+                name: format!("Byte {}", i),
+                bit_offset: i * 8,
+                bit_len: 8,
+                value: (*byte).into(),
+                unmasked_value: None,
+            })
+            .collect();
+        Page::new(title, &formatted, &plain, row_width.label(), fields, bit_numbering.unwrap_or_default(), false)
+    }
+
+    fn new(
+        title: String,
+        formatted: &str,
+        plain: &str,
+        row_label: &str,
+        fields: Vec<FrameField>,
+        bit_numbering: BitNumbering,
+        masked: bool,
+    ) -> Page {
+        let plain_lines: Vec<String> = plain.lines().map(String::from).collect();
+        let cells = find_bit_cells(&plain_lines, row_label);
+        Page {
+            title,
+            lines: formatted.lines().map(String::from).collect(),
+            plain_lines,
+            fields,
+            cells,
+            bit_numbering,
+            masked,
+        }
+    }
+
+    /// Finds the field containing a bit.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit` - The offset of the bit from the start of the data.
+    fn field_at(&self, bit: usize) -> Option<&FrameField> {
+        self.fields.iter().find(|field| bit >= field.bit_offset && bit < field.bit_offset + field.bit_len)
+    }
+
+    /// Gets the position at which a bit is displayed.
+    ///
+    /// Unmasked payload bits are drawn two lines beneath their masked counterparts. Unmasked frames
+    /// only draw their payload bits once.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit` - The offset of the bit from the start of the data.
+    /// * `unmasked` - Whether the unmasked view is selected.
+    fn position(&self, bit: usize, unmasked: bool) -> Option<(usize, usize)> {
+        let cell = self.cells.get(bit)?;
+        let is_payload = self.field_at(bit).is_some_and(|field| field.unmasked_value.is_some());
+        if unmasked && is_payload && self.masked {
+            Some((cell.line + 2, cell.column))
+        } else {
+            Some((cell.line, cell.column))
+        }
+    }
+
+    /// Gets the character displayed at a position (without markup).
+    fn char_at(&self, line: usize, column: usize) -> char {
+        self.plain_lines
+            .get(line)
+            .and_then(|text| text.chars().nth(column))
+            .unwrap_or(' ')
+    }
+
+    /// Describes the field containing a bit for the status line.
+    ///
+    /// # Arguments
+    ///
+    /// * `bit` - The offset of the bit from the start of the data.
+    /// * `unmasked` - Whether the unmasked view is selected.
+    fn describe(&self, bit: usize, unmasked: bool) -> String {
+        let location = format!("bit {} (byte {}, bit {})", bit, bit / 8, self.bit_numbering.number_in_byte(bit % 8));
+        let field = match self.field_at(bit) {
+            Some(field) => field,
+            None => return location,
+        };
+        let value = match field.unmasked_value {
+            Some(byte) if unmasked => format!("{:#04x} ({}) '{}' unmasked", byte, byte, printable(byte)),
+            Some(_) => format!("{:#04x} ({}) masked", field.value, field.value),
+            None => format!("{:#x} ({})", field.value, field.value),
+        };
+        format!(
+            "{} | bits {}-{} ({} bits) | {} | {}",
+            field.name,
+            field.bit_offset,
+            field.bit_offset + field.bit_len - 1,
+            field.bit_len,
+            value,
+            location
+        )
+    }
+}
+
+/// Interactive state while exploring pages.
+struct Explorer {
+    pages: Vec<Page>,
+    page: usize,
+    bit: usize,
+    scroll: usize,
+    unmasked: bool,
+}
+
+impl Explorer {
+    fn current(&self) -> &Page {
+        &self.pages[self.page]
+    }
+
+    /// Moves the cursor to the nearest bit on the previous or next row of bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `down` - Whether to move down (rather than up).
+    fn move_vertically(&mut self, down: bool) {
+        let page = self.current();
+        let cell = match page.cells.get(self.bit) {
+            Some(cell) => cell,
+            None => return,
+        };
+        let target_line = if down {
+            page.cells.iter().map(|c| c.line).filter(|line| *line > cell.line).min()
+        } else {
+            page.cells.iter().map(|c| c.line).filter(|line| *line < cell.line).max()
+        };
+        if let Some(target_line) = target_line {
+            let column = cell.column;
+            if let Some((bit, _)) = page
+                .cells
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| c.line == target_line)
+                .min_by_key(|(_, c)| (c.column as isize - column as isize).abs())
+            {
+                self.bit = bit;
+            }
+        }
+    }
+
+    /// Moves the cursor to the first bit of the next field.
+    fn next_field(&mut self) {
+        let page = self.current();
+        if let Some(field) = page.field_at(self.bit) {
+            let next = field.bit_offset + field.bit_len;
+            if next < page.cells.len() {
+                self.bit = next;
+            }
+        }
+    }
+
+    /// Shows another page, keeping the cursor within its bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `page` - The index of the page to show.
+    fn show_page(&mut self, page: usize) {
+        self.page = page;
+        self.scroll = 0;
+        self.bit = self.bit.min(self.current().cells.len().saturating_sub(1));
+    }
+
+    /// Handles a key press, returning `false` when exploring should stop.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The key that was pressed.
+    /// * `body_height` - The number of lines available for the page.
+    fn handle_key(&mut self, key: KeyEvent, body_height: usize) -> bool {
+        let num_bits = self.current().cells.len();
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Left | KeyCode::Char('h') => self.bit = self.bit.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => self.bit = (self.bit + 1).min(num_bits.saturating_sub(1)),
+            KeyCode::Up | KeyCode::Char('k') => self.move_vertically(false),
+            KeyCode::Down | KeyCode::Char('j') => self.move_vertically(true),
+            KeyCode::Tab => self.next_field(),
+            KeyCode::Home | KeyCode::Char('g') => self.bit = 0,
+            KeyCode::End | KeyCode::Char('G') => self.bit = num_bits.saturating_sub(1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(body_height),
+            KeyCode::PageDown => self.scroll = (self.scroll + body_height).min(self.current().lines.len().saturating_sub(1)),
+            KeyCode::Char('n') if self.page + 1 < self.pages.len() => self.show_page(self.page + 1),
+            KeyCode::Char('p') if self.page > 0 => self.show_page(self.page - 1),
+            KeyCode::Char('m') => self.unmasked = !self.unmasked,
+            _ => {}
+        }
+        // Scroll the cursor into view after moving it
+        if !matches!(key.code, KeyCode::PageUp | KeyCode::PageDown) {
+            if let Some((line, _)) = self.current().position(self.bit, self.unmasked) {
+                if line < self.scroll {
+                    self.scroll = line;
+                } else if line >= self.scroll + body_height {
+                    self.scroll = line + 1 - body_height;
+                }
+            }
+        }
+        true
+    }
+
+    /// Draws the title, the visible part of the page, the cursor and the status line.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The terminal to draw to.
+    /// * `width` - The width of the terminal.
+    /// * `height` - The height of the terminal.
+    fn draw(&self, out: &mut impl Write, width: u16, height: u16) -> io::Result<()> {
+        let page = self.current();
+        let body_height = height.saturating_sub(2) as usize;
+        queue!(out, terminal::Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+
+        // Title
+        let title = format!(
+            " {} [{}/{}] {} view | arrows/hjkl move, tab next field, n/p frame, m mask, q quit",
+            page.title,
+            self.page + 1,
+            self.pages.len(),
+            if self.unmasked { "unmasked" } else { "masked" }
+        );
+        queue!(out, PrintStyledContent(fit(&title, width).reverse()))?;
+
+        // Visible lines
+        for (row, line) in page.lines.iter().skip(self.scroll).take(body_height).enumerate() {
+            queue!(out, cursor::MoveTo(0, row as u16 + 1), Print(line), ResetColor)?;
+        }
+
+        // Underline the selected field and reverse the selected bit
+        if let Some(field) = page.field_at(self.bit) {
+            for bit in field.bit_offset..(field.bit_offset + field.bit_len).min(page.cells.len()) {
+                if let Some((line, column)) = page.position(bit, self.unmasked) {
+                    if line >= self.scroll && line < self.scroll + body_height {
+                        let c = page.char_at(line, column);
+                        let styled = if bit == self.bit { c.reverse() } else { c.underlined() };
+                        queue!(out, cursor::MoveTo(column as u16, (line - self.scroll) as u16 + 1), PrintStyledContent(styled))?;
+                    }
+                }
+            }
+        }
+
+        // Status line
+        queue!(
+            out,
+            cursor::MoveTo(0, height.saturating_sub(1)),
+            PrintStyledContent(fit(&page.describe(self.bit, self.unmasked), width).reverse())
+        )?;
+        out.flush()
+    }
+}
+
+/// Explores pages interactively until the user quits.
+///
+/// # Arguments
+///
+/// * `pages` - The pages to explore.
+pub fn explore(pages: Vec<Page>) -> io::Result<()> {
+    if pages.is_empty() {
+        return Ok(());
+    }
+    let mut explorer = Explorer {
+        pages,
+        page: 0,
+        bit: 0,
+        scroll: 0,
+        unmasked: false,
+    };
+
+    let mut out = io::stdout();
+    let _terminal = RawTerminal::enter(&mut out)?;
+    run(&mut explorer, &mut out)
+}
+
+/// Puts the terminal in raw mode on the alternate screen, restoring it when dropped (including
+/// after an error or a panic), so that the user's terminal is never left broken.
+struct RawTerminal;
+
+impl RawTerminal {
+    /// Enters raw mode and the alternate screen, hiding the cursor.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The terminal.
+    fn enter(out: &mut impl Write) -> io::Result<RawTerminal> {
+        terminal::enable_raw_mode()?;
+        let guard = RawTerminal;
+        execute!(out, EnterAlternateScreen, cursor::Hide)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        // Errors cannot be reported while dropping, and restoring as much as possible is best
+        let _ = execute!(io::stdout(), cursor::Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn run(explorer: &mut Explorer, out: &mut impl Write) -> io::Result<()> {
+    loop {
+        let (width, height) = terminal::size()?;
+        explorer.draw(out, width, height)?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !explorer.handle_key(key, height.saturating_sub(2) as usize) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Finds the bit cells on the rows of a formatted table.
+///
+/// Rows of bits are the lines carrying the row label (e.g. `DWORD`); every `0` or `1` after the
+/// label is a bit (whatever characters draw the walls).
+///
+/// # Arguments
+///
+/// * `lines` - The formatted lines (without markup).
+/// * `row_label` - The label identifying lines containing bits.
+fn find_bit_cells(lines: &[String], row_label: &str) -> Vec<Cell> {
+    let mut cells = Vec::new();
+    for (line_ix, line) in lines.iter().enumerate() {
+        let label_ix = match line.find(row_label) {
+            Some(ix) => ix,
+            None => continue,
+        };
+        let first_bit_ix = line[..label_ix].chars().count() + row_label.chars().count();
+        for (column, c) in line.chars().enumerate().skip(first_bit_ix) {
+            if c == '0' || c == '1' {
+                cells.push(Cell { line: line_ix, column });
+            }
+        }
+    }
+    cells
+}
+
+/// Shortens or pads text to fill the width of the terminal.
+fn fit(text: &str, width: u16) -> String {
+    format!("{:1$.1$}", text, width as usize)
+}
+
+/// Gets a printable representation of a byte.
+fn printable(byte: u8) -> char {
+    if byte.is_ascii_graphic() || byte == b' ' {
+        byte as char
+    } else {
+        '.'
+    }
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame_page() -> Page {
+        let bytes = base64::decode("gYR7q0rdD845qQ==").unwrap();
+        Page::from_frame(String::from("Frame 1"), WebSocketFrame::from_bytes(&bytes), Markup::Plain)
+    }
+
+    #[test]
+    fn test_frame_bit_cells() {
+        let page = frame_page();
+
+        // 10 bytes in the frame
+        assert_eq!(80, page.cells.len());
+        assert_eq!(Cell { line: 6, column: 16 }, page.cells[0]);
+        assert_eq!(Cell { line: 6, column: 78 }, page.cells[31]);
+        assert_eq!(Cell { line: 12, column: 16 }, page.cells[32]);
+        assert_eq!(Cell { line: 18, column: 16 }, page.cells[64]);
+    }
+
+    #[test]
+    fn test_frame_positions() {
+        let page = frame_page();
+
+        // Payload bits have an unmasked position two lines down
+        assert_eq!(Some((12, 48)), page.position(48, false));
+        assert_eq!(Some((14, 48)), page.position(48, true));
+        // Header bits do not
+        assert_eq!(Some((6, 16)), page.position(0, true));
+        assert_eq!('0', page.char_at(14, 48));
+    }
+
+    #[test]
+    fn test_unmasked_frame_positions() {
+        let bytes = [0x81, 0x02, b'h', b'i'];
+        let page = Page::from_frame(String::from("Frame 1"), WebSocketFrame::from_bytes(&bytes), Markup::Plain);
+
+        // Unmasked frames draw their payload bits once, so the unmasked view doesn't move them
+        assert_eq!(32, page.cells.len());
+        assert_eq!(Some((6, 48)), page.position(16, false));
+        assert_eq!(Some((6, 48)), page.position(16, true));
+        assert_eq!('0', page.char_at(6, 48));
+    }
+
+    #[test]
+    fn test_describe() {
+        let page = frame_page();
+
+        assert_eq!("Opcode | bits 4-7 (4 bits) | 0x1 (1) | bit 5 (byte 0, bit 5)", page.describe(5, false));
+        assert_eq!(
+            "Payload byte 0 | bits 48-55 (8 bits) | 0x74 (116) 't' unmasked | bit 48 (byte 6, bit 0)",
+            page.describe(48, true)
+        );
+    }
+
+    #[test]
+    fn test_byte_bit_cells() {
+        let data = vec![0x81, 0x0f];
+        let page = Page::from_bytes(String::from("Bytes"), &data, Markup::Plain, RowWidth::Word, None);
+
+        assert_eq!(16, page.cells.len());
+        assert_eq!(Cell { line: 3, column: 8 }, page.cells[0]);
+        assert_eq!(Cell { line: 3, column: 17 }, page.cells[8]);
+        assert_eq!("Byte 1 | bits 8-15 (8 bits) | 0xf (15) | bit 9 (byte 1, bit 1)", page.describe(9, false));
+    }
+
+    #[test]
+    fn test_bit_numbering() {
+        let data = vec![0x81, 0x0f];
+        let page = Page::from_bytes(String::from("Bytes"), &data, Markup::Plain, RowWidth::Word, "lsb0".parse().ok());
+
+        // The tick marks add a line to the header
+        assert_eq!(Cell { line: 4, column: 8 }, page.cells[0]);
+        assert_eq!("Byte 1 | bits 8-15 (8 bits) | 0xf (15) | bit 9 (byte 1, bit 6)", page.describe(9, false));
+    }
+}
+
+// #endregion Unit tests
@@ -0,0 +1,222 @@
+use core::fmt;
+use core::str::FromStr;
+use crate::prelude::*;
+
+/// The encodings that input data can be read from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputFormat {
+    /// Raw binary bytes.
+    Raw,
+    /// Hexadecimal digit pairs, optionally prefixed with `0x` and separated by whitespace, commas,
+    /// colons or dashes (e.g. `81 84 7b ab` or `81847bab`).
+    Hex,
+    /// Standard base64 (e.g. `gYR7q0rdD845qQ==`).
+    Base64,
+    /// A C array literal (e.g. `unsigned char frame[] = { 0x81, 0x84, 123 };`).
+    CArray,
+}
+
+/// An error encountered while decoding input data.
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum InputError {
+    /// The input is not valid hexadecimal.
+    InvalidHex(String),
+    /// The input is not valid base64.
+    InvalidBase64(String),
+    /// The input is not a valid C array literal.
+    InvalidCArray(String),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::InvalidHex(message) => write!(f, "Invalid hex input. {}", message),
+            InputError::InvalidBase64(message) => write!(f, "Invalid base64 input. {}", message),
+            InputError::InvalidCArray(message) => write!(f, "Invalid C array input. {}", message),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InputError {}
+
+impl InputFormat {
+    /// Decodes input data into bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input data, encoded using this format.
+    pub fn decode(self, input: &[u8]) -> Result<Vec<u8>, InputError> {
+        match self {
+            InputFormat::Raw => Ok(input.to_vec()),
+            InputFormat::Hex => decode_hex(&String::from_utf8_lossy(input)),
+            InputFormat::Base64 => decode_base64(&String::from_utf8_lossy(input)),
+            InputFormat::CArray => decode_c_array(&String::from_utf8_lossy(input)),
+        }
+    }
+}
+
+impl FromStr for InputFormat {
+    type Err = String;
+
+    /// Parses an input format from its name (e.g. `base64` or `c-array`).
+    fn from_str(text: &str) -> Result<InputFormat, String> {
+        match text.to_ascii_lowercase().as_str() {
+            "raw" => Ok(InputFormat::Raw),
+            "hex" => Ok(InputFormat::Hex),
+            "base64" => Ok(InputFormat::Base64),
+            "c-array" => Ok(InputFormat::CArray),
+            _ => Err(format!("'{}' is not an input format. Use raw, hex, base64 or c-array.", text)),
+        }
+    }
+}
+
+/// Decodes hexadecimal text into bytes.
+///
+/// # Arguments
+///
+/// * `text` - The hexadecimal text.
+fn decode_hex(text: &str) -> Result<Vec<u8>, InputError> {
+    let mut result: Vec<u8> = Vec::new();
+    for token in text.split(|c: char| c.is_whitespace() || c == ',' || c == ':' || c == '-') {
+        let digits = token.trim_start_matches("0x").trim_start_matches("0X");
+        if digits.len() % 2 != 0 {
+            return Err(InputError::InvalidHex(format!("Odd number of digits in '{}'.", token)));
+        }
+        for ix in (0..digits.len()).step_by(2) {
+            let pair = digits.get(ix..ix + 2).unwrap_or(digits);
+            match u8::from_str_radix(pair, 16) {
+                Ok(byte) => result.push(byte),
+                Err(_) => return Err(InputError::InvalidHex(format!("'{}' is not a hex byte.", pair))),
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Decodes base64 text into bytes (whitespace such as line breaks is ignored).
+///
+/// # Arguments
+///
+/// * `text` - The base64 text.
+fn decode_base64(text: &str) -> Result<Vec<u8>, InputError> {
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    base64::decode(&compact).map_err(|error| InputError::InvalidBase64(error.to_string()))
+}
+
+/// Decodes a C array literal into bytes.
+///
+/// Elements can be hexadecimal (`0x81`), binary (`0b1010`), octal (`017`), decimal (`129`) or
+/// character literals (`'a'`). Any declaration around the braces is ignored.
+///
+/// # Arguments
+///
+/// * `text` - The C array literal.
+fn decode_c_array(text: &str) -> Result<Vec<u8>, InputError> {
+    // Take the elements between the braces (if there are any)
+    let start = text.find('{').map(|ix| ix + 1).unwrap_or(0);
+    let end = text.rfind('}').unwrap_or(text.len());
+    if end < start {
+        return Err(InputError::InvalidCArray(String::from("Mismatched braces.")));
+    }
+
+    let mut result: Vec<u8> = Vec::new();
+    for element in text[start..end].split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let value = if element.len() == 3 && element.starts_with('\'') && element.ends_with('\'') {
+            element.chars().nth(1).map(|c| c as u32).ok_or(())
+        } else if let Some(digits) = element.strip_prefix("0x").or_else(|| element.strip_prefix("0X")) {
+            u32::from_str_radix(digits, 16).map_err(|_| ())
+        } else if let Some(digits) = element.strip_prefix("0b").or_else(|| element.strip_prefix("0B")) {
+            u32::from_str_radix(digits, 2).map_err(|_| ())
+        } else if element.len() > 1 && element.starts_with('0') {
+            u32::from_str_radix(&element[1..], 8).map_err(|_| ())
+        } else {
+            element.parse::<u32>().map_err(|_| ())
+        };
+        match value {
+            Ok(byte) if byte <= u8::MAX.into() => result.push(byte as u8),
+            _ => return Err(InputError::InvalidCArray(format!("'{}' is not a byte value.", element))),
+        }
+    }
+    Ok(result)
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_decode_raw() {
+        assert_eq!(Ok(vec![0, 10, 255]), InputFormat::Raw.decode(&[0, 10, 255]));
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(Ok(InputFormat::Base64), "Base64".parse());
+        assert_eq!(Ok(InputFormat::CArray), "c-array".parse());
+        assert!("octal".parse::<InputFormat>().is_err());
+    }
+
+    #[test]
+    fn test_decode_hex() {
+        assert_eq!(Ok(vec![0x81, 0x84, 0x7b, 0xab]), InputFormat::Hex.decode(b"81 84 7B ab\n"));
+        assert_eq!(Ok(vec![0x81, 0x84, 0x7b, 0xab]), InputFormat::Hex.decode(b"81847bab"));
+        assert_eq!(Ok(vec![0x81, 0x84]), InputFormat::Hex.decode(b"0x81,0x84"));
+        assert_eq!(Ok(vec![0x81, 0x84]), InputFormat::Hex.decode(b"81:84"));
+    }
+
+    #[test]
+    fn test_decode_hex_invalid() {
+        assert!(InputFormat::Hex.decode(b"818").is_err());
+        assert!(InputFormat::Hex.decode(b"8g").is_err());
+    }
+
+    #[test]
+    fn test_decode_base64() {
+        assert_eq!(Ok(vec![0x81, 0x84, 0x7b, 0xab]), InputFormat::Base64.decode(b"gYR7\nqw==\n"));
+        assert!(InputFormat::Base64.decode(b"g!").is_err());
+    }
+
+    #[test]
+    fn test_decode_c_array() {
+        assert_eq!(
+            Ok(vec![0x81, 132, 10, 15, b'a']),
+            InputFormat::CArray.decode(b"unsigned char frame[] = { 0x81, 132, 0b1010, 017, 'a', };")
+        );
+        assert_eq!(Ok(vec![1, 2]), InputFormat::CArray.decode(b"1, 2"));
+    }
+
+    #[test]
+    fn test_decode_c_array_invalid() {
+        assert!(InputFormat::CArray.decode(b"{ 256 }").is_err());
+        assert!(InputFormat::CArray.decode(b"{ 0xZZ }").is_err());
+    }
+    proptest! {
+        /// Tests that decoding arbitrary input returns an error rather than panicking.
+        #[test]
+        fn prop_decode_never_panics(input: Vec<u8>, text in "\\PC*") {
+            for format in [InputFormat::Raw, InputFormat::Hex, InputFormat::Base64, InputFormat::CArray] {
+                let _ = format.decode(&input);
+                let _ = format.decode(text.as_bytes());
+            }
+        }
+
+        /// Tests that encoded bytes decode back to the same bytes.
+        #[test]
+        fn prop_round_trip(bytes: Vec<u8>) {
+            let hex: Vec<String> = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+            let c_array: Vec<String> = bytes.iter().map(|byte| format!("0x{:02X}", byte)).collect();
+
+            prop_assert_eq!(&bytes, &InputFormat::Hex.decode(hex.concat().as_bytes()).unwrap());
+            prop_assert_eq!(&bytes, &InputFormat::Hex.decode(hex.join(" ").as_bytes()).unwrap());
+            prop_assert_eq!(&bytes, &InputFormat::Base64.decode(base64::encode(&bytes).as_bytes()).unwrap());
+            prop_assert_eq!(&bytes, &InputFormat::CArray.decode(format!("{{ {} }}", c_array.join(", ")).as_bytes()).unwrap());
+        }
+    }
+}
+
+// #endregion Unit tests
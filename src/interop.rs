@@ -0,0 +1,360 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+use tungstenite::protocol::frame::coding::{Data, OpCode};
+use tungstenite::protocol::frame::Frame;
+use tungstenite::Message;
+use crate::websocket_frame::{FrameError, WebSocketFrame};
+
+impl From<&Frame> for WebSocketFrame<'static> {
+    /// Converts a tungstenite frame into the frame it is written as.
+    ///
+    /// Tungstenite unmasks frames as it reads them and masks frames as it writes them, so the frame
+    /// is only masked when it still holds a mask. Use a `FrameTap` to see the masked bytes on the wire.
+    fn from(frame: &Frame) -> WebSocketFrame<'static> {
+        let mut bytes = Vec::with_capacity(frame.len());
+        frame.clone().format(&mut bytes).expect("Writing to a vector cannot fail.");
+        WebSocketFrame::from_bytes(&bytes).into_owned()
+    }
+}
+
+impl From<&Message> for WebSocketFrame<'static> {
+    /// Converts a tungstenite message into the single (unmasked) frame carrying it.
+    fn from(message: &Message) -> WebSocketFrame<'static> {
+        let frame = match message.clone() {
+            Message::Text(text) => Frame::message(text, OpCode::Data(Data::Text), true),
+            Message::Binary(data) => Frame::message(data, OpCode::Data(Data::Binary), true),
+            Message::Ping(data) => Frame::ping(data),
+            Message::Pong(data) => Frame::pong(data),
+            Message::Close(close) => Frame::close(close),
+            Message::Frame(frame) => frame,
+        };
+        WebSocketFrame::from(&frame)
+    }
+}
+
+/// The bytes captured in one direction of a tapped stream.
+#[derive(Default)]
+struct Capture {
+    /// The bytes of the handshake or frame still being captured.
+    buffer: Vec<u8>,
+    /// The frames captured in full and not yet taken.
+    frames: Vec<WebSocketFrame<'static>>,
+    /// Whether the HTTP upgrade message (ending with a blank line) is still to be skipped.
+    in_handshake: bool,
+    /// The length of the frame (or handshake) that stopped the capture by exceeding the limit.
+    too_long: Option<usize>,
+}
+
+impl fmt::Debug for Capture {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Capture")
+            .field("buffer", &self.buffer)
+            .field("frames", &self.frames.len())
+            .field("in_handshake", &self.in_handshake)
+            .field("too_long", &self.too_long)
+            .finish()
+    }
+}
+
+impl Capture {
+    /// Adds bytes to the capture, parsing the frames they complete.
+    ///
+    /// A handshake or frame longer than the limit stops the capture, freeing the buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The bytes read or written.
+    /// * `max_frame_len` - The longest frame to buffer (in bytes).
+    fn extend(&mut self, bytes: &[u8], max_frame_len: Option<usize>) {
+        if self.too_long.is_some() {
+            return;
+        }
+        self.buffer.extend_from_slice(bytes);
+        let too_long = |len: usize| max_frame_len.is_some_and(|max| len > max);
+        if self.in_handshake {
+            match self.buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+                Some(end) => {
+                    self.buffer.drain(..end + 4);
+                    self.in_handshake = false;
+                }
+                None => {
+                    if too_long(self.buffer.len()) {
+                        self.stop(self.buffer.len());
+                    }
+                    return;
+                }
+            }
+        }
+        let mut offset = 0;
+        loop {
+            match WebSocketFrame::try_from_bytes(&self.buffer[offset..]) {
+                Ok(frame) if !too_long(frame.frame_len) => {
+                    offset += frame.frame_len;
+                    self.frames.push(frame.into_owned());
+                }
+                Ok(frame) => return self.stop(frame.frame_len),
+                Err(FrameError::Truncated { expected, .. }) if too_long(expected) => return self.stop(expected),
+                Err(FrameError::Truncated { .. }) => break,
+            }
+        }
+        self.buffer.drain(..offset);
+    }
+
+    /// Stops capturing after a handshake or frame longer than the limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `len` - The length of the handshake or frame (in bytes).
+    fn stop(&mut self, len: usize) {
+        self.buffer = Vec::new();
+        self.too_long = Some(len);
+    }
+
+    /// Takes the frames captured in full, or reports that the capture stopped once they are taken.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_frame_len` - The longest frame buffered (in bytes).
+    fn take_frames(&mut self, max_frame_len: Option<usize>) -> io::Result<Vec<WebSocketFrame<'static>>> {
+        match self.too_long {
+            Some(len) if self.frames.is_empty() => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "The frame is longer than {} bytes (at least {} bytes), so capturing stopped.",
+                    max_frame_len.unwrap_or(0),
+                    len
+                ),
+            )),
+            _ => Ok(std::mem::take(&mut self.frames)),
+        }
+    }
+}
+
+/// Wraps a stream handed to tungstenite (e.g. a `TcpStream`), capturing the bytes read and written
+/// so that the frames can be rendered exactly as they were sent and received, masking included.
+///
+/// The stream implements `Read` and `Write` (and, with the `tokio` feature, `AsyncRead` and
+/// `AsyncWrite` for tokio-tungstenite).
+#[derive(Debug)]
+pub struct FrameTap<S> {
+    /// The longest frame captured (in bytes). A longer frame (or handshake) stops the capture in
+    /// its direction rather than being buffered, without affecting the stream. Defaults to
+    /// `WebSocketFrame::DEFAULT_MAX_LEN`; `None` buffers frames of any length, so only use it with
+    /// trusted peers.
+    pub max_frame_len: Option<usize>,
+    inner: S,
+    received: Capture,
+    sent: Capture,
+}
+
+impl<S> FrameTap<S> {
+    /// Taps a stream before the HTTP upgrade handshake, which is skipped in both directions.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The stream to tap.
+    pub fn new(inner: S) -> FrameTap<S> {
+        let capture = || Capture { in_handshake: true, ..Capture::default() };
+        FrameTap {
+            max_frame_len: Some(WebSocketFrame::DEFAULT_MAX_LEN),
+            inner,
+            received: capture(),
+            sent: capture(),
+        }
+    }
+
+    /// Taps a stream after the handshake, so that frames start with the first byte.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The stream to tap.
+    pub fn after_handshake(inner: S) -> FrameTap<S> {
+        FrameTap {
+            max_frame_len: Some(WebSocketFrame::DEFAULT_MAX_LEN),
+            inner,
+            received: Capture::default(),
+            sent: Capture::default(),
+        }
+    }
+
+    /// Takes the frames read in full since the last call.
+    ///
+    /// Once the frames before it are taken, a frame longer than `max_frame_len` is reported as
+    /// invalid data, as capturing the frames read stops there.
+    pub fn take_received(&mut self) -> io::Result<Vec<WebSocketFrame<'static>>> {
+        self.received.take_frames(self.max_frame_len)
+    }
+
+    /// Takes the frames written in full since the last call.
+    ///
+    /// Once the frames before it are taken, a frame longer than `max_frame_len` is reported as
+    /// invalid data, as capturing the frames written stops there.
+    pub fn take_sent(&mut self) -> io::Result<Vec<WebSocketFrame<'static>>> {
+        self.sent.take_frames(self.max_frame_len)
+    }
+
+    /// Gets the tapped stream.
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    /// Gets the tapped stream mutably. Bytes read or written directly are not captured.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// Unwraps the tapped stream.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+}
+
+impl<S: Read> Read for FrameTap<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.received.extend(&buf[..len], self.max_frame_len);
+        Ok(len)
+    }
+}
+
+impl<S: Write> Write for FrameTap<S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.sent.extend(&buf[..len], self.max_frame_len);
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(feature = "tokio")]
+mod tokio_io {
+    use std::io;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+    use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+    use super::FrameTap;
+
+    impl<S: AsyncRead + Unpin> AsyncRead for FrameTap<S> {
+        fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+            let start = buf.filled().len();
+            let result = Pin::new(&mut self.inner).poll_read(cx, buf);
+            if let Poll::Ready(Ok(())) = result {
+                let max_frame_len = self.max_frame_len;
+                self.received.extend(&buf.filled()[start..], max_frame_len);
+            }
+            result
+        }
+    }
+
+    impl<S: AsyncWrite + Unpin> AsyncWrite for FrameTap<S> {
+        fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+            let result = Pin::new(&mut self.inner).poll_write(cx, buf);
+            if let Poll::Ready(Ok(len)) = result {
+                let max_frame_len = self.max_frame_len;
+                self.sent.extend(&buf[..len], max_frame_len);
+            }
+            result
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.inner).poll_flush(cx)
+        }
+
+        fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Pin::new(&mut self.inner).poll_shutdown(cx)
+        }
+    }
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tungstenite::protocol::{Role, WebSocket};
+
+    /// A connection whose reads come from a script and whose writes are kept.
+    struct Script {
+        input: io::Cursor<Vec<u8>>,
+        output: Vec<u8>,
+    }
+
+    impl Read for Script {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for Script {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_from_message() {
+        let frame = WebSocketFrame::from(&Message::text("test"));
+
+        assert_eq!(vec![0x81, 0x04, b't', b'e', b's', b't'], frame.to_bytes());
+        assert_eq!("Pong", WebSocketFrame::from(&Message::Pong(vec![1].into())).opcode_name());
+    }
+
+    #[test]
+    fn test_tap() {
+        let mut input = b"HTTP/1.1 101 Switching Protocols\r\n\r\n".to_vec();
+        input.extend_from_slice(&[0x81, 0x02, b'h', b'i', 0x89]);
+        let script = Script { input: io::Cursor::new(input), output: Vec::new() };
+        let mut tap = FrameTap::new(script);
+        tap.write_all(b"GET / HTTP/1.1\r\n\r\n").unwrap();
+        tap.read_exact(&mut [0; 36]).unwrap();
+
+        // A client masks the frames it sends
+        let mut socket = WebSocket::from_raw_socket(tap, Role::Client, None);
+        socket.send(Message::text("test")).unwrap();
+        assert_eq!("hi", socket.read().unwrap().into_text().unwrap().as_str());
+        let tap = socket.get_mut();
+
+        let sent = tap.take_sent().unwrap();
+        assert_eq!(1, sent.len());
+        assert!(sent[0].is_payload_masked);
+        assert_eq!(10, sent[0].frame_len);
+        let received = tap.take_received().unwrap();
+        assert_eq!(1, received.len());
+        assert_eq!(4, received[0].frame_len);
+        // The start of the ping is kept until the rest arrives
+        assert!(tap.take_received().unwrap().is_empty());
+        assert_eq!(vec![0x89], tap.received.buffer);
+    }
+
+    #[test]
+    fn test_tap_max_frame_len() {
+        // A frame, then a long frame header declaring the largest possible payload
+        let mut input = vec![0x81, 0x02, b'h', b'i'];
+        input.extend_from_slice(&[0x82, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00]);
+        let script = Script { input: io::Cursor::new(input), output: Vec::new() };
+        let mut tap = FrameTap::after_handshake(script);
+        tap.max_frame_len = Some(8);
+        let mut read = Vec::new();
+        tap.read_to_end(&mut read).unwrap();
+
+        // The stream is read in full, but capturing stops at the long frame
+        assert_eq!(15, read.len());
+        assert!(tap.received.buffer.is_empty());
+        assert_eq!(1, tap.take_received().unwrap().len());
+        let error = tap.take_received().err().expect("The frame is too long.");
+        assert_eq!(io::ErrorKind::InvalidData, error.kind());
+
+        // Writing is captured separately
+        tap.write_all(&[0x89, 0x00]).unwrap();
+        assert_eq!(1, tap.take_sent().unwrap().len());
+    }
+}
+
+// #endregion Unit tests
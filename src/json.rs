@@ -0,0 +1,53 @@
+use crate::prelude::*;
+
+/// Formats bytes as a JSON array of numbers.
+///
+/// # Arguments
+///
+/// * `bytes` - The bytes to format.
+pub(crate) fn byte_array(bytes: &[u8]) -> String {
+    let values: Vec<String> = bytes.iter().map(|byte| byte.to_string()).collect();
+    format!("[{}]", values.join(","))
+}
+
+/// Formats text as a quoted and escaped JSON string.
+///
+/// # Arguments
+///
+/// * `text` - The text to format.
+pub(crate) fn string(text: &str) -> String {
+    let mut result = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_byte_array() {
+        assert_eq!("[]", byte_array(&[]));
+        assert_eq!("[1,255]", byte_array(&[1, 255]));
+    }
+
+    #[test]
+    fn test_string_escapes() {
+        assert_eq!("\"a\\\"b\\\\c\\n\\u0001\"", string("a\"b\\c\n\u{1}"));
+    }
+}
+
+// #endregion Unit tests
@@ -1,5 +1,53 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+mod bit_numbering;
+mod border;
+mod buffer;
 mod byte_list;
+#[cfg(feature = "tokio")]
+mod codec;
+mod color;
+mod diff;
+mod doc_table;
+mod input;
+#[cfg(feature = "tungstenite")]
+mod interop;
+mod json;
+mod logging;
+mod markup;
+mod prelude;
+#[cfg(any(test, feature = "snapshot"))]
+pub mod snapshot;
+mod theme;
+#[cfg(feature = "wasm-bindgen")]
+pub mod wasm;
 mod websocket_frame;
 
-pub use byte_list::ByteList;
-pub use websocket_frame::WebSocketFrame;
+pub use bit_numbering::{BitNumbering, BitOrder, BitSpan};
+pub use border::BorderStyle;
+pub use buffer::FixedBuffer;
+#[cfg(feature = "tokio")]
+pub use codec::FrameCodec;
+pub use color::Color;
+pub use byte_list::{Annotation, AnnotationLabels, ByteList, Endianness, Interpretation, Layout, Notation, NumberType, OffsetColumn, OffsetFormat, RowWidth, ValueStyle};
+#[cfg(feature = "std")]
+pub use byte_list::ByteStream;
+pub use diff::{align, ByteDiff, DiffOp, FieldDiff, FrameDiff};
+pub use input::{InputError, InputFormat};
+#[cfg(feature = "tungstenite")]
+pub use interop::FrameTap;
+pub use logging::PlainText;
+pub use markup::Markup;
+pub use theme::{Style, Theme, ThemeError};
+pub use websocket_frame::{FrameError, FrameField, WebSocketFrame};
+
+#[doc(hidden)]
+pub mod __private {
+    // The crates used by the logging macros
+    #[cfg(feature = "log")]
+    pub use ::log;
+    #[cfg(feature = "tracing")]
+    pub use ::tracing;
+}
@@ -0,0 +1,234 @@
+use core::fmt;
+use crate::byte_list::ByteList;
+use crate::markup::Markup;
+use crate::websocket_frame::WebSocketFrame;
+
+/// Renders a table or frame diagram as plain text when displayed, so nothing is formatted until a
+/// log sink writes it (and nothing at all when the log level is disabled).
+///
+/// Created by `ByteList::plain` and `WebSocketFrame::plain`.
+pub struct PlainText<'r, T> {
+    value: &'r T,
+}
+
+impl<'r, T> PlainText<'r, T> {
+    /// Wraps a table or frame to display as plain text.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The table or frame.
+    pub(crate) fn new(value: &'r T) -> PlainText<'r, T> {
+        PlainText { value }
+    }
+}
+
+impl fmt::Display for PlainText<'_, ByteList<'_>> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut list = self.value.clone();
+        list.markup = Markup::Plain;
+        list.render_to(f)
+    }
+}
+
+impl fmt::Display for PlainText<'_, WebSocketFrame<'_>> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut frame = self.value.clone();
+        frame.markup = Markup::Plain;
+        frame.render_to(f)
+    }
+}
+
+/// Logs a WebSocket frame diagram as plain text with the `log` crate, rendering it only when the
+/// level is enabled. A message (with format arguments) may follow the frame.
+///
+/// ```ignore
+/// log_frame!(log::Level::Debug, frame);
+/// log_frame!(log::Level::Debug, frame, "Sent frame {}", id);
+/// ```
+#[cfg(feature = "log")]
+#[macro_export]
+macro_rules! log_frame {
+    ($level:expr, $frame:expr) => {
+        $crate::log_frame!($level, $frame, "WebSocket frame")
+    };
+    ($level:expr, $frame:expr, $($message:tt)+) => {
+        $crate::__private::log::log!($level, "{}\n{}", format_args!($($message)+), $frame.plain())
+    };
+}
+
+/// Logs a table of bytes as plain text with the `log` crate, rendering it only when the level is
+/// enabled. A message (with format arguments) may follow the table.
+///
+/// ```ignore
+/// log_bytes!(log::Level::Trace, ByteList::from_bytes(&packet), "Received {} bytes", packet.len());
+/// ```
+#[cfg(feature = "log")]
+#[macro_export]
+macro_rules! log_bytes {
+    ($level:expr, $list:expr) => {
+        $crate::log_bytes!($level, $list, "Bytes")
+    };
+    ($level:expr, $list:expr, $($message:tt)+) => {
+        $crate::__private::log::log!($level, "{}\n{}", format_args!($($message)+), $list.plain())
+    };
+}
+
+/// Records a `tracing` event holding a WebSocket frame diagram as plain text, with the frame's
+/// `opcode`, `opcode_name`, `payload_length`, `masked` and `frame_length` as structured fields.
+/// Nothing is rendered unless the event is enabled. The level must be a constant (e.g.
+/// `tracing::Level::DEBUG`), and a message (with format arguments) may follow the frame.
+///
+/// ```ignore
+/// trace_frame!(tracing::Level::DEBUG, frame, "Sent frame {}", id);
+/// ```
+#[cfg(feature = "tracing")]
+#[macro_export]
+macro_rules! trace_frame {
+    ($level:expr, $frame:expr) => {
+        $crate::trace_frame!($level, $frame, "WebSocket frame")
+    };
+    ($level:expr, $frame:expr, $($message:tt)+) => {{
+        let frame = &$frame;
+        $crate::__private::tracing::event!(
+            $level,
+            opcode = frame.opcode(),
+            opcode_name = %frame.opcode_name(),
+            payload_length = frame.payload_length.num_bytes(),
+            masked = frame.is_payload_masked,
+            frame_length = frame.frame_len,
+            "{}\n{}",
+            format_args!($($message)+),
+            frame.plain()
+        )
+    }};
+}
+
+/// Records a `tracing` event holding a table of bytes as plain text, rendered only when the event
+/// is enabled. The level must be a constant (e.g. `tracing::Level::TRACE`), and a message (with
+/// format arguments) may follow the table.
+#[cfg(feature = "tracing")]
+#[macro_export]
+macro_rules! trace_bytes {
+    ($level:expr, $list:expr) => {
+        $crate::trace_bytes!($level, $list, "Bytes")
+    };
+    ($level:expr, $list:expr, $($message:tt)+) => {
+        $crate::__private::tracing::event!($level, "{}\n{}", format_args!($($message)+), $list.plain())
+    };
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::byte_list::{Notation, RowWidth};
+
+    #[test]
+    fn test_plain_text() {
+        let data = vec![0x81];
+        let mut list = ByteList::from_bytes(&data);
+        list.markup = Markup::Html;
+        list.notations = vec![Notation::Hex];
+        list.row_width = RowWidth::Byte;
+
+        let expected = "       +--------+\n Bytes | Byte 0 |\n+------+--------+\n|BYTE  |    0x81|\n|  1   |        |\n+------+--------+\n";
+
+        assert_eq!(expected, list.plain().to_string());
+        // The list itself is left alone
+        assert!(list.format().starts_with("<pre"));
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn test_log_frame() {
+        use std::sync::Mutex;
+
+        struct Capture(Mutex<Vec<String>>);
+
+        impl log::Log for Capture {
+            fn enabled(&self, metadata: &log::Metadata) -> bool {
+                metadata.level() <= log::Level::Info
+            }
+
+            fn log(&self, record: &log::Record) {
+                if self.enabled(record.metadata()) {
+                    self.0.lock().unwrap().push(record.args().to_string());
+                }
+            }
+
+            fn flush(&self) {}
+        }
+
+        static CAPTURE: Capture = Capture(Mutex::new(Vec::new()));
+        log::set_logger(&CAPTURE).unwrap();
+        log::set_max_level(log::LevelFilter::Info);
+
+        let bytes = base64::decode("gYR7q0rdD845qQ==").unwrap();
+        let frame = WebSocketFrame::from_bytes(&bytes);
+        crate::log_frame!(log::Level::Debug, frame);
+        crate::log_frame!(log::Level::Info, frame, "Frame {}", 1);
+
+        let records = CAPTURE.0.lock().unwrap();
+        assert_eq!(1, records.len());
+        assert!(records[0].starts_with("Frame 1\n               +---------------+"));
+        assert!(!records[0].contains('\x1b'));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_trace_frame() {
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Level, Metadata, Subscriber};
+
+        /// Records the fields of each event as `name=value` strings.
+        #[derive(Clone, Default)]
+        struct Capture(Arc<Mutex<Vec<String>>>);
+
+        impl Visit for Capture {
+            fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+                self.0.lock().unwrap().push(format!("{}={:?}", field.name(), value));
+            }
+        }
+
+        impl Subscriber for Capture {
+            fn enabled(&self, metadata: &Metadata) -> bool {
+                *metadata.level() <= Level::INFO
+            }
+
+            fn new_span(&self, _: &Attributes) -> Id {
+                Id::from_u64(1)
+            }
+
+            fn record(&self, _: &Id, _: &Record) {}
+
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+            fn event(&self, event: &Event) {
+                event.record(&mut self.clone());
+            }
+
+            fn enter(&self, _: &Id) {}
+
+            fn exit(&self, _: &Id) {}
+        }
+
+        let capture = Capture::default();
+        let bytes = base64::decode("gYR7q0rdD845qQ==").unwrap();
+        let frame = WebSocketFrame::from_bytes(&bytes);
+        tracing::subscriber::with_default(capture.clone(), || {
+            crate::trace_frame!(Level::DEBUG, frame);
+            crate::trace_frame!(Level::INFO, frame, "Frame {}", 1);
+        });
+
+        let fields = capture.0.lock().unwrap();
+        // Only the enabled event is recorded (its message comes first)
+        assert_eq!(6, fields.len());
+        assert!(fields[0].starts_with("message=Frame 1\n               +---------------+"));
+        assert_eq!(["opcode=1", "opcode_name=Text", "payload_length=4", "masked=true", "frame_length=10"], fields[1..]);
+    }
+}
+
+// #endregion Unit tests
@@ -0,0 +1,753 @@
+#[cfg(feature = "tui")]
+mod explorer;
+
+use std::error::Error;
+use std::fs;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::process;
+
+use bitformat::{
+    Annotation, AnnotationLabels, BitNumbering, BorderStyle, ByteDiff, ByteList, ByteStream, FrameDiff, InputFormat, Interpretation, Layout, Markup, Notation, OffsetColumn, OffsetFormat, RowWidth, Theme, ValueStyle, WebSocketFrame,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+/// Formats bit-focused data structures for printing to terminals and logs.
+#[derive(Parser)]
+#[command(name = "bitformat", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Formats bytes as a table.
+    Bytes {
+        #[command(flatten)]
+        common: CommonArgs,
+        #[command(flatten)]
+        table: TableArgs,
+    },
+    /// Formats a single WebSocket data frame.
+    WsFrame(CommonArgs),
+    /// Formats a sequence of WebSocket data frames.
+    WsStream {
+        #[command(flatten)]
+        common: CommonArgs,
+        /// The maximum number of frames to format.
+        #[arg(long)]
+        max_frames: Option<usize>,
+    },
+    /// Compares expected and actual bytes (or WebSocket data frames).
+    Diff(DiffArgs),
+    /// Prints a theme as TOML to start a theme file from (lists the presets when omitted).
+    Theme {
+        /// A preset or a TOML theme file.
+        #[arg(value_parser = parse_theme)]
+        theme: Option<Theme>,
+    },
+}
+
+#[derive(Args)]
+struct CommonArgs {
+    /// The file to read (reads standard input if omitted or `-`).
+    file: Option<PathBuf>,
+    /// How the input is encoded.
+    #[arg(short, long, value_enum, default_value_t = InputArg::Raw)]
+    input: InputArg,
+    /// How the output is rendered.
+    #[arg(short, long, value_enum, default_value_t = OutputArg::Auto)]
+    output: OutputArg,
+    /// The maximum number of bytes to format (payload bytes when formatting frames).
+    #[arg(short = 'n', long)]
+    limit: Option<usize>,
+    /// The theme of tables and frame diagrams: a preset (e.g. `solarized`, see `bitformat theme`) or a TOML theme file.
+    #[arg(long, value_parser = parse_theme)]
+    theme: Option<Theme>,
+    /// The characters drawing the borders of tables and frame diagrams.
+    #[arg(long, value_enum, default_value_t = BorderArg::Ascii)]
+    border: BorderArg,
+    /// The maximum width of tables and frame diagrams, which switch to narrower layouts to fit
+    /// (defaults to the width of the terminal).
+    #[arg(short, long)]
+    width: Option<usize>,
+    /// Numbers bits in tick marks to match a document: msb0 (bit 0 is the most significant, as in
+    /// RFC 6455) or lsb0, with a `-byte` suffix to start again in every byte (e.g. `lsb0-byte`).
+    /// Tables show the tick marks only when this is given.
+    #[arg(long)]
+    bit_numbering: Option<BitNumbering>,
+    /// Explores the output interactively (colour and plain output only).
+    #[cfg(feature = "tui")]
+    #[arg(short = 'x', long)]
+    interactive: bool,
+}
+
+#[derive(Args)]
+struct DiffArgs {
+    /// The file holding the expected bytes (`-` reads standard input).
+    expected: PathBuf,
+    /// The file holding the actual bytes (`-` reads standard input).
+    actual: PathBuf,
+    /// How the inputs are encoded.
+    #[arg(short, long, value_enum, default_value_t = InputArg::Raw)]
+    input: InputArg,
+    /// How the output is rendered.
+    #[arg(short, long, value_enum, default_value_t = OutputArg::Auto)]
+    output: OutputArg,
+    /// Compares the inputs as WebSocket data frames, field by field.
+    #[arg(long)]
+    ws_frame: bool,
+    /// The notation used to show each byte.
+    #[arg(long, value_enum, default_value_t = NotationArg::Binary)]
+    notation: NotationArg,
+    /// The aligned bytes per row (byte, word, dword, qword, oword or a number of bytes).
+    #[arg(short, long, default_value_t = RowWidth::Qword)]
+    row_width: RowWidth,
+    /// The theme of the comparison: a preset (e.g. `solarized`, see `bitformat theme`) or a TOML theme file.
+    #[arg(long, value_parser = parse_theme)]
+    theme: Option<Theme>,
+}
+
+#[derive(Args)]
+struct TableArgs {
+    /// The notations used to show each byte (one line per notation).
+    #[arg(long, value_enum, value_delimiter = ',', default_values_t = [NotationArg::Binary, NotationArg::Decimal])]
+    notation: Vec<NotationArg>,
+    /// The bytes per row (byte, word, dword, qword, oword or a number of bytes).
+    #[arg(short, long, default_value_t = RowWidth::Qword)]
+    row_width: RowWidth,
+    /// Numbers to read from each group of bytes (e.g. `u32`, `i16le` or `f64be`; big-endian by default).
+    #[arg(long, value_delimiter = ',')]
+    interpret: Vec<Interpretation>,
+    /// Shows the offset of each row (shown in hex when only `--base-address` is given).
+    #[arg(long, value_enum)]
+    offsets: Option<OffsetArg>,
+    /// The minimum number of digits in each offset.
+    #[arg(long, default_value_t = 8)]
+    offset_digits: usize,
+    /// The address of the first byte (e.g. `0x20000000`); rows stay aligned to the row width.
+    #[arg(long, value_parser = parse_address)]
+    base_address: Option<u64>,
+    /// The number of bytes to skip before formatting (e.g. `0x400`); offsets include the skipped bytes.
+    #[arg(long, value_parser = parse_address, default_value_t = 0)]
+    skip: u64,
+    /// The overall layout.
+    #[arg(long, value_enum, default_value_t = LayoutArg::Table)]
+    layout: LayoutArg,
+    /// Replaces repeated lines with `*` (all-zero lines for xxd); the default for hexdump.
+    #[arg(long, overrides_with = "no_collapse")]
+    collapse: bool,
+    /// Shows every line of a dump; the default for xxd.
+    #[arg(long)]
+    no_collapse: bool,
+    /// Highlights a labelled range (e.g. `4..8=CRC@red+bold`, `12=bad` or `bits:100..103=flags@underline`; repeatable).
+    #[arg(long)]
+    annotate: Vec<Annotation>,
+    /// Shows annotation labels beneath each row instead of in a legend.
+    #[arg(long)]
+    inline_labels: bool,
+    /// Styles bytes by value: dims zero bytes, highlights printable ASCII or marks 0xff (comma separated).
+    #[arg(long, value_enum, value_delimiter = ',')]
+    value_styles: Vec<ValueStyleArg>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum InputArg {
+    /// Raw binary bytes.
+    Raw,
+    /// Hexadecimal digit pairs (e.g. `81 84 7b ab`).
+    Hex,
+    /// Base64 (e.g. `gYR7q0rdD845qQ==`).
+    Base64,
+    /// A C array literal (e.g. `{ 0x81, 0x84, 123 }`).
+    CArray,
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum OutputArg {
+    /// Colour when writing to a terminal, plain text otherwise.
+    Auto,
+    /// Always colour using ANSI escape codes.
+    Color,
+    /// Plain text.
+    Plain,
+    /// HTML.
+    Html,
+    /// JSON.
+    Json,
+    /// A GitHub-flavoured Markdown table (bytes) or fenced code block (frames).
+    Markdown,
+    /// A reStructuredText grid table.
+    Rst,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum NotationArg {
+    /// Binary digits.
+    Binary,
+    /// Hexadecimal.
+    Hex,
+    /// Octal.
+    Octal,
+    /// Signed decimal.
+    Signed,
+    /// Unsigned decimal.
+    Decimal,
+    /// ASCII characters.
+    Ascii,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum LayoutArg {
+    /// A table with a line per notation.
+    Table,
+    /// The output of `xxd`.
+    Xxd,
+    /// The output of `hexdump -C`.
+    Hexdump,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ValueStyleArg {
+    /// Dims zero bytes.
+    Zero,
+    /// Highlights printable ASCII characters.
+    Printable,
+    /// Marks bytes with every bit set.
+    Ff,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum BorderArg {
+    /// ASCII characters (`+`, `-` and `|`).
+    Ascii,
+    /// Light box drawing characters.
+    Light,
+    /// Heavy box drawing characters.
+    Heavy,
+    /// Double-line box drawing characters.
+    Double,
+    /// Light box drawing characters with rounded corners.
+    Rounded,
+    /// No borders.
+    None,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OffsetArg {
+    /// Hexadecimal.
+    Hex,
+    /// Decimal.
+    Decimal,
+}
+
+impl From<OffsetArg> for OffsetFormat {
+    fn from(offset: OffsetArg) -> OffsetFormat {
+        match offset {
+            OffsetArg::Hex => OffsetFormat::Hex,
+            OffsetArg::Decimal => OffsetFormat::Decimal,
+        }
+    }
+}
+
+impl From<ValueStyleArg> for ValueStyle {
+    fn from(value_style: ValueStyleArg) -> ValueStyle {
+        match value_style {
+            ValueStyleArg::Zero => ValueStyle::zero(),
+            ValueStyleArg::Printable => ValueStyle::printable(),
+            ValueStyleArg::Ff => ValueStyle::max(),
+        }
+    }
+}
+
+impl From<BorderArg> for BorderStyle {
+    fn from(border: BorderArg) -> BorderStyle {
+        match border {
+            BorderArg::Ascii => BorderStyle::Ascii,
+            BorderArg::Light => BorderStyle::Light,
+            BorderArg::Heavy => BorderStyle::Heavy,
+            BorderArg::Double => BorderStyle::Double,
+            BorderArg::Rounded => BorderStyle::Rounded,
+            BorderArg::None => BorderStyle::None,
+        }
+    }
+}
+
+impl From<NotationArg> for Notation {
+    fn from(notation: NotationArg) -> Notation {
+        match notation {
+            NotationArg::Binary => Notation::Binary,
+            NotationArg::Hex => Notation::Hex,
+            NotationArg::Octal => Notation::Octal,
+            NotationArg::Signed => Notation::SignedDecimal,
+            NotationArg::Decimal => Notation::UnsignedDecimal,
+            NotationArg::Ascii => Notation::Ascii,
+        }
+    }
+}
+
+impl From<InputArg> for InputFormat {
+    fn from(input: InputArg) -> InputFormat {
+        match input {
+            InputArg::Raw => InputFormat::Raw,
+            InputArg::Hex => InputFormat::Hex,
+            InputArg::Base64 => InputFormat::Base64,
+            InputArg::CArray => InputFormat::CArray,
+        }
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if let Err(error) = run(cli) {
+        eprintln!("bitformat: {}", error);
+        process::exit(1);
+    }
+}
+
+/// Runs a command.
+///
+/// # Arguments
+///
+/// * `cli` - The parsed command line.
+fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    #[cfg(feature = "tui")]
+    {
+        if let Some(pages) = explorer_pages(&cli.command)? {
+            return Ok(explorer::explore(pages)?);
+        }
+    }
+
+    match cli.command {
+        Command::Bytes { common: args, table } => {
+            write_bytes(&args, &table, BufWriter::new(io::stdout().lock()))?;
+        }
+        Command::WsFrame(args) => {
+            let data = read_input(&args)?;
+            let frame = WebSocketFrame::try_from_bytes(&data)?;
+            if frame.frame_len < data.len() {
+                eprintln!("bitformat: ignoring {} bytes after the end of the frame", data.len() - frame.frame_len);
+            }
+            print!("{}", format_frame(frame, &args));
+        }
+        Command::WsStream { common: args, max_frames } => {
+            let data = read_input(&args)?;
+            print!("{}", format_stream(&data, &args, max_frames)?);
+        }
+        Command::Diff(args) => {
+            if args.expected.as_os_str() == "-" && args.actual.as_os_str() == "-" {
+                return Err("Only one input can be read from standard input.".into());
+            }
+            let expected = read_file(Some(&args.expected), args.input)?;
+            let actual = read_file(Some(&args.actual), args.input)?;
+            print!("{}", format_diff(&expected, &actual, &args)?);
+        }
+        Command::Theme { theme: Some(theme) } => print!("{}", theme.to_toml()),
+        Command::Theme { theme: None } => println!("{}", Theme::PRESETS.join("\n")),
+    }
+    Ok(())
+}
+
+/// Builds the pages to explore when the interactive explorer is requested.
+///
+/// # Arguments
+///
+/// * `command` - The command being run.
+#[cfg(feature = "tui")]
+fn explorer_pages(command: &Command) -> Result<Option<Vec<explorer::Page>>, Box<dyn Error>> {
+    let args = match command {
+        Command::Bytes { common: args, .. } | Command::WsFrame(args) | Command::WsStream { common: args, .. } => args,
+        Command::Diff(_) | Command::Theme { .. } => return Ok(None),
+    };
+    if !args.interactive {
+        return Ok(None);
+    }
+    if !matches!(args.output, OutputArg::Auto | OutputArg::Color | OutputArg::Plain) {
+        return Err("Interactive exploring requires colour or plain output.".into());
+    }
+    let markup = markup(args.output);
+    let data = read_input(args)?;
+
+    let pages = match command {
+        Command::Bytes { table, .. } => {
+            let skip = table.skip.min(data.len() as u64) as usize;
+            let num_bytes = args.limit.map_or(data.len() - skip, |limit| limit.min(data.len() - skip));
+            vec![explorer::Page::from_bytes(String::from("Bytes"), &data[skip..skip + num_bytes], markup, table.row_width, args.bit_numbering)]
+        }
+        Command::WsFrame(_) => {
+            let mut frame = WebSocketFrame::try_from_bytes(&data)?;
+            frame.max_payload_bytes = args.limit;
+            apply_style(&mut frame, args);
+            vec![explorer::Page::from_frame(String::from("Frame"), frame, markup)]
+        }
+        Command::Diff(_) | Command::Theme { .. } => Vec::new(),
+        Command::WsStream { max_frames, .. } => split_frames(&data, *max_frames)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, (offset, mut frame))| {
+                frame.max_payload_bytes = args.limit;
+                apply_style(&mut frame, args);
+                let title = format!("Frame {} (offset {}, {} bytes)", i + 1, offset, frame.frame_len);
+                explorer::Page::from_frame(title, frame, markup)
+            })
+            .collect(),
+    };
+    Ok(Some(pages))
+}
+
+/// Reads and decodes the input data.
+///
+/// # Arguments
+///
+/// * `args` - The arguments naming the input file and its encoding.
+fn read_input(args: &CommonArgs) -> Result<Vec<u8>, Box<dyn Error>> {
+    read_file(args.file.as_ref(), args.input)
+}
+
+/// Reads and decodes a file (or standard input when the file is omitted or `-`).
+///
+/// # Arguments
+///
+/// * `file` - The file to read.
+/// * `input` - How the file is encoded.
+fn read_file(file: Option<&PathBuf>, input: InputArg) -> Result<Vec<u8>, Box<dyn Error>> {
+    let data = match file {
+        Some(path) if path.as_os_str() != "-" => fs::read(path)
+            .map_err(|error| format!("Unable to read {}. {}", path.display(), error))?,
+        _ => {
+            let mut buffer = Vec::new();
+            io::stdin().read_to_end(&mut buffer)?;
+            buffer
+        }
+    };
+    Ok(InputFormat::from(input).decode(&data)?)
+}
+
+/// Gets the markup for an output option.
+///
+/// # Arguments
+///
+/// * `output` - The output option.
+fn markup(output: OutputArg) -> Markup {
+    match output {
+        OutputArg::Auto | OutputArg::Json => Markup::Ansi,
+        OutputArg::Color => {
+            colored::control::set_override(true);
+            Markup::Ansi
+        }
+        OutputArg::Plain | OutputArg::Markdown | OutputArg::Rst => Markup::Plain,
+        OutputArg::Html => Markup::Html,
+    }
+}
+
+/// Gets the width the output must fit: the width option, or the width of the terminal when
+/// writing text to one (documents are never fitted to the terminal).
+///
+/// # Arguments
+///
+/// * `args` - The output options.
+fn max_width(args: &CommonArgs) -> Option<usize> {
+    match (args.width, args.output) {
+        (Some(width), _) => Some(width),
+        (None, OutputArg::Html | OutputArg::Json | OutputArg::Markdown | OutputArg::Rst) => None,
+        (None, _) => terminal_size::terminal_size().map(|(terminal_size::Width(width), _)| width as usize),
+    }
+}
+
+/// Formats bytes as a table, streaming raw input so that files of any size can be formatted.
+///
+/// # Arguments
+///
+/// * `args` - The input and output options.
+/// * `table_args` - The table layout options.
+/// * `writer` - The destination of the formatted output.
+fn write_bytes<W: Write>(args: &CommonArgs, table_args: &TableArgs, mut writer: W) -> Result<(), Box<dyn Error>> {
+    if let (InputArg::Raw, OutputArg::Auto | OutputArg::Color | OutputArg::Plain | OutputArg::Html) = (args.input, args.output) {
+        let mut list = ByteList::empty();
+        configure_list(&mut list, args, table_args);
+        let mut stream = ByteStream::new(&list);
+        stream.offset = table_args.skip;
+        stream.length = args.limit.map(|limit| limit as u64);
+        match args.file.as_ref() {
+            Some(path) if path.as_os_str() != "-" => {
+                let file = fs::File::open(path).map_err(|error| format!("Unable to read {}. {}", path.display(), error))?;
+                // Knowing the length keeps the offset and row label columns no wider than needed
+                if let Ok(metadata) = file.metadata() {
+                    let remaining = metadata.len().saturating_sub(stream.offset);
+                    stream.length = Some(stream.length.map_or(remaining, |length| length.min(remaining)));
+                }
+                stream.write(io::BufReader::new(file), &mut writer)?;
+            }
+            _ => {
+                stream.write(io::stdin().lock(), &mut writer)?;
+            }
+        }
+        writer.flush()?;
+        return Ok(());
+    }
+
+    // Encoded input (and JSON, Markdown and RST output) is handled in memory
+    let data = read_input(args)?;
+    let skip = table_args.skip.min(data.len() as u64) as usize;
+    let remaining = data.len() - skip;
+    let num_bytes = args.limit.map_or(remaining, |limit| limit.min(remaining));
+    if num_bytes < remaining {
+        eprintln!("bitformat: showing {} of {} bytes", num_bytes, remaining);
+    }
+    let shown = data[skip..skip + num_bytes].to_vec();
+    let mut list = ByteList::from_bytes(&shown);
+    configure_list(&mut list, args, table_args);
+    list.base_address = list.base_address.wrapping_add(skip as u64);
+    match args.output {
+        OutputArg::Json => writeln!(writer, "{}", list.to_json())?,
+        OutputArg::Markdown => write!(writer, "{}", list.to_markdown())?,
+        OutputArg::Rst => write!(writer, "{}", list.to_rst())?,
+        _ => write!(writer, "{}", list.format())?,
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Applies the output and table layout options to a list.
+///
+/// # Arguments
+///
+/// * `list` - The list to configure.
+/// * `args` - The output options.
+/// * `table_args` - The table layout options.
+fn configure_list(list: &mut ByteList, args: &CommonArgs, table_args: &TableArgs) {
+    list.markup = markup(args.output);
+    list.border_style = BorderStyle::from(args.border);
+    list.max_width = max_width(args);
+    list.bit_numbering = args.bit_numbering;
+    if let Some(theme) = &args.theme {
+        list.theme = theme.clone();
+    }
+    list.value_styles = table_args.value_styles.iter().map(|value_style| ValueStyle::from(*value_style)).collect();
+    list.notations = table_args.notation.iter().map(|notation| Notation::from(*notation)).collect();
+    list.row_width = table_args.row_width;
+    list.interpretations = table_args.interpret.clone();
+    list.base_address = table_args.base_address.unwrap_or(0);
+    list.annotations = table_args.annotate.clone();
+    if table_args.inline_labels {
+        list.annotation_labels = AnnotationLabels::Inline;
+    }
+    list.layout = match table_args.layout {
+        LayoutArg::Table => Layout::Table,
+        LayoutArg::Xxd => Layout::Xxd { collapse: table_args.collapse },
+        LayoutArg::Hexdump => Layout::Hexdump { collapse: !table_args.no_collapse },
+    };
+    list.offset_column = match (table_args.offsets, table_args.base_address) {
+        (Some(offsets), _) => Some(OffsetColumn::new(OffsetFormat::from(offsets), table_args.offset_digits)),
+        (None, Some(_)) => Some(OffsetColumn::new(OffsetFormat::Hex, table_args.offset_digits)),
+        (None, None) => None,
+    };
+}
+
+/// Parses an address written in decimal or in hex with a `0x` prefix (underscores are ignored).
+///
+/// # Arguments
+///
+/// * `text` - The address to parse.
+fn parse_address(text: &str) -> Result<u64, String> {
+    let digits = text.replace('_', "");
+    match digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => digits.parse::<u64>(),
+    }
+    .map_err(|_| format!("'{}' is not an address. Use a decimal number or hex with a 0x prefix.", text))
+}
+
+/// Parses a theme option: the name of a preset or the path of a TOML theme file.
+///
+/// # Arguments
+///
+/// * `text` - The theme option.
+fn parse_theme(text: &str) -> Result<Theme, String> {
+    match Theme::preset(text) {
+        Some(theme) => Ok(theme),
+        None => Theme::load(text).map_err(|error| format!("'{}' is not a preset or a theme file. {}", text, error)),
+    }
+}
+
+/// Formats a WebSocket data frame.
+///
+/// # Arguments
+///
+/// * `frame` - The frame to format.
+/// * `args` - The output options.
+fn format_frame(mut frame: WebSocketFrame, args: &CommonArgs) -> String {
+    frame.markup = markup(args.output);
+    frame.max_payload_bytes = args.limit;
+    frame.max_width = max_width(args);
+    apply_style(&mut frame, args);
+    match args.output {
+        OutputArg::Json => format!("{}\n", frame.to_json()),
+        OutputArg::Markdown => frame.to_markdown(),
+        OutputArg::Rst => frame.to_rst(),
+        _ => frame.format(),
+    }
+}
+
+/// Applies the theme, border and bit numbering options to a frame.
+///
+/// # Arguments
+///
+/// * `frame` - The frame to style.
+/// * `args` - The output options.
+fn apply_style(frame: &mut WebSocketFrame, args: &CommonArgs) {
+    if let Some(theme) = &args.theme {
+        frame.theme = theme.clone();
+    }
+    frame.border_style = BorderStyle::from(args.border);
+    frame.bit_numbering = args.bit_numbering.unwrap_or_default();
+}
+
+/// Formats a sequence of WebSocket data frames.
+///
+/// # Arguments
+///
+/// * `data` - The bytes containing the frames.
+/// * `args` - The output options.
+/// * `max_frames` - The maximum number of frames to format.
+fn format_stream(data: &[u8], args: &CommonArgs, max_frames: Option<usize>) -> Result<String, Box<dyn Error>> {
+    let frames: Vec<String> = split_frames(data, max_frames)?
+        .into_iter()
+        .enumerate()
+        .map(|(i, (offset, frame))| {
+            let frame_len = frame.frame_len;
+            let formatted = format_frame(frame, args);
+            match args.output {
+                OutputArg::Json => formatted.trim_end().to_string(),
+                // Documents need a blank line between the title and the table (or code block)
+                OutputArg::Markdown | OutputArg::Rst => format!("Frame {} (offset {}, {} bytes)\n\n{}", i + 1, offset, frame_len, formatted),
+                _ => format!("Frame {} (offset {}, {} bytes)\n{}", i + 1, offset, frame_len, formatted),
+            }
+        })
+        .collect();
+    Ok(match args.output {
+        OutputArg::Json => format!("[{}]\n", frames.join(",")),
+        _ => frames.join("\n"),
+    })
+}
+
+/// Formats a comparison of expected and actual bytes.
+///
+/// # Arguments
+///
+/// * `expected` - The expected bytes.
+/// * `actual` - The actual bytes.
+/// * `args` - The comparison options.
+fn format_diff(expected: &[u8], actual: &[u8], args: &DiffArgs) -> Result<String, Box<dyn Error>> {
+    if let OutputArg::Markdown | OutputArg::Rst = args.output {
+        return Err("Comparisons cannot be written as Markdown or reStructuredText.".into());
+    }
+    if args.ws_frame {
+        let mut diff = FrameDiff::from_bytes(expected, actual)?;
+        diff.markup = markup(args.output);
+        if let Some(theme) = &args.theme {
+            diff.theme = theme.clone();
+        }
+        return Ok(match args.output {
+            OutputArg::Json => format!("{}\n", diff.to_json()),
+            _ => diff.format(),
+        });
+    }
+    let mut diff = ByteDiff::from_bytes(expected, actual);
+    diff.table.markup = markup(args.output);
+    diff.table.notations = vec![Notation::from(args.notation)];
+    diff.table.row_width = args.row_width;
+    if let Some(theme) = &args.theme {
+        diff.table.theme = theme.clone();
+    }
+    Ok(match args.output {
+        OutputArg::Json => format!("{}\n", diff.to_json()),
+        _ => diff.format(),
+    })
+}
+
+/// Splits data into consecutive WebSocket data frames, returning each frame with its offset.
+///
+/// # Arguments
+///
+/// * `data` - The bytes containing the frames.
+/// * `max_frames` - The maximum number of frames to read.
+fn split_frames(data: &[u8], max_frames: Option<usize>) -> Result<Vec<(usize, WebSocketFrame<'_>)>, Box<dyn Error>> {
+    let mut frames = Vec::new();
+    let mut offset: usize = 0;
+    while offset < data.len() && max_frames.is_none_or(|max| frames.len() < max) {
+        let frame = WebSocketFrame::try_from_bytes(&data[offset..])
+            .map_err(|error| format!("Frame {} at offset {}: {}", frames.len() + 1, offset, error))?;
+        let frame_len = frame.frame_len;
+        frames.push((offset, frame));
+        offset += frame_len;
+    }
+    if offset < data.len() {
+        eprintln!("bitformat: ignoring {} bytes after frame {}", data.len() - offset, frames.len());
+    }
+    Ok(frames)
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(output: OutputArg) -> CommonArgs {
+        CommonArgs {
+            file: None,
+            input: InputArg::Raw,
+            output,
+            limit: None,
+            theme: None,
+            border: BorderArg::Ascii,
+            width: None,
+            bit_numbering: None,
+            #[cfg(feature = "tui")]
+            interactive: false,
+        }
+    }
+
+    #[test]
+    fn test_format_stream_json() {
+        let mut data = base64::decode("gYR7q0rdD845qQ==").unwrap();
+        data.extend_from_slice(&[0x89, 0x00]);
+
+        let formatted = format_stream(&data, &args(OutputArg::Json), None).unwrap();
+
+        assert!(formatted.starts_with("[{\"frame_length\":10,"));
+        assert!(formatted.contains("},{\"frame_length\":2,"));
+        assert!(formatted.ends_with("}]\n"));
+    }
+
+    #[test]
+    fn test_format_stream_max_frames() {
+        let mut data = base64::decode("gYR7q0rdD845qQ==").unwrap();
+        data.extend_from_slice(&data.clone());
+
+        let formatted = format_stream(&data, &args(OutputArg::Plain), Some(1)).unwrap();
+
+        assert!(formatted.starts_with("Frame 1 (offset 0, 10 bytes)\n"));
+        assert!(!formatted.contains("Frame 2"));
+    }
+
+    #[test]
+    fn test_format_stream_truncated_frame() {
+        let data = base64::decode("gYR7q0rdD845").unwrap();
+
+        let error = format_stream(&data, &args(OutputArg::Plain), None).unwrap_err();
+
+        assert_eq!("Frame 1 at offset 0: Frame truncated. Expected at least 10 bytes but found 9.", error.to_string());
+    }
+
+    #[test]
+    fn test_parse_address() {
+        assert_eq!(Ok(0x2000_0000), parse_address("0x2000_0000"));
+        assert_eq!(Ok(4096), parse_address("4096"));
+        assert!(parse_address("0xg").is_err());
+    }
+}
+
+// #endregion Unit tests
@@ -0,0 +1,331 @@
+use core::fmt::{self, Write};
+use crate::color::Color;
+use crate::prelude::*;
+use crate::theme::Style;
+
+/// The markup used when rendering formatted output.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Markup {
+    /// Coloured text using ANSI escape codes (the default with the `std` feature).
+    ///
+    /// Colours are subject to the terminal detection performed by the `colored` crate, so they are
+    /// dropped when writing to a pipe or when `NO_COLOR` is set.
+    #[cfg(feature = "std")]
+    #[default]
+    Ansi,
+    /// Uncoloured text (the default without the `std` feature).
+    #[cfg_attr(not(feature = "std"), default)]
+    Plain,
+    /// An HTML `<pre>` block with colours applied using inline styles.
+    Html,
+}
+
+impl Markup {
+    /// Wraps a fully rendered table in any enclosing markup required by this mode.
+    ///
+    /// # Arguments
+    ///
+    /// * `body` - The rendered table.
+    pub(crate) fn wrap(self, body: String) -> String {
+        match self {
+            Markup::Html => format!("{}{}{}", self.prefix(), body, self.suffix()),
+            _ => body,
+        }
+    }
+
+    /// Gets the markup written before a table that is rendered a piece at a time.
+    pub(crate) fn prefix(self) -> &'static str {
+        match self {
+            Markup::Html => "<pre class=\"bitformat\">\n",
+            _ => "",
+        }
+    }
+
+    /// Gets the markup written after a table that is rendered a piece at a time.
+    pub(crate) fn suffix(self) -> &'static str {
+        match self {
+            Markup::Html => "</pre>\n",
+            _ => "",
+        }
+    }
+}
+
+/// A piece of text with a style, rendered according to a `Markup`.
+///
+/// The text is anything that can be displayed (e.g. a `&str` or the `fmt::Arguments` built by
+/// `format_args!`), written straight into the formatter along with the escape codes or tags
+/// around it. Padding requested by the format string (e.g. `{:^15}`) is applied to the visible
+/// text, so columns line up regardless of the markup surrounding it.
+pub(crate) struct Painted<T> {
+    text: T,
+    style: Style,
+    markup: Markup,
+}
+
+/// Paints text with a style (or just a colour) for a particular markup.
+pub(crate) trait Paint: Sized {
+    fn paint<S: Into<Style>>(self, style: S, markup: Markup) -> Painted<Self>;
+}
+
+impl<T: fmt::Display> Paint for T {
+    fn paint<S: Into<Style>>(self, style: S, markup: Markup) -> Painted<T> {
+        Painted {
+            text: self,
+            style: style.into(),
+            markup,
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Painted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // The visible text is only measured when it's padded
+        let (left, right) = match f.width() {
+            Some(_) => {
+                let mut count = CharCount(0);
+                write!(count, "{}", self.text)?;
+                padding(f, count.0)
+            }
+            None => (0, 0),
+        };
+        let style = self.style;
+        match self.markup {
+            #[cfg(feature = "std")]
+            Markup::Ansi if style != Style::default() && colored::control::SHOULD_COLORIZE.should_colorize() => {
+                write!(f, "\x1b[{}m", AnsiCodes(style))?;
+                write_padded(f, &self.text, left, right)?;
+                f.write_str("\x1b[0m")
+            }
+            #[cfg(feature = "std")]
+            Markup::Ansi => write_padded(f, &self.text, left, right),
+            Markup::Plain => write_padded(f, &self.text, left, right),
+            // Unstyled text needs no span
+            Markup::Html if style == Style::default() => write_padded(&mut EscapeHtml(&mut *f), &self.text, left, right),
+            Markup::Html => {
+                write_spaces(f, left)?;
+                write!(f, "<span style=\"{}\">", CssStyle(style))?;
+                write!(EscapeHtml(&mut *f), "{}", self.text)?;
+                f.write_str("</span>")?;
+                write_spaces(f, right)
+            }
+        }
+    }
+}
+
+/// Writes text with spaces either side.
+///
+/// # Arguments
+///
+/// * `out` - The writer receiving the text.
+/// * `text` - The text.
+/// * `left` - The number of spaces before the text.
+/// * `right` - The number of spaces after the text.
+fn write_padded<W: fmt::Write, T: fmt::Display>(out: &mut W, text: &T, left: usize, right: usize) -> fmt::Result {
+    write_spaces(out, left)?;
+    write!(out, "{}", text)?;
+    write_spaces(out, right)
+}
+
+/// Writes a number of spaces.
+///
+/// # Arguments
+///
+/// * `out` - The writer receiving the spaces.
+/// * `count` - The number of spaces.
+fn write_spaces<W: fmt::Write>(out: &mut W, count: usize) -> fmt::Result {
+    const SPACES: &str = "                ";
+    let mut left = count;
+    while left > 0 {
+        let len = left.min(SPACES.len());
+        out.write_str(&SPACES[..len])?;
+        left -= len;
+    }
+    Ok(())
+}
+
+/// A writer counting the characters written to it.
+struct CharCount(usize);
+
+impl fmt::Write for CharCount {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.0 += text.chars().count();
+        Ok(())
+    }
+}
+
+/// Calculates the padding needed either side of text to honour a formatter's width and alignment.
+///
+/// # Arguments
+///
+/// * `f` - The formatter whose width and alignment are applied.
+/// * `len` - The number of visible characters in the text.
+pub(crate) fn padding(f: &fmt::Formatter, len: usize) -> (usize, usize) {
+    let total = f.width().unwrap_or(0).saturating_sub(len);
+    match f.align() {
+        Some(fmt::Alignment::Right) => (total, 0),
+        Some(fmt::Alignment::Center) => (total / 2, total - total / 2),
+        Some(fmt::Alignment::Left) | None => (0, total),
+    }
+}
+
+/// A writer escaping the characters that have special meaning in HTML before passing them on.
+pub(crate) struct EscapeHtml<'w, W: fmt::Write>(pub(crate) &'w mut W);
+
+impl<W: fmt::Write> fmt::Write for EscapeHtml<'_, W> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        // Write the runs between special characters unchanged
+        let mut start = 0;
+        for (i, c) in text.char_indices() {
+            let escaped = match c {
+                '&' => "&amp;",
+                '<' => "&lt;",
+                '>' => "&gt;",
+                '"' => "&quot;",
+                '\'' => "&#39;",
+                _ => continue,
+            };
+            self.0.write_str(&text[start..i])?;
+            self.0.write_str(escaped)?;
+            start = i + 1;
+        }
+        self.0.write_str(&text[start..])
+    }
+}
+
+/// The parameters of the ANSI escape code selecting a style (e.g. `1;31` for bold red text), in
+/// the order written by the `colored` crate.
+#[cfg(feature = "std")]
+struct AnsiCodes(Style);
+
+#[cfg(feature = "std")]
+impl fmt::Display for AnsiCodes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let style = self.0;
+        let attributes = [(style.bold, "1"), (style.dim, "2"), (style.underline, "4")];
+        let codes = attributes.iter().filter(|(set, _)| *set).map(|(_, code)| *code);
+        let color = style.color.map(|color| color.to_fg_str());
+        for (i, code) in codes.chain(color.as_deref()).enumerate() {
+            if i > 0 {
+                f.write_char(';')?;
+            }
+            f.write_str(code)?;
+        }
+        Ok(())
+    }
+}
+
+/// The inline CSS declarations matching a style.
+struct CssStyle(Style);
+
+impl fmt::Display for CssStyle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let style = self.0;
+        let mut separator = "";
+        if let Some(color) = style.color {
+            write!(f, "color:{}", CssColor(color))?;
+            separator = ";";
+        }
+        for (set, declaration) in [(style.bold, "font-weight:bold"), (style.dim, "opacity:0.6"), (style.underline, "text-decoration:underline")] {
+            if set {
+                write!(f, "{}{}", separator, declaration)?;
+                separator = ";";
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The CSS colour matching a terminal colour (using the xterm palette).
+struct CssColor(Color);
+
+impl fmt::Display for CssColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let hex = match self.0 {
+            Color::Black => "#000000",
+            Color::Red => "#cd0000",
+            Color::Green => "#00cd00",
+            Color::Yellow => "#cdcd00",
+            Color::Blue => "#0000ee",
+            Color::Magenta => "#cd00cd",
+            Color::Cyan => "#00cdcd",
+            Color::White => "#e5e5e5",
+            Color::BrightBlack => "#7f7f7f",
+            Color::BrightRed => "#ff0000",
+            Color::BrightGreen => "#00ff00",
+            Color::BrightYellow => "#ffff00",
+            Color::BrightBlue => "#5c5cff",
+            Color::BrightMagenta => "#ff00ff",
+            Color::BrightCyan => "#00ffff",
+            Color::BrightWhite => "#ffffff",
+            Color::TrueColor { r, g, b } => return write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
+        };
+        f.write_str(hex)
+    }
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_html_writer() {
+        let mut result = String::new();
+
+        write!(EscapeHtml(&mut result), "{:>6}|'\"", "<a&b>").unwrap();
+
+        assert_eq!(" &lt;a&amp;b&gt;|&#39;&quot;", result);
+    }
+
+    #[test]
+    fn test_plain_padding() {
+        assert_eq!("  ab  |", format!("{:^6}|", "ab".paint(Color::Red, Markup::Plain)));
+        assert_eq!("   ab|", format!("{:>5}|", "ab".paint(Color::Red, Markup::Plain)));
+    }
+
+    #[test]
+    fn test_html_padding_excludes_markup() {
+        assert_eq!(
+            " <span style=\"color:#cd0000\">&lt;a&gt;</span>  |",
+            format!("{:^6}|", "<a>".paint(Color::Red, Markup::Html))
+        );
+    }
+
+    #[test]
+    fn test_html_style() {
+        let style = Style::new(Color::Red).bold().underline();
+
+        assert_eq!(
+            "<span style=\"color:#cd0000;font-weight:bold;text-decoration:underline\">a</span>",
+            format!("{}", "a".paint(style, Markup::Html))
+        );
+        assert_eq!("<span style=\"opacity:0.6\">a</span>", format!("{}", "a".paint(Style::default().dim(), Markup::Html)));
+        assert_eq!(" a&lt;", format!("{:>3}", "a<".paint(Style::default(), Markup::Html)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_ansi_codes() {
+        assert_eq!("31", AnsiCodes(Style::new(Color::Red)).to_string());
+        assert_eq!("1;4;94", AnsiCodes(Style::new(Color::BrightBlue).bold().underline()).to_string());
+        assert_eq!("2", AnsiCodes(Style::default().dim()).to_string());
+    }
+
+    #[test]
+    fn test_display_padding() {
+        assert_eq!(" (129) |", format!("{:^7}|", format_args!("({})", 129).paint(Color::Red, Markup::Plain)));
+        assert_eq!(
+            "  <span style=\"color:#cd0000\">&#39;a&#39;</span>|",
+            format!("{:>5}|", format_args!("'{}'", 'a').paint(Color::Red, Markup::Html))
+        );
+    }
+
+    #[test]
+    fn test_html_wrap() {
+        assert_eq!("<pre class=\"bitformat\">\nx\n</pre>\n", Markup::Html.wrap(String::from("x\n")));
+        assert_eq!("x\n", Markup::Plain.wrap(String::from("x\n")));
+    }
+}
+
+// #endregion Unit tests
@@ -0,0 +1,6 @@
+// The `alloc` types and macros that the standard prelude provides, so that modules can be shared
+// between `std` and `no_std` builds.
+pub(crate) use alloc::format;
+pub(crate) use alloc::string::{String, ToString};
+pub(crate) use alloc::vec;
+pub(crate) use alloc::vec::Vec;
@@ -0,0 +1,212 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use crate::byte_list::ByteList;
+use crate::markup::Markup;
+use crate::websocket_frame::WebSocketFrame;
+
+/// The environment variable that writes the rendered output to the snapshot files instead of
+/// comparing against them (e.g. `BITFORMAT_BLESS=1 cargo test`).
+pub const BLESS_VAR: &str = "BITFORMAT_BLESS";
+
+/// A value rendered the same way on every machine, so it can be compared against a golden file.
+pub trait Snapshot {
+    /// Renders the value without colour codes and at full width (whatever the terminal).
+    fn snapshot(&self) -> String;
+}
+
+impl Snapshot for WebSocketFrame<'_> {
+    fn snapshot(&self) -> String {
+        let mut frame = self.clone();
+        frame.markup = Markup::Plain;
+        frame.max_width = None;
+        frame.format()
+    }
+}
+
+impl Snapshot for ByteList<'_> {
+    fn snapshot(&self) -> String {
+        let mut list = self.clone();
+        list.markup = Markup::Plain;
+        list.max_width = None;
+        list.format()
+    }
+}
+
+impl Snapshot for str {
+    fn snapshot(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Snapshot for String {
+    fn snapshot(&self) -> String {
+        self.clone()
+    }
+}
+
+/// An error encountered while comparing output against a snapshot file.
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The snapshot file does not exist yet.
+    Missing(PathBuf),
+    /// The output differs from the snapshot.
+    Mismatch {
+        /// The snapshot file.
+        path: PathBuf,
+        /// The lines removed from (`-`) and added to (`+`) the snapshot.
+        diff: String,
+    },
+    /// The snapshot file could not be read or written.
+    Io(PathBuf, io::Error),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnapshotError::Missing(path) =>
+                write!(f, "Snapshot {} does not exist. Run with {}=1 to create it.", path.display(), BLESS_VAR),
+            SnapshotError::Mismatch { path, diff } =>
+                write!(f, "Output differs from snapshot {} (run with {}=1 to update it).\n{}", path.display(), BLESS_VAR, diff),
+            SnapshotError::Io(path, error) => write!(f, "Cannot access snapshot {}. {}", path.display(), error),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+/// Compares a value against its snapshot file, or writes the file when `BITFORMAT_BLESS` is set.
+///
+/// # Arguments
+///
+/// * `path` - The snapshot file.
+/// * `value` - The value to render.
+pub fn check_snapshot<T: Snapshot + ?Sized>(path: &Path, value: &T) -> Result<(), SnapshotError> {
+    let bless = env::var_os(BLESS_VAR).is_some_and(|bless| !bless.is_empty() && bless != "0");
+    compare(path, &value.snapshot(), bless)
+}
+
+/// Compares rendered output against a snapshot file, or writes the file.
+///
+/// # Arguments
+///
+/// * `path` - The snapshot file.
+/// * `actual` - The rendered output.
+/// * `bless` - Whether to write the output to the file rather than compare.
+fn compare(path: &Path, actual: &str, bless: bool) -> Result<(), SnapshotError> {
+    if bless {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|error| SnapshotError::Io(path.to_path_buf(), error))?;
+        }
+        return fs::write(path, actual).map_err(|error| SnapshotError::Io(path.to_path_buf(), error));
+    }
+    let expected = match fs::read_to_string(path) {
+        Ok(expected) => expected,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Err(SnapshotError::Missing(path.to_path_buf())),
+        Err(error) => return Err(SnapshotError::Io(path.to_path_buf(), error)),
+    };
+    // Snapshots checked out on Windows may have gained carriage returns
+    let expected = expected.replace("\r\n", "\n");
+    if expected == actual {
+        return Ok(());
+    }
+    Err(SnapshotError::Mismatch { path: path.to_path_buf(), diff: line_diff(&expected, actual) })
+}
+
+/// Asserts that a value matches its snapshot file, panicking with a diff when it doesn't.
+///
+/// # Arguments
+///
+/// * `path` - The snapshot file.
+/// * `value` - The value to render.
+pub fn assert_snapshot<T: Snapshot + ?Sized, P: AsRef<Path>>(path: P, value: &T) {
+    if let Err(error) = check_snapshot(path.as_ref(), value) {
+        panic!("{}", error);
+    }
+}
+
+/// Asserts that a value matches the snapshot `tests/snapshots/<name>.txt` in the calling crate,
+/// panicking with a diff when it doesn't. Set `BITFORMAT_BLESS=1` to write the snapshot instead.
+///
+/// ```ignore
+/// assert_snapshot!("ping_frame", WebSocketFrame::from_bytes(&[0x89, 0x00]));
+/// ```
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($name:expr, $value:expr) => {
+        $crate::snapshot::assert_snapshot(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snapshots/", $name, ".txt"),
+            &$value,
+        )
+    };
+}
+
+/// Lists the lines of two texts, marking the lines only in the expected text with `-` and the
+/// lines only in the actual text with `+`.
+///
+/// # Arguments
+///
+/// * `expected` - The expected text.
+/// * `actual` - The actual text.
+fn line_diff(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    // The length of the longest common subsequence of the lines following each pair of positions
+    let mut common = vec![vec![0usize; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            common[i][j] = match expected[i] == actual[j] {
+                true => common[i + 1][j + 1] + 1,
+                false => common[i + 1][j].max(common[i][j + 1]),
+            };
+        }
+    }
+
+    let mut result = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && expected[i] == actual[j] {
+            result.push_str(&format!("  {}\n", expected[i]));
+            i += 1;
+            j += 1;
+        } else if j == actual.len() || (i < expected.len() && common[i + 1][j] >= common[i][j + 1]) {
+            result.push_str(&format!("- {}\n", expected[i]));
+            i += 1;
+        } else {
+            result.push_str(&format!("+ {}\n", actual[j]));
+            j += 1;
+        }
+    }
+    result
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_diff() {
+        assert_eq!("  a\n- b\n+ c\n  d\n", line_diff("a\nb\nd\n", "a\nc\nd\n"));
+        assert_eq!("  a\n+ b\n", line_diff("a\n", "a\nb\n"));
+    }
+
+    #[test]
+    fn test_compare() {
+        let path = env::temp_dir().join(format!("bitformat-snapshot-{}.txt", std::process::id()));
+        fs::write(&path, "a\nb\n").unwrap();
+
+        let result = compare(&path, "a\nc\n", false);
+        let missing = compare(&path.with_extension("missing"), "a\n", false);
+        fs::remove_file(&path).unwrap();
+
+        assert!(result.unwrap_err().to_string().ends_with("\n  a\n- b\n+ c\n"));
+        assert!(matches!(missing, Err(SnapshotError::Missing(_))));
+    }
+}
+
+// #endregion Unit tests
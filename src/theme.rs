@@ -0,0 +1,396 @@
+mod toml;
+
+use core::fmt;
+#[cfg(feature = "std")]
+use std::fs;
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::path::Path;
+use crate::color::Color;
+use crate::prelude::*;
+
+/// How a piece of text is drawn: its colour and attributes.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Style {
+    /// The foreground colour (the terminal's default colour when `None`).
+    pub color: Option<Color>,
+    /// Whether the text is bold.
+    pub bold: bool,
+    /// Whether the text is dim (faint).
+    pub dim: bool,
+    /// Whether the text is underlined.
+    pub underline: bool,
+}
+
+impl Style {
+    /// Creates a style drawing text in a colour without any attributes.
+    ///
+    /// # Arguments
+    ///
+    /// * `color` - The foreground colour.
+    pub fn new(color: Color) -> Style {
+        Style {
+            color: Some(color),
+            ..Style::default()
+        }
+    }
+
+    /// Gets this style with bold text.
+    pub fn bold(self) -> Style {
+        Style { bold: true, ..self }
+    }
+
+    /// Gets this style with dim text.
+    pub fn dim(self) -> Style {
+        Style { dim: true, ..self }
+    }
+
+    /// Gets this style with underlined text.
+    pub fn underline(self) -> Style {
+        Style { underline: true, ..self }
+    }
+}
+
+impl From<Color> for Style {
+    fn from(color: Color) -> Style {
+        Style::new(color)
+    }
+}
+
+/// The styles used for each part of a WebSocket frame diagram or a `ByteList` table.
+///
+/// Start from one of the presets (`Theme::preset`) or load a theme shared as a TOML file
+/// (`Theme::load`), which can adjust any of the roles of a preset:
+///
+/// ```toml
+/// base = "solarized"
+/// title = { color = "#fdf6e3", bold = true }
+/// highlight = 196
+///
+/// [border]
+/// dim = true
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// The lines drawn around cells.
+    pub border: Style,
+    /// The bit numbers above the diagram (and the offsets of table rows).
+    pub tick_mark: Style,
+    /// The title at the top left of the diagram (or table).
+    pub title: Style,
+    /// The byte numbers above the diagram (or table).
+    pub column_title: Style,
+    /// The DWORD numbers to the left of each row (or the row labels of a table).
+    pub dword_title: Style,
+    /// The names of fields (and the labels of table interpretations).
+    pub notes: Style,
+    /// The bits of the frame (or the binary cells of a table).
+    pub bit: Style,
+    /// The bits of the payload once unmasked.
+    pub unmasked_payload_bit: Style,
+    /// The decimal value of each payload byte (or the cells of a table in other notations).
+    pub byte_value: Style,
+    /// The values of fields (e.g. the opcode and payload characters, or table interpretations).
+    pub data_value: Style,
+    /// Highlighted bits (e.g. the bits that differ in a `FrameDiff`).
+    pub highlight: Style,
+}
+
+impl Theme {
+    /// The names of the preset themes.
+    pub const PRESETS: [&'static str; 7] = ["dark", "light", "solarized", "high-contrast", "colour-blind", "monochrome-bold", "plain"];
+
+    /// Gets a preset theme by name, or `None` if there is no such preset (see `Theme::PRESETS`).
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the preset (e.g. `solarized`).
+    pub fn preset(name: &str) -> Option<Theme> {
+        match name.to_lowercase().replace('_', "-").as_str() {
+            "dark" => Some(Theme::dark()),
+            "light" => Some(Theme::light()),
+            "solarized" => Some(Theme::solarized()),
+            "high-contrast" => Some(Theme::high_contrast()),
+            "colour-blind" | "color-blind" => Some(Theme::colour_blind()),
+            "monochrome-bold" => Some(Theme::monochrome_bold()),
+            "plain" => Some(Theme::plain()),
+            _ => None,
+        }
+    }
+
+    /// The default theme, using the 16 ANSI colours on a dark background.
+    pub fn dark() -> Theme {
+        Theme {
+            border: Style::new(Color::Cyan),
+            tick_mark: Style::new(Color::Green),
+            title: Style::new(Color::White),
+            column_title: Style::new(Color::Green),
+            dword_title: Style::new(Color::Green),
+            notes: Style::new(Color::Magenta),
+            bit: Style::new(Color::White),
+            unmasked_payload_bit: Style::new(Color::Yellow),
+            byte_value: Style::new(Color::Blue),
+            data_value: Style::new(Color::Red),
+            highlight: Style::new(Color::BrightRed),
+        }
+    }
+
+    /// A theme using the 16 ANSI colours on a light background.
+    pub fn light() -> Theme {
+        Theme {
+            border: Style::new(Color::Blue),
+            tick_mark: Style::new(Color::Green),
+            title: Style::new(Color::Black).bold(),
+            column_title: Style::new(Color::Green),
+            dword_title: Style::new(Color::Green),
+            notes: Style::new(Color::Magenta),
+            bit: Style::new(Color::Black),
+            unmasked_payload_bit: Style::new(Color::Blue),
+            byte_value: Style::new(Color::BrightBlack),
+            data_value: Style::new(Color::Red),
+            highlight: Style::new(Color::BrightRed).bold(),
+        }
+    }
+
+    /// The Solarized palette in true colour (for a dark background).
+    pub fn solarized() -> Theme {
+        Theme {
+            border: Style::new(rgb(0x586e75)),
+            tick_mark: Style::new(rgb(0x859900)),
+            title: Style::new(rgb(0x93a1a1)),
+            column_title: Style::new(rgb(0x268bd2)),
+            dword_title: Style::new(rgb(0x268bd2)),
+            notes: Style::new(rgb(0x6c71c4)),
+            bit: Style::new(rgb(0x839496)),
+            unmasked_payload_bit: Style::new(rgb(0xb58900)),
+            byte_value: Style::new(rgb(0x2aa198)),
+            data_value: Style::new(rgb(0xcb4b16)),
+            highlight: Style::new(rgb(0xdc322f)).bold(),
+        }
+    }
+
+    /// A theme using bright colours and bold titles.
+    pub fn high_contrast() -> Theme {
+        Theme {
+            border: Style::new(Color::BrightWhite),
+            tick_mark: Style::new(Color::BrightYellow),
+            title: Style::new(Color::BrightWhite).bold(),
+            column_title: Style::new(Color::BrightCyan).bold(),
+            dword_title: Style::new(Color::BrightCyan).bold(),
+            notes: Style::new(Color::BrightMagenta),
+            bit: Style::new(Color::BrightWhite),
+            unmasked_payload_bit: Style::new(Color::BrightYellow).bold(),
+            byte_value: Style::new(Color::BrightCyan),
+            data_value: Style::new(Color::BrightGreen),
+            highlight: Style::new(Color::BrightRed).bold().underline(),
+        }
+    }
+
+    /// A theme using the Okabe-Ito palette, which avoids pairs of colours that are hard to tell
+    /// apart with common colour vision deficiencies. Highlights are also underlined.
+    pub fn colour_blind() -> Theme {
+        Theme {
+            border: Style::new(rgb(0x56b4e9)),
+            tick_mark: Style::new(rgb(0x009e73)),
+            title: Style::new(Color::White),
+            column_title: Style::new(rgb(0x009e73)),
+            dword_title: Style::new(rgb(0x009e73)),
+            notes: Style::new(rgb(0xcc79a7)),
+            bit: Style::new(Color::White),
+            unmasked_payload_bit: Style::new(rgb(0xf0e442)),
+            byte_value: Style::new(rgb(0x56b4e9)),
+            data_value: Style::new(rgb(0xe69f00)),
+            highlight: Style::new(rgb(0xd55e00)).bold().underline(),
+        }
+    }
+
+    /// A theme without colours, distinguishing roles using bold, dim and underlined text.
+    pub fn monochrome_bold() -> Theme {
+        let plain = Style::default();
+        Theme {
+            border: plain.dim(),
+            tick_mark: plain.dim(),
+            title: plain.bold(),
+            column_title: plain.bold(),
+            dword_title: plain.bold(),
+            notes: plain,
+            bit: plain,
+            unmasked_payload_bit: plain.bold(),
+            byte_value: plain.dim(),
+            data_value: plain,
+            highlight: plain.bold().underline(),
+        }
+    }
+
+    /// A theme without any colours or attributes (the default for `ByteList` tables).
+    pub fn plain() -> Theme {
+        let plain = Style::default();
+        Theme {
+            border: plain,
+            tick_mark: plain,
+            title: plain,
+            column_title: plain,
+            dword_title: plain,
+            notes: plain,
+            bit: plain,
+            unmasked_payload_bit: plain,
+            byte_value: plain,
+            data_value: plain,
+            highlight: plain,
+        }
+    }
+
+    /// Reads a theme from TOML text.
+    ///
+    /// The text is parsed by the `toml` crate, so any TOML syntax may be used (e.g. `[role]` tables,
+    /// dotted keys or escaped strings). Each role is set to a colour (a name such as `bright-red`,
+    /// `#rrggbb` or a 256-colour index) or to a table of `color`, `bold`, `dim` and `underline`
+    /// values. Roles that are not set are taken from the preset named by `base` (`dark` by default),
+    /// wherever `base` is written.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The TOML text.
+    pub fn from_toml(text: &str) -> Result<Theme, ThemeError> {
+        toml::parse(text)
+    }
+
+    /// Writes the theme as TOML text that `from_toml` reads back.
+    pub fn to_toml(self: &Theme) -> String {
+        toml::write(self)
+    }
+
+    /// Loads a theme from a TOML file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to read.
+    #[cfg(feature = "std")]
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Theme, ThemeError> {
+        let text = fs::read_to_string(path).map_err(ThemeError::Io)?;
+        Theme::from_toml(&text)
+    }
+
+    /// Saves the theme to a TOML file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The file to write.
+    #[cfg(feature = "std")]
+    pub fn save<P: AsRef<Path>>(self: &Theme, path: P) -> io::Result<()> {
+        fs::write(path, self.to_toml())
+    }
+
+    /// Lists the roles of the theme with their names in TOML files.
+    pub(crate) fn roles(self: &Theme) -> [(&'static str, Style); 11] {
+        [
+            ("border", self.border),
+            ("tick_mark", self.tick_mark),
+            ("title", self.title),
+            ("column_title", self.column_title),
+            ("dword_title", self.dword_title),
+            ("notes", self.notes),
+            ("bit", self.bit),
+            ("unmasked_payload_bit", self.unmasked_payload_bit),
+            ("byte_value", self.byte_value),
+            ("data_value", self.data_value),
+            ("highlight", self.highlight),
+        ]
+    }
+
+    /// Gets a role by its name in TOML files.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the role (e.g. `tick_mark`).
+    pub(crate) fn role_mut(self: &mut Theme, name: &str) -> Option<&mut Style> {
+        match name {
+            "border" => Some(&mut self.border),
+            "tick_mark" => Some(&mut self.tick_mark),
+            "title" => Some(&mut self.title),
+            "column_title" => Some(&mut self.column_title),
+            "dword_title" => Some(&mut self.dword_title),
+            "notes" => Some(&mut self.notes),
+            "bit" => Some(&mut self.bit),
+            "unmasked_payload_bit" => Some(&mut self.unmasked_payload_bit),
+            "byte_value" => Some(&mut self.byte_value),
+            "data_value" => Some(&mut self.data_value),
+            "highlight" => Some(&mut self.highlight),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::dark()
+    }
+}
+
+/// An error encountered while loading a theme.
+#[derive(Debug)]
+pub enum ThemeError {
+    /// The theme file could not be read.
+    #[cfg(feature = "std")]
+    Io(io::Error),
+    /// The theme is not valid.
+    Invalid {
+        /// The number of the line holding the error (starting from 1).
+        line: usize,
+        /// A description of the error.
+        message: String,
+    },
+}
+
+impl fmt::Display for ThemeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            ThemeError::Io(error) => write!(f, "Unable to read theme. {}", error),
+            ThemeError::Invalid { line, message } => write!(f, "Invalid theme on line {}. {}", line, message),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ThemeError {}
+
+/// Gets the colour written as `0xrrggbb`.
+///
+/// # Arguments
+///
+/// * `hex` - The red, green and blue components.
+fn rgb(hex: u32) -> Color {
+    Color::TrueColor {
+        r: (hex >> 16) as u8,
+        g: (hex >> 8) as u8,
+        b: hex as u8,
+    }
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        for name in Theme::PRESETS.iter() {
+            assert!(Theme::preset(name).is_some(), "{}", name);
+        }
+        assert_eq!(Some(Theme::colour_blind()), Theme::preset("Color_Blind"));
+        assert_eq!(None, Theme::preset("neon"));
+    }
+
+    #[test]
+    fn test_style_builders() {
+        let style = Style::new(Color::Red).bold().underline();
+
+        assert_eq!(Some(Color::Red), style.color);
+        assert!(style.bold && style.underline && !style.dim);
+    }
+}
+
+// #endregion Unit tests
@@ -0,0 +1,260 @@
+use core::fmt::Write;
+use core::ops::Range;
+use core::str::FromStr;
+use ::toml::de::{DeTable, DeValue};
+use crate::color::Color;
+use super::{Style, Theme, ThemeError};
+use crate::prelude::*;
+
+/// Reads a theme from TOML text, parsed by the `toml` crate.
+///
+/// # Arguments
+///
+/// * `text` - The TOML text.
+pub(super) fn parse(text: &str) -> Result<Theme, ThemeError> {
+    let invalid = |span: Range<usize>, message: String| ThemeError::Invalid { line: line_number(text, span.start), message };
+    let document = DeTable::parse(text)
+        .map_err(|error| invalid(error.span().unwrap_or(0..0), sentence(error.message())))?
+        .into_inner();
+
+    // The base is applied first, so that roles adjust it wherever they are written
+    let mut theme = Theme::default();
+    if let Some((_, base)) = document.iter().find(|(key, _)| key.get_ref() == "base") {
+        theme = match base.get_ref() {
+            DeValue::String(name) => Theme::preset(name).ok_or_else(|| {
+                invalid(base.span(), format!("`{}` is not a preset. Use one of: {}.", name, Theme::PRESETS.join(", ")))
+            })?,
+            _ => return Err(invalid(base.span(), String::from("`base` must be the name of a preset."))),
+        };
+    }
+    for (key, value) in document.iter().filter(|(key, _)| key.get_ref() != "base") {
+        let role: &str = key.get_ref();
+        let style = theme.role_mut(role).ok_or_else(|| invalid(key.span(), format!("`{}` is not a role.", role)))?;
+        match value.get_ref() {
+            DeValue::Table(attributes) => {
+                for (key, value) in attributes.iter() {
+                    set_attribute(style, key.get_ref(), value.get_ref()).map_err(|message| invalid(key.span(), message))?;
+                }
+            }
+            color => style.color = parse_color(color).map_err(|message| invalid(value.span(), message))?,
+        }
+    }
+    Ok(theme)
+}
+
+/// Writes a theme as TOML, with an inline table per role.
+///
+/// # Arguments
+///
+/// * `theme` - The theme to write.
+pub(super) fn write(theme: &Theme) -> String {
+    let mut result = String::from("# bitformat theme\n");
+    for (name, style) in theme.roles().iter() {
+        let mut attributes = vec![format!("color = \"{}\"", style.color.map_or(String::from("default"), color_name))];
+        for (attribute, set) in [("bold", style.bold), ("dim", style.dim), ("underline", style.underline)].iter() {
+            if *set {
+                attributes.push(format!("{} = true", attribute));
+            }
+        }
+        writeln!(result, "{} = {{ {} }}", name, attributes.join(", ")).expect("Writing to a string cannot fail.");
+    }
+    result
+}
+
+/// Sets an attribute of a style.
+///
+/// # Arguments
+///
+/// * `style` - The style to change.
+/// * `key` - The name of the attribute.
+/// * `value` - The value of the attribute.
+fn set_attribute(style: &mut Style, key: &str, value: &DeValue) -> Result<(), String> {
+    let flag = match key {
+        "color" | "colour" => {
+            style.color = parse_color(value)?;
+            return Ok(());
+        }
+        "bold" => &mut style.bold,
+        "dim" => &mut style.dim,
+        "underline" => &mut style.underline,
+        _ => return Err(format!("`{}` is not an attribute. Use color, bold, dim or underline.", key)),
+    };
+    match value {
+        DeValue::Boolean(set) => {
+            *flag = *set;
+            Ok(())
+        }
+        _ => Err(format!("`{}` must be true or false.", key)),
+    }
+}
+
+/// Parses a colour written as a name (e.g. `bright-red`), as `#rrggbb`, as a 256-colour index or as
+/// `default` for the terminal's own colour.
+///
+/// # Arguments
+///
+/// * `value` - The colour.
+fn parse_color(value: &DeValue) -> Result<Option<Color>, String> {
+    match value {
+        DeValue::Integer(index) => u8::from_str_radix(index.as_str(), index.radix())
+            .map(|index| Some(indexed_color(index)))
+            .map_err(|_| format!("{} is not a 256-colour index (0 to 255).", index)),
+        DeValue::String(name) => {
+            if name == "default" {
+                return Ok(None);
+            }
+            if let Some(hex) = name.strip_prefix('#') {
+                return match u32::from_str_radix(hex, 16) {
+                    Ok(rgb) if hex.len() == 6 => Ok(Some(super::rgb(rgb))),
+                    _ => Err(format!("`{}` is not a colour. Use #rrggbb.", name)),
+                };
+            }
+            Color::from_str(&name.replace(['-', '_'], " "))
+                .map(Some)
+                .map_err(|_| format!("`{}` is not a colour.", name))
+        }
+        _ => Err(String::from("A colour must be a name, #rrggbb or a 256-colour index.")),
+    }
+}
+
+/// Gets the name of a colour as written in theme files.
+///
+/// # Arguments
+///
+/// * `color` - The colour.
+fn color_name(color: Color) -> String {
+    match color {
+        Color::TrueColor { r, g, b } => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        color => format!("{:?}", color)
+            .char_indices()
+            .fold(String::new(), |mut name, (i, c)| {
+                if i > 0 && c.is_uppercase() {
+                    name.push('-');
+                }
+                name.push(c.to_ascii_lowercase());
+                name
+            }),
+    }
+}
+
+/// Gets a colour from the xterm 256-colour palette.
+///
+/// The first 16 colours are the ANSI colours (drawn from the terminal's own palette). The rest are
+/// drawn in true colour.
+///
+/// # Arguments
+///
+/// * `index` - The index of the colour in the palette.
+fn indexed_color(index: u8) -> Color {
+    const ANSI: [Color; 16] = [
+        Color::Black, Color::Red, Color::Green, Color::Yellow, Color::Blue, Color::Magenta, Color::Cyan, Color::White,
+        Color::BrightBlack, Color::BrightRed, Color::BrightGreen, Color::BrightYellow, Color::BrightBlue, Color::BrightMagenta,
+        Color::BrightCyan, Color::BrightWhite,
+    ];
+    const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match index {
+        0..=15 => ANSI[index as usize],
+        16..=231 => {
+            let cube = index - 16;
+            Color::TrueColor {
+                r: LEVELS[(cube / 36) as usize],
+                g: LEVELS[(cube / 6 % 6) as usize],
+                b: LEVELS[(cube % 6) as usize],
+            }
+        }
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            Color::TrueColor { r: level, g: level, b: level }
+        }
+    }
+}
+
+/// Writes a message of the `toml` crate (e.g. `duplicate key`) as a sentence, like the other errors.
+///
+/// # Arguments
+///
+/// * `message` - The message.
+fn sentence(message: &str) -> String {
+    let message = message.trim_end().trim_end_matches('.');
+    let mut chars = message.chars();
+    match chars.next() {
+        Some(first) => format!("{}{}.", first.to_uppercase(), chars.as_str()),
+        None => String::from("Invalid TOML."),
+    }
+}
+
+/// Gets the number of the line (starting from 1) holding a position in the text.
+///
+/// # Arguments
+///
+/// * `text` - The text.
+/// * `offset` - The position (in bytes).
+fn line_number(text: &str, offset: usize) -> usize {
+    text.as_bytes()[..offset.min(text.len())].iter().filter(|&&byte| byte == b'\n').count() + 1
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        for name in Theme::PRESETS.iter() {
+            let theme = Theme::preset(name).unwrap();
+
+            assert_eq!(theme, parse(&write(&theme)).unwrap(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let text = "# House style\nbase = \"light\"\ntitle = \"bright-blue\" # shorthand\n\
+                    notes = { color = \"#ff8000\", underline = true }\nhighlight = 0xc4\nbit.dim = true\n\n\
+                    [border]\ndim = true\n";
+
+        let theme = parse(text).unwrap();
+
+        assert_eq!(Style::new(Color::BrightBlue).bold(), theme.title);
+        assert_eq!(Style::new(Color::TrueColor { r: 255, g: 128, b: 0 }).underline(), theme.notes);
+        assert_eq!(Style::new(Color::TrueColor { r: 255, g: 0, b: 0 }).bold(), theme.highlight);
+        assert_eq!(Style::new(Color::Blue).dim(), theme.border);
+        assert_eq!(Theme::light().bit.dim(), theme.bit);
+        assert_eq!(Theme::light().byte_value, theme.byte_value);
+    }
+
+    #[test]
+    fn test_base_after_roles() {
+        let theme = parse("bit = \"red\"\nbase = \"light\"").unwrap();
+
+        assert_eq!(Some(Color::Red), theme.bit.color);
+        assert_eq!(Theme::light().title, theme.title);
+    }
+
+    #[test]
+    fn test_indexed_color() {
+        assert_eq!(Color::BrightRed, indexed_color(9));
+        assert_eq!(Color::TrueColor { r: 0, g: 135, b: 255 }, indexed_color(33));
+        assert_eq!(Color::TrueColor { r: 238, g: 238, b: 238 }, indexed_color(255));
+    }
+
+    #[test]
+    fn test_errors() {
+        let line = |text: &str| match parse(text) {
+            Err(ThemeError::Invalid { line, .. }) => line,
+            other => panic!("Expected an error but got {:?}", other),
+        };
+
+        assert_eq!(1, line("sparkle = \"red\""));
+        assert_eq!(2, line("\ntitle = { color = \"mauve\" }"));
+        assert_eq!(1, line("bit = 256"));
+        assert_eq!(2, line("[bit]\nbold = \"yes\""));
+        assert_eq!(2, line("[bit]\nbase = \"dark\""));
+        assert_eq!(1, line("title"));
+        assert_eq!(3, line("bit = \"red\"\n\nbit = \"blue\""));
+        assert_eq!(1, line("notes = 1.5"));
+    }
+}
+
+// #endregion Unit tests
@@ -0,0 +1,164 @@
+use core::fmt;
+use wasm_bindgen::prelude::*;
+use crate::byte_list::{ByteList, RowWidth};
+use crate::input::InputFormat;
+use crate::markup::Markup;
+use crate::prelude::*;
+use crate::websocket_frame::WebSocketFrame;
+
+/// Decodes input text (such as a pasted base64 frame) into bytes.
+///
+/// Whitespace around text formats (e.g. a trailing newline) is ignored, while raw input is decoded
+/// as it is, since spaces and newlines are bytes of the data.
+///
+/// # Arguments
+///
+/// * `input` - The input text.
+/// * `input_format` - The name of the input format (base64 when `None`).
+fn decode(input: &str, input_format: Option<String>) -> Result<Vec<u8>, String> {
+    let input_format = match input_format {
+        Some(name) => name.parse::<InputFormat>()?,
+        None => InputFormat::Base64,
+    };
+    let input = match input_format {
+        InputFormat::Raw => input,
+        InputFormat::Hex | InputFormat::Base64 | InputFormat::CArray => input.trim(),
+    };
+    input_format.decode(input.as_bytes()).map_err(|error| error.to_string())
+}
+
+/// Parses input text as a complete WebSocket frame.
+///
+/// # Arguments
+///
+/// * `data` - The decoded bytes.
+fn parse_frame(data: &[u8]) -> Result<WebSocketFrame<'_>, String> {
+    WebSocketFrame::try_from_bytes(data).map_err(|error| error.to_string())
+}
+
+/// Builds the list shown for input bytes.
+///
+/// # Arguments
+///
+/// * `data` - The decoded bytes.
+/// * `row_width` - The name or number of bytes of each row (a QWORD when `None`).
+fn byte_list(data: &[u8], row_width: Option<String>) -> Result<ByteList<'_>, String> {
+    let mut list = ByteList::from_bytes(data);
+    list.markup = Markup::Html;
+    if let Some(row_width) = row_width {
+        list.row_width = row_width.parse::<RowWidth>()?;
+    }
+    Ok(list)
+}
+
+/// Converts an error message into an exception thrown in JavaScript.
+///
+/// # Arguments
+///
+/// * `message` - The error message.
+fn js_error<M: fmt::Display>(message: M) -> JsError {
+    JsError::new(&message.to_string())
+}
+
+/// Formats input bytes as an HTML table (a `<pre>` block with inline styles).
+///
+/// # Arguments
+///
+/// * `input` - The input text.
+/// * `input_format` - `raw`, `hex`, `base64` (the default) or `c-array`.
+/// * `row_width` - The name or number of bytes of each row (e.g. `dword` or `4`).
+#[wasm_bindgen(js_name = formatBytes)]
+pub fn format_bytes(input: &str, input_format: Option<String>, row_width: Option<String>) -> Result<String, JsError> {
+    let data = decode(input, input_format).map_err(js_error)?;
+    Ok(byte_list(&data, row_width).map_err(js_error)?.format())
+}
+
+/// Formats an input WebSocket frame as an HTML diagram (a `<pre>` block with inline styles).
+///
+/// # Arguments
+///
+/// * `input` - The input text.
+/// * `input_format` - `raw`, `hex`, `base64` (the default) or `c-array`.
+#[wasm_bindgen(js_name = formatWebSocketFrame)]
+pub fn format_web_socket_frame(input: &str, input_format: Option<String>) -> Result<String, JsError> {
+    let data = decode(input, input_format).map_err(js_error)?;
+    let mut frame = parse_frame(&data).map_err(js_error)?;
+    frame.markup = Markup::Html;
+    Ok(frame.format())
+}
+
+/// Exports input bytes as a JSON object listing the bytes in each row.
+///
+/// # Arguments
+///
+/// * `input` - The input text.
+/// * `input_format` - `raw`, `hex`, `base64` (the default) or `c-array`.
+/// * `row_width` - The name or number of bytes of each row (e.g. `dword` or `4`).
+#[wasm_bindgen(js_name = bytesToJson)]
+pub fn bytes_to_json(input: &str, input_format: Option<String>, row_width: Option<String>) -> Result<String, JsError> {
+    let data = decode(input, input_format).map_err(js_error)?;
+    Ok(byte_list(&data, row_width).map_err(js_error)?.to_json())
+}
+
+/// Exports an input WebSocket frame as a JSON object holding its fields.
+///
+/// # Arguments
+///
+/// * `input` - The input text.
+/// * `input_format` - `raw`, `hex`, `base64` (the default) or `c-array`.
+#[wasm_bindgen(js_name = webSocketFrameToJson)]
+pub fn web_socket_frame_to_json(input: &str, input_format: Option<String>) -> Result<String, JsError> {
+    let data = decode(input, input_format).map_err(js_error)?;
+    Ok(parse_frame(&data).map_err(js_error)?.to_json())
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_web_socket_frame() {
+        let html = format_web_socket_frame("gYR7q0rdD845qQ==\n", None).unwrap();
+
+        assert!(html.starts_with("<pre class=\"bitformat\">\n"));
+        assert!(html.contains("Masking-key"));
+        // Payloads are escaped, so the diagram can be inserted into a page
+        let html = format_web_socket_frame("gQM8Yj4=", None).unwrap();
+        assert!(html.contains("&#39;&lt;&#39;"));
+        assert!(!html.contains("<b"));
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        let html = format_bytes("81 84", Some(String::from("hex")), Some(String::from("word"))).unwrap();
+
+        assert!(html.starts_with("<pre class=\"bitformat\">\n"));
+        assert!(html.contains("WORD"));
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert!(web_socket_frame_to_json("gYR7q0rdD845qQ==", None).unwrap().contains("\"payload_text\":\"test\""));
+        assert!(bytes_to_json("{ 1, 2 }", Some(String::from("c-array")), None).unwrap().contains("\"bytes\":[1,2]"));
+    }
+
+    #[test]
+    fn test_decode_whitespace() {
+        assert_eq!(Ok(vec![0x81, 0x84]), decode(" 81 84\n", Some(String::from("hex"))));
+        // Raw input keeps its whitespace
+        assert_eq!(Ok(b" hi\n".to_vec()), decode(" hi\n", Some(String::from("raw"))));
+    }
+
+    #[test]
+    fn test_errors() {
+        // Errors are checked before they become exceptions, which need a JavaScript host
+        assert_eq!(Err(String::from("'octal' is not an input format. Use raw, hex, base64 or c-array.")), decode("", Some(String::from("octal"))));
+        assert!(decode("not base64!", None).is_err());
+        assert!(parse_frame(&[0x81, 0x84, 0x7b]).is_err());
+        assert!(byte_list(&[], Some(String::from("0"))).is_err());
+    }
+}
+
+// #endregion Unit tests
@@ -1,57 +1,53 @@
+mod compact;
 mod websocket_opcode;
 
-use std::convert::TryInto;
-use colored::{Colorize, Color};
+use alloc::borrow::Cow;
+use alloc::collections::BTreeSet;
+use core::convert::TryInto;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+use crate::bit_numbering::{BitNumbering, BitSpan};
+use crate::border::BorderStyle;
+use crate::doc_table::DocTable;
+use crate::json;
+use crate::logging::PlainText;
+use crate::markup::{padding, Markup, Paint, Painted};
+use crate::theme::{Style, Theme};
 use websocket_opcode::WebSocketOpCode;
+use crate::prelude::*;
 
 const BITS_IN_BYTE: usize = 8;
 const BYTES_IN_DWORD: usize = 4;
 
-pub struct FormatStyle {
-    pub border_color: Color,
-    pub tick_mark_color: Color,
-    pub title_color: Color,
-    pub column_title_color: Color,
-    pub dword_title_color: Color,
-    pub notes_color: Color,
-    pub bit_color: Color,
-    pub unmasked_payload_bit_color: Color,
-    pub byte_value_color: Color,
-    pub data_value_color: Color,
-    pub summary_title_color: Color,
-    pub summary_value_color: Color,
-}
-
-impl FormatStyle {
-    pub fn new() -> FormatStyle {
-        FormatStyle {
-            border_color: Color::Cyan,
-            tick_mark_color: Color::Green,
-            title_color: Color::White,
-            column_title_color: Color::Green,
-            dword_title_color: Color::Green,
-            notes_color: Color::Magenta,
-            bit_color: Color::White,
-            unmasked_payload_bit_color: Color::Yellow,
-            byte_value_color: Color::Blue,
-            data_value_color: Color::Red,
-            summary_title_color: Color::Magenta,
-            summary_value_color: Color::Red,
-        }
-    }
-}
+// The positions of the walls in each part of the diagram, counted from the left edge of the DWORD
+// rows, which are used to join the walls of adjacent lines with the right border characters
+const HEADER_WALLS: [usize; 5] = [8, 24, 40, 56, 72];
+// The walls of the first two bytes of DWORD 1 (the flags, opcode and payload length code)
+const FIRST_DWORD_WALLS: [usize; 9] = [0, 8, 10, 12, 14, 16, 24, 26, 40];
 
 /// The length of a WebSocket data frame payload.
 #[derive(Debug)]
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq)]
 pub enum PayloadLength {
     Short(u8),
     Medium(u16),
     Long(u64)
 }
 
-impl std::fmt::Display for PayloadLength {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl PayloadLength {
+    /// Gets the number of bytes in the payload.
+    pub fn num_bytes(&self) -> u64 {
+        match *self {
+            PayloadLength::Short(length) => length.into(),
+            PayloadLength::Medium(length) => length.into(),
+            PayloadLength::Long(length) => length,
+        }
+    }
+}
+
+impl fmt::Display for PayloadLength {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let out = match *self {
             PayloadLength::Short(length) => format!("Short ({0} bytes)", length),
             PayloadLength::Medium(length) => format!("Medium ({0} bytes)", length),
@@ -61,11 +57,66 @@ impl std::fmt::Display for PayloadLength {
     }
 }
 
+/// An error encountered while parsing a WebSocket data frame.
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum FrameError {
+    /// The data ends before the frame does.
+    Truncated {
+        /// The number of bytes needed to reach the end of the part of the frame being read.
+        expected: usize,
+        /// The number of bytes available.
+        actual: usize,
+    },
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            FrameError::Truncated { expected, actual } => 
+                write!(f, "Frame truncated. Expected at least {} bytes but found {}.", expected, actual),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FrameError {}
+
+/// A named field within a WebSocket data frame.
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct FrameField {
+    /// The name of the field.
+    pub name: String,
+    /// The offset of the field's first bit from the start of the frame.
+    pub bit_offset: usize,
+    /// The number of bits in the field.
+    pub bit_len: usize,
+    /// The value of the field's bits as they appear in the frame.
+    pub value: u64,
+    /// The value of a payload byte once unmasked (`None` for fields outside the payload).
+    pub unmasked_value: Option<u8>,
+}
+
+#[derive(Clone)]
 pub struct WebSocketFrame<'a> {
-    pub frame_len: u8,
+    pub frame_len: usize,
     pub is_payload_masked: bool,
     pub payload_length: PayloadLength,
-    pub format_style: FormatStyle,
+    /// The styles used for each part of the diagram.
+    pub theme: Theme,
+    /// The characters used to draw the borders.
+    pub border_style: BorderStyle,
+    pub markup: Markup,
+    pub max_payload_bytes: Option<usize>,
+    /// The width the diagram must fit (e.g. the width of the terminal). Narrower diagrams switch to
+    /// a compact layout with 16-bit (or 8-bit) rows.
+    pub max_width: Option<usize>,
+    /// How the bits are numbered in the tick marks above the rows (MSB-0 across each row by default,
+    /// as in RFC 6455).
+    pub bit_numbering: BitNumbering,
+    /// Bits (numbered from the start of the frame) drawn in the highlight style.
+    pub highlighted_bits: BTreeSet<usize>,
     fin_bit: bool,
     rsv1: bool,
     rsv2: bool,
@@ -76,22 +127,44 @@ pub struct WebSocketFrame<'a> {
     payload_length_code: u8,
     payload_length_bytes: Vec<u8>,
     masking_key: [u8; 4],
-    masked_payload: &'a [u8],
+    /// The payload as it appears in the frame, borrowed from the parsed bytes until `into_owned`.
+    masked_payload: Cow<'a, [u8]>,
     unmasked_payload: Vec<u8>,
     payload_chars: Vec<char>,
 }
 
 impl<'a> WebSocketFrame<'a> {
+    /// The longest frame (in bytes) that `FrameCodec` and `FrameTap` buffer by default (16 MiB).
+    pub const DEFAULT_MAX_LEN: usize = 16 * 1024 * 1024;
+
     /// Builds a websocket frame from a byte array
     ///
+    /// Panics if the bytes do not contain a complete frame. Use `try_from_bytes` to handle
+    /// incomplete data.
+    ///
     /// # Arguments
     ///
     /// * `data` - The byte array to convert to a `WebSocketFrame`.
-    pub fn from_bytes(data: &Vec<u8>) -> WebSocketFrame {
+    pub fn from_bytes(data: &'a [u8]) -> WebSocketFrame<'a> {
+        match WebSocketFrame::try_from_bytes(data) {
+            Ok(frame) => frame,
+            Err(error) => panic!("ERROR: {}", error),
+        }
+    }
+
+    /// Builds a websocket frame from the start of a byte array.
+    ///
+    /// Any bytes following the end of the frame are ignored, so a sequence of frames can be read
+    /// by advancing through the data by `frame_len` bytes at a time.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The byte array to read a `WebSocketFrame` from.
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<WebSocketFrame<'a>, FrameError> {
         const NUM_MASK_BYTES: usize = 4;
 
-        // Get frame length
-        let frame_length: usize = data.len();
+        // The first two bytes contain the flags, opcode, mask bit and payload length code
+        check_length(data, 2)?;
 
         // Get the opcode bit values
         let opcode_bits = get_bits_from_byte(data[0], 0b00001111);
@@ -101,45 +174,42 @@ impl<'a> WebSocketFrame<'a> {
 
         // Get the payload length code (bits 9 - 15)
         let payload_length_code: u8 = get_bits_from_byte(data[1], 0b01111111);
-        
-        // Assemble extension data
-        let mut extension_data: Vec<u8> = Vec::new();
-        for ix in 0..8 {
-            if data.len() > ix + 2 {
-                extension_data.push(data[ix + 2]);
-            }
-        }
 
-        // Calculate payload length
-        let payload_length = WebSocketFrame::get_payload_length(payload_length_code, extension_data);
-
-        // TODO: Handle larger payloads and unmasked payloads
-        let payload_start_index: usize = 
-            match payload_length {
-                // Short payload begin at byte 6 (first 2 plus 4 for masking key)
-                PayloadLength::Short(_) => 6,
-                // Medium payload begin at byte 8 (first 2 plus 2 for 16-bit payload length plus 4 for masking key)                
-                PayloadLength::Medium(_) => 8,
-                // Long payload begin at byte 14 (first 2 plus 8 for 64-bit payload length plus 4 for masking key)                
-                PayloadLength::Long(_) => 14,
+        // Get the extension bytes holding medium (16-bit) and long (64-bit) payload lengths
+        let num_extension_bytes: usize =
+            match payload_length_code {
+                126 => 2,
+                127 => 8,
+                _ => 0,
             };
-
-        // Get the byte values describing payload length
+        let masking_key_index: usize = 2 + num_extension_bytes;
+        check_length(data, masking_key_index)?;
         let payload_length_bytes: Vec<u8> = 
-            match payload_length {
-                PayloadLength::Short(_) => vec!(payload_length_code),
-                PayloadLength::Medium(_) => vec!(data[2], data[3]),
-                PayloadLength::Long(_) => vec!(data[2], data[3], data[4], data[5], data[6], data[7], data[8], data[9]),
+            match num_extension_bytes {
+                0 => vec!(payload_length_code),
+                _ => data[2..masking_key_index].to_vec(),
             };
 
-        let num_payload_bytes: usize = frame_length - payload_start_index;
+        // Calculate payload length
+        let payload_length = WebSocketFrame::get_payload_length(payload_length_code, &payload_length_bytes);
 
-        // Get mask
-        let masking_key: [u8; 4] = match payload_length {
-            PayloadLength::Short(_) => [data[2], data[3], data[4], data[5]],
-            PayloadLength::Medium(_) => [data[4], data[5], data[6], data[7]],
-            PayloadLength::Long(_) => [data[10], data[11], data[12], data[13]],
-        };
+        // Masked payloads begin after the 4-byte masking key
+        let payload_start_index: usize =
+            if is_payload_masked { masking_key_index + NUM_MASK_BYTES } else { masking_key_index };
+        check_length(data, payload_start_index)?;
+
+        // Check the whole payload is present
+        let num_payload_bytes: usize = payload_length.num_bytes().try_into().unwrap_or(usize::MAX);
+        let frame_length: usize = payload_start_index.saturating_add(num_payload_bytes);
+        check_length(data, frame_length)?;
+
+        // Get mask (an unmasked payload is equivalent to one masked with zeros)
+        let masking_key: [u8; 4] = 
+            if is_payload_masked {
+                [data[masking_key_index], data[masking_key_index + 1], data[masking_key_index + 2], data[masking_key_index + 3]]
+            } else {
+                [0; 4]
+            };
 
         // Unmask and parse payload data
         let mut unmasked_payload: Vec<u8> = Vec::new();
@@ -147,19 +217,31 @@ impl<'a> WebSocketFrame<'a> {
         for i in 0..num_payload_bytes {
             let byte: u8 = data[payload_start_index + i] ^ masking_key[i % NUM_MASK_BYTES];
             unmasked_payload.push(byte); // 32 mask bits are used repeatedly
-                                         //payload.push(byte as char);
-            payload_chars.push(byte as char);
+            // Bytes that aren't printable ASCII characters (e.g. control codes) would break the layout
+            payload_chars.push(if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' });
         }
 
-        WebSocketFrame {
+        Ok(WebSocketFrame {
             // Bytes in frame
-            frame_len: data.len() as u8,
+            frame_len: frame_length,
             // Mask bit (bit 8) indicates if the payload is masked
             is_payload_masked,
             // Payload length
             payload_length,
-            // Use default format style
-            format_style: FormatStyle::new(),
+            // Use the default theme
+            theme: Theme::default(),
+            // Draw borders using ASCII characters by default
+            border_style: BorderStyle::Ascii,
+            // Use ANSI colours by default (plain text without the `std` feature)
+            markup: Markup::default(),
+            // Format the whole payload by default
+            max_payload_bytes: None,
+            // Fit any width by default
+            max_width: None,
+            // Number bits as RFC 6455 does by default
+            bit_numbering: BitNumbering::default(),
+            // Highlight nothing by default
+            highlighted_bits: BTreeSet::new(),
             // Bit 0 contains fin bit
             fin_bit: get_bit(data[0], 0),
             // Bit 1 contains rsv1
@@ -180,80 +262,335 @@ impl<'a> WebSocketFrame<'a> {
             payload_length_bytes,
             // Next 4 bytes contain masking key
             masking_key,
-            // Masked payload is from byte 6 to end of frame
-            masked_payload: &data[payload_start_index..data.len()],
+            // Masked payload runs from the end of the masking key to the end of the frame
+            masked_payload: Cow::Borrowed(&data[payload_start_index..frame_length]),
             // Unmasked payload
             unmasked_payload,
             // Vector of chars in payload
             payload_chars,
+        })
+    }
+
+    /// Copies any bytes borrowed from the parsed data, so the frame can outlive them (e.g. when
+    /// frames are queued after the capture buffer is reused).
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The `WebSocketFrame` to take ownership of.
+    pub fn into_owned(self: WebSocketFrame<'a>) -> WebSocketFrame<'static> {
+        WebSocketFrame {
+            frame_len: self.frame_len,
+            is_payload_masked: self.is_payload_masked,
+            payload_length: self.payload_length,
+            theme: self.theme,
+            border_style: self.border_style,
+            markup: self.markup,
+            max_payload_bytes: self.max_payload_bytes,
+            max_width: self.max_width,
+            bit_numbering: self.bit_numbering,
+            highlighted_bits: self.highlighted_bits,
+            fin_bit: self.fin_bit,
+            rsv1: self.rsv1,
+            rsv2: self.rsv2,
+            rsv3: self.rsv3,
+            opcode_bits: self.opcode_bits,
+            opcode: self.opcode,
+            mask_bit: self.mask_bit,
+            payload_length_code: self.payload_length_code,
+            payload_length_bytes: self.payload_length_bytes,
+            masking_key: self.masking_key,
+            masked_payload: Cow::Owned(self.masked_payload.into_owned()),
+            unmasked_payload: self.unmasked_payload,
+            payload_chars: self.payload_chars,
         }
     }
 
+    /// Gets the bytes of the frame as they appeared on the wire (with the payload still masked).
+    pub fn to_bytes(self: &WebSocketFrame<'a>) -> Vec<u8> {
+        let flags = [self.fin_bit, self.rsv1, self.rsv2, self.rsv3];
+        let first_byte = flags.iter().fold(0, |byte, flag| byte << 1 | u8::from(*flag)) << 4 | self.opcode_bits;
+        let mut bytes = vec![first_byte, u8::from(self.mask_bit) << 7 | self.payload_length_code];
+        if self.num_extension_bytes() > 0 {
+            bytes.extend_from_slice(&self.payload_length_bytes);
+        }
+        if self.is_payload_masked {
+            bytes.extend_from_slice(&self.masking_key);
+        }
+        bytes.extend_from_slice(&self.masked_payload);
+        bytes
+    }
+
+    /// Displays the diagram as plain text, rendered only when displayed (e.g. by a log sink that
+    /// has the level enabled).
+    pub fn plain(self: &WebSocketFrame<'a>) -> PlainText<'_, WebSocketFrame<'a>> {
+        PlainText::new(self)
+    }
+
+    /// Gets the 4-bit opcode of the frame (e.g. `1` for a text frame).
+    pub fn opcode(self: &WebSocketFrame<'a>) -> u8 {
+        self.opcode_bits
+    }
+
+    /// Gets the name of the frame's opcode (e.g. `Text` or `Ping`).
+    pub fn opcode_name(self: &WebSocketFrame<'a>) -> String {
+        format!("{:?}", self.opcode)
+    }
+
+    /// Lists the fields in the frame in the order they appear.
+    ///
+    /// Each payload byte is listed as a separate field.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The `WebSocketFrame` whose fields are listed.
+    pub fn fields(self: &WebSocketFrame<'a>) -> Vec<FrameField> {
+        let field = |name: &str, bit_offset: usize, bit_len: usize, value: u64| FrameField {
+            name: String::from(name),
+            bit_offset,
+            bit_len,
+            value,
+            unmasked_value: None,
+        };
+
+        let mut fields = vec![
+            field("FIN", 0, 1, self.fin_bit.into()),
+            field("RSV1", 1, 1, self.rsv1.into()),
+            field("RSV2", 2, 1, self.rsv2.into()),
+            field("RSV3", 3, 1, self.rsv3.into()),
+            field("Opcode", 4, 4, self.opcode_bits.into()),
+            field("MASK", 8, 1, self.mask_bit.into()),
+            field("Payload length", 9, 7, self.payload_length_code.into()),
+        ];
+        let mut bit_offset: usize = 16;
+
+        // Extended payload length
+        match self.payload_length {
+            PayloadLength::Short(_) => {}
+            PayloadLength::Medium(length) => {
+                fields.push(field("Extended payload length", bit_offset, 16, length.into()));
+                bit_offset += 16;
+            }
+            PayloadLength::Long(length) => {
+                fields.push(field("Extended payload length", bit_offset, 64, length));
+                bit_offset += 64;
+            }
+        }
+
+        // Masking key
+        if self.is_payload_masked {
+            fields.push(field("Masking-key", bit_offset, 32, u32::from_be_bytes(self.masking_key).into()));
+            bit_offset += 32;
+        }
+
+        // Payload bytes
+        for (i, byte) in self.masked_payload.iter().enumerate() {
+            fields.push(FrameField {
+                name: format!("Payload byte {}", i),
+                bit_offset: bit_offset + (i * BITS_IN_BYTE),
+                bit_len: BITS_IN_BYTE,
+                value: (*byte).into(),
+                unmasked_value: Some(self.unmasked_payload[i]),
+            });
+        }
+
+        fields
+    }
+
+    /// Exports the frame diagram as a fenced Markdown code block, without colour codes.
+    ///
+    /// The full diagram is always drawn (ignoring `max_width`), so it can be committed to docs.
+    pub fn to_markdown(self: &WebSocketFrame<'a>) -> String {
+        format!("```text\n{}```\n", self.plain_diagram())
+    }
+
+    /// Exports the fields of the frame as a reStructuredText grid table, with the bits and value of
+    /// each field (and the unmasked value of each payload byte).
+    pub fn to_rst(self: &WebSocketFrame<'a>) -> String {
+        let payload_len = self.masked_payload.len();
+        let num_payload_bytes = self.max_payload_bytes.map_or(payload_len, |max| max.min(payload_len));
+        let end_bit = self.payload_bit(num_payload_bytes).expect("Payload bits are part of the frame.");
+        let header = ["Field", "Bits", "Value"];
+        let mut table = DocTable::new(header.iter().map(|title| title.to_string()).collect());
+        for field in self.fields().into_iter().filter(|field| field.bit_offset < end_bit) {
+            let bits = match field.bit_len {
+                1 => field.bit_offset.to_string(),
+                len => format!("{}-{}", field.bit_offset, field.bit_offset + len - 1),
+            };
+            let mut value = vec![format!("{:#x} ({})", field.value, field.value)];
+            if let Some(byte) = field.unmasked_value {
+                value.push(format!("{:#x} ({}) unmasked", byte, byte));
+            }
+            table.push_row(vec![vec![field.name], vec![bits], value]);
+        }
+        table.to_rst()
+    }
+
+    /// Renders the full diagram without markup.
+    fn plain_diagram(self: &WebSocketFrame<'a>) -> String {
+        let mut frame = self.clone();
+        frame.markup = Markup::Plain;
+        frame.max_width = None;
+        frame.format()
+    }
+
+    /// Exports the websocket frame as a JSON object.
+    ///
+    /// # Arguments
+    ///
+    /// * `self` - The `WebSocketFrame` being exported.
+    pub fn to_json(self: &WebSocketFrame<'a>) -> String {
+        format!(
+            "{{\"frame_length\":{},\"fin\":{},\"rsv1\":{},\"rsv2\":{},\"rsv3\":{},\"opcode\":{{\"value\":{},\"name\":\"{:?}\"}},\"masked\":{},\"payload_length\":{{\"kind\":\"{}\",\"value\":{}}},\"masking_key\":{},\"payload\":{},\"payload_text\":{}}}",
+            self.frame_len,
+            self.fin_bit,
+            self.rsv1,
+            self.rsv2,
+            self.rsv3,
+            self.opcode_bits,
+            self.opcode,
+            self.is_payload_masked,
+            match self.payload_length {
+                PayloadLength::Short(_) => "Short",
+                PayloadLength::Medium(_) => "Medium",
+                PayloadLength::Long(_) => "Long",
+            },
+            self.payload_length.num_bytes(),
+            if self.is_payload_masked { json::byte_array(&self.masking_key) } else { String::from("null") },
+            json::byte_array(&self.unmasked_payload),
+            match core::str::from_utf8(&self.unmasked_payload) {
+                Ok(text) => json::string(text),
+                Err(_) => String::from("null"),
+            },
+        )
+    }
+
     /// Formats the websocket frame.
     ///
     /// # Arguments
     ///
     /// * `self` - The `WebSocketFrame` being formatted.
     pub fn format(self: &WebSocketFrame<'a>) -> String {
-        let mut result = self.format_header();
+        self.to_string()
+    }
 
-        // DWORD 1
-        result.push_str(&self.format_first_dword());
+    /// Renders the websocket frame straight into a writer, without building intermediate strings.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the rendered frame (e.g. a `String` or `fmt::Formatter`).
+    pub fn render_to<W: fmt::Write>(self: &WebSocketFrame<'a>, out: &mut W) -> fmt::Result {
+        out.write_str(self.markup.prefix())?;
+        self.render_diagram(out)?;
+        out.write_str(self.markup.suffix())
+    }
 
-        // DWORD 2
-        result.push_str(&self.format_second_dword());
+    /// Renders the websocket frame into an I/O writer (such as a file, socket or log sink).
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the rendered frame.
+    #[cfg(feature = "std")]
+    pub fn write_to<W: io::Write>(self: &WebSocketFrame<'a>, mut writer: W) -> io::Result<()> {
+        write!(writer, "{}", self)
+    }
 
-        let payload_length: usize = 
-            match self.payload_length {
-                PayloadLength::Short(length) => length.into(),
-                PayloadLength::Medium(length) => length.into(),
-                PayloadLength::Long(length) => length.try_into().unwrap()
-            };
+    /// Renders the websocket frame without any enclosing markup.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the rendered frame.
+    pub(crate) fn render_diagram<W: fmt::Write>(self: &WebSocketFrame<'a>, out: &mut W) -> fmt::Result {
+        match self.max_width {
+            Some(width) if width < compact::FULL_WIDTH => {
+                let bits_per_row = if width < compact::WORD_WIDTH { 8 } else { 16 };
+                return self.render_compact(out, bits_per_row);
+            }
+            _ => {}
+        }
+        self.write_header(out)?;
 
-        // The sequential dword number to start from
-        let dword_from = 
-            match self.payload_length {
-                PayloadLength::Short(_) => 3,
-                PayloadLength::Medium(_) => 3,
-                PayloadLength::Long(_) => 4
-            };
+        // Limit the number of payload bytes formatted
+        let payload_length = self.masked_payload.len();
+        let num_payload_bytes = self.max_payload_bytes.map_or(payload_length, |max| max.min(payload_length));
+        let rows = self.row_cells(num_payload_bytes);
 
-        // Determine how many payload bytes were formatted by initial rows
-        let payload_bytes_formatted_already = match self.payload_length {
-            // Short length: 2 payload bytes formatted into DWORD 1 and DWORD 2 rows
-            PayloadLength::Short(_) => 2,
-            // Medium length: 0 payload bytes formatted so far
-            PayloadLength::Medium(_) => 0,
-            // Long length: 2 payload bytes formatted into DWORD 4 row
-            PayloadLength::Long(_) => 2,
-        };
+        // DWORD 1 (the flags, opcode and payload length code are followed by the first cells)
+        let (top_walls, bottom_walls) = cell_walls(&rows[0], 2);
+        self.write_rule(out, 7, &HEADER_WALLS, &[&FIRST_DWORD_WALLS[..], &top_walls].concat())?;
+        self.write_first_dword(out, &rows[0])?;
+        let mut walls_above = [&FIRST_DWORD_WALLS[..], &bottom_walls].concat();
 
-        // Format remaining full dwords
-        let remaining_payload_dwords = (payload_length - payload_bytes_formatted_already).div_euclid(BYTES_IN_DWORD.into());
-        for i in 0..remaining_payload_dwords {
-            let from_byte_ix = (i * BYTES_IN_DWORD) + payload_bytes_formatted_already;
-            let to_byte_ix = BYTES_IN_DWORD + from_byte_ix;
-            result.push_str(&self.format_payload_dword_row(
-                from_byte_ix, 
-                to_byte_ix, 
-                i + dword_from, 
-                i + payload_bytes_formatted_already
-            ));
-        }
-        // Format remaining bytes (formatted as partial dword)
-        let remaining_bytes: usize = (payload_length - payload_bytes_formatted_already).rem_euclid(BYTES_IN_DWORD);
-        if remaining_bytes > 0 {
-            let from_byte_ix: usize = (remaining_payload_dwords * BYTES_IN_DWORD) + payload_bytes_formatted_already;
-            let to_byte_ix: usize = from_byte_ix + remaining_bytes;
-            result.push_str(&self.format_payload_dword_row(
-                from_byte_ix,
-                to_byte_ix,
-                remaining_payload_dwords + dword_from,
-                (remaining_payload_dwords * 2) + payload_bytes_formatted_already,
-            ));
-        }
-
-        result
+        // The following DWORDs
+        for (i, cells) in rows.iter().enumerate().skip(1) {
+            let (top_walls, bottom_walls) = cell_walls(cells, 0);
+            self.write_rule(out, 7, &walls_above, &[&[0, 8][..], &top_walls].concat())?;
+            self.write_dword(out, cells, i + 1)?;
+            walls_above = [&[0, 8][..], &bottom_walls].concat();
+        }
+        // Append the bottom border
+        self.write_rule(out, 7, &walls_above, &[])?;
+
+        // Note any payload bytes left out
+        if num_payload_bytes < payload_length {
+            writeln!(
+                out,
+                "{0:7}{1}",
+                "",
+                format_args!("... {} more payload bytes", payload_length - num_payload_bytes).paint(self.theme.notes, self.markup),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Lays out the bytes following the first two bytes of the frame as cells in DWORD rows. The
+    /// first row holds the last two bytes of DWORD 1, and each following row holds a whole DWORD.
+    ///
+    /// # Arguments
+    ///
+    /// * `num_payload_bytes` - The number of payload bytes formatted.
+    fn row_cells(self: &WebSocketFrame<'a>, num_payload_bytes: usize) -> Vec<Vec<RowCell>> {
+        // The extended payload length and masking key are drawn 16 bits at a time
+        let mut halves: Vec<(String, bool)> = Vec::new();
+        match self.payload_length {
+            PayloadLength::Short(_) => {}
+            PayloadLength::Medium(_) => halves.push((String::from("Payload length"), true)),
+            PayloadLength::Long(_) => halves.extend((1..=4).map(|part| (format!("Payload length (part {} of 4)", part), part == 1))),
+        }
+        if self.is_payload_masked {
+            halves.extend((1..=2).map(|part| (format!("Masking-key (part {})", part), false)));
+        }
+
+        let mut rows: Vec<Vec<RowCell>> = vec![Vec::new()];
+        let mut bytes_left_in_row = 2;
+        let mut byte_index = 2;
+        for (name, shows_length) in halves {
+            if bytes_left_in_row == 0 {
+                rows.push(Vec::new());
+                bytes_left_in_row = BYTES_IN_DWORD;
+            }
+            let last = rows.len() - 1;
+            rows[last].push(RowCell::Half { byte_index, name, shows_length });
+            byte_index += 2;
+            bytes_left_in_row -= 2;
+        }
+
+        // The payload fills the rest of each row
+        let mut from = 0;
+        let mut part = 1;
+        while from < num_payload_bytes {
+            if bytes_left_in_row == 0 {
+                rows.push(Vec::new());
+                bytes_left_in_row = BYTES_IN_DWORD;
+            }
+            let to = num_payload_bytes.min(from + bytes_left_in_row);
+            let last = rows.len() - 1;
+            rows[last].push(RowCell::Payload { from, to, part });
+            bytes_left_in_row -= to - from;
+            from = to;
+            part += 1;
+        }
+        rows
     }
 
     /// Formats the WebSocket frame header.
@@ -261,67 +598,68 @@ impl<'a> WebSocketFrame<'a> {
     /// # Arguments
     ///
     /// * `self` The WebSocket frame being formatted.
-    fn format_header(self: &WebSocketFrame<'a>) -> String {
-        // Start with the top border
-        let mut result: String = 
-            format!(
-                "{0:15}{1}\n", 
-                "",
-                "+---------------+---------------+---------------+---------------+".color(self.format_style.border_color),
-            );
+    /// * `out` - The writer receiving the header.
+    fn write_header<W: fmt::Write>(self: &WebSocketFrame<'a>, out: &mut W) -> fmt::Result {
+        // Start with the top border (the header is indented by a further 8 characters)
+        let header_walls = HEADER_WALLS.map(|x| x - 8);
+        self.write_rule(out, 15, &[], &header_walls)?;
 
         // Append column headers
-        result.push_str(
-            &format!(
-                "{1:^15}{0}{2:^15}{0}{3:^15}{0}{4:^15}{0}{5:^15}{0}\n", 
-                "|".color(self.format_style.border_color),
-                "Frame Data".color(self.format_style.title_color),
-                "Byte  1".color(self.format_style.column_title_color),
-                "Byte  2".color(self.format_style.column_title_color),
-                "Byte  3".color(self.format_style.column_title_color),
-                "Byte  4".color(self.format_style.column_title_color),
-            )
-        );
+        writeln!(
+            out,
+            "{1:^15}{0}{2:^15}{0}{3:^15}{0}{4:^15}{0}{5:^15}{0}",
+            self.wall(),
+            "Frame Data".paint(self.theme.title, self.markup),
+            "Byte  1".paint(self.theme.column_title, self.markup),
+            "Byte  2".paint(self.theme.column_title, self.markup),
+            "Byte  3".paint(self.theme.column_title, self.markup),
+            "Byte  4".paint(self.theme.column_title, self.markup),
+        )?;
         // Append divider (between byte headers and bit tick marks)
-        result.push_str(
-            &format!(
-                "{0:2}{1}\n", 
-                " ",
-                format!(
-                    "{0:^10}{1:3}{2}",
-                    if self.is_payload_masked { "(Masked)".color(self.format_style.title_color) } else { "(Unmasked)".color(self.format_style.title_color) },
-                    "",
-                    "+---------------+---------------+---------------+---------------+".color(self.format_style.border_color),
-                )
-            )
-        );
-        // Append tens tick marks
-        result.push_str(
-            &format!(
-                "{1:^15}{2}{3}{0:14}{2}{0:4}{4}{0:10}{2}{0:8}{5}{0:6}{2}{0:12}{6}{0:2}{2}\n",
-                "",
-                format!("{:?}", self.payload_length),
-                "|".color(self.format_style.border_color),
-                "0".color(self.format_style.tick_mark_color),
-                "1".color(self.format_style.tick_mark_color),
-                "2".color(self.format_style.tick_mark_color),
-                "3".color(self.format_style.tick_mark_color)
-            )
-        );
-        // Append unit tick marks
-        result.push_str(
-            &format!(
-                "{0:15}{1}{2}{1}{3}{1}{4}{1}{5}{1}\n",
-                "",
-                "|".color(self.format_style.border_color),
-                "0 1 2 3 4 5 6 7".color(self.format_style.tick_mark_color),
-                "8 9 0 1 2 3 4 5".color(self.format_style.tick_mark_color),
-                "6 7 8 9 0 1 2 3".color(self.format_style.tick_mark_color),
-                "4 5 6 7 8 9 0 1".color(self.format_style.tick_mark_color)
-            )
-        );
-        
-        result
+        writeln!(
+            out,
+            "{0:2}{1:^10}{0:3}{2}",
+            "",
+            if self.is_payload_masked { "(Masked)".paint(self.theme.title, self.markup) } else { "(Unmasked)".paint(self.theme.title, self.markup) },
+            self.border_style.rule(&header_walls, &header_walls).paint(self.theme.border, self.markup),
+        )?;
+        // Append tick marks (the tens digits of every tenth bit number, then the units digits)
+        write!(out, "{:^15}{}", format!("{:?}", self.payload_length), self.wall())?;
+        self.write_tick_marks(out, true)?;
+        write!(out, "{:15}{}", "", self.wall())?;
+        self.write_tick_marks(out, false)?;
+
+        Ok(())
+    }
+
+    /// Formats the rest of a line of tick marks above the bits of a DWORD, numbered by the frame's
+    /// bit numbering.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the tick marks.
+    /// * `tens` - Whether to show the tens digits of every tenth number (shown only when numbers run
+    ///   across the row) rather than the units digits of every number.
+    fn write_tick_marks<W: fmt::Write>(self: &WebSocketFrame<'a>, out: &mut W, tens: bool) -> fmt::Result {
+        let row_bits = BITS_IN_BYTE * BYTES_IN_DWORD;
+        for byte in 0..BYTES_IN_DWORD {
+            let numbers = (0..BITS_IN_BYTE).map(|bit| self.bit_numbering.number(byte * BITS_IN_BYTE + bit, row_bits));
+            if tens {
+                for (bit, number) in numbers.enumerate() {
+                    if bit > 0 {
+                        out.write_char(' ')?;
+                    }
+                    match self.bit_numbering.span {
+                        BitSpan::Row if number % 10 == 0 => write!(out, "{}", (number / 10).paint(self.theme.tick_mark, self.markup))?,
+                        _ => out.write_char(' ')?,
+                    }
+                }
+            } else {
+                write!(out, "{}", UnitsDigits(numbers).paint(self.theme.tick_mark, self.markup))?;
+            }
+            write!(out, "{}", self.wall())?;
+        }
+        out.write_char('\n')
     }
 
     /// Formats the first dword of the data frame.
@@ -329,534 +667,342 @@ impl<'a> WebSocketFrame<'a> {
     /// # Arguments
     /// 
     /// * `self` - The WebSocket data frame containing the dwords to format.
-    fn format_first_dword(
-        self: &WebSocketFrame<'a>
-    ) -> String {
-        // Start with the top border
-        let mut result: String = 
-            format!(
-                "{0:7}{1}\n", 
-                "",
-                "+-------+---------------+---------------+---------------+---------------+".color(self.format_style.border_color),
-            );
+    /// * `out` - The writer receiving the dword.
+    /// * `cells` - The cells holding the last two bytes of the dword.
+    fn write_first_dword<W: fmt::Write>(
+        self: &WebSocketFrame<'a>,
+        out: &mut W,
+        cells: &[RowCell],
+    ) -> fmt::Result {
         // Line 1: DWORD 1 bit values
-        result.push_str(
-            &format!(
-                "{0:7}{1}{2:^7}{1}{3}{1}{4}{1}{5}{1}{6}{1}{7}{1}{8}{1}{9}{1}{10}{1}{11}{1}\n",
-                "",
-                "|".color(self.format_style.border_color),
-                "DWORD".color(self.format_style.dword_title_color),
-                bit_str(self.fin_bit).color(self.format_style.bit_color),
-                bit_str(self.rsv1).color(self.format_style.bit_color),
-                bit_str(self.rsv2).color(self.format_style.bit_color),
-                bit_str(self.rsv3).color(self.format_style.bit_color),
-                &byte_str(self.opcode_bits, 4).color(self.format_style.bit_color),
-                bit_str(self.mask_bit).color(self.format_style.bit_color),
-                &byte_str(self.payload_length_code, 7).color(self.format_style.bit_color),
-                match self.payload_length {
-                    PayloadLength::Short(_) => 
-                        byte_str(self.masking_key[0], 8).color(self.format_style.bit_color),
-                    PayloadLength::Medium(_) | PayloadLength::Long(_) => 
-                        byte_str(self.payload_length_bytes[0], 8).color(self.format_style.bit_color),
-                },
-                match self.payload_length {
-                    PayloadLength::Short(_) => 
-                        byte_str(self.masking_key[1], 8).color(self.format_style.bit_color),
-                    PayloadLength::Medium(_) | PayloadLength::Long(_) => 
-                        byte_str(self.payload_length_bytes[1], 8).color(self.format_style.bit_color),
-                },
-            )
-        );
+        write!(
+            out,
+            "{0:7}{1}{2:^7}{1}{3}{1}{4}{1}{5}{1}{6}{1}{7}{1}{8}{1}{9}{1}",
+            "",
+            self.wall(),
+            "DWORD".paint(self.theme.dword_title, self.markup),
+            self.paint_bits(self.fin_bit.into(), 1, Some(0), self.theme.bit),
+            self.paint_bits(self.rsv1.into(), 1, Some(1), self.theme.bit),
+            self.paint_bits(self.rsv2.into(), 1, Some(2), self.theme.bit),
+            self.paint_bits(self.rsv3.into(), 1, Some(3), self.theme.bit),
+            &self.paint_bits(self.opcode_bits, 4, Some(4), self.theme.bit),
+            self.paint_bits(self.mask_bit.into(), 1, Some(8), self.theme.bit),
+            &self.paint_bits(self.payload_length_code, 7, Some(9), self.theme.bit),
+        )?;
+        self.write_cells_line(out, cells, 0)?;
         // Line 2: Op code and first line of bit names
-        result.push_str(
-            &format!(
-                "{0:7}{1}{2:^7}{1}{3}{1}{4}{1}{4}{1}{4}{1}{6:^7}{1}{5}{1}{7:^13}{1}{8:^31}{1}\n",
-                "",
-                "|".color(self.format_style.border_color),
-                "1".color(self.format_style.dword_title_color),
-                "F".color(self.format_style.notes_color),
-                "R".color(self.format_style.notes_color),
-                "M".color(self.format_style.notes_color),
-                &format!("{:?}", self.opcode).color(self.format_style.data_value_color),
-                match self.payload_length {
-                    PayloadLength::Short(length) => format!("{} bytes", length).color(self.format_style.data_value_color),
-                    PayloadLength::Medium(_) => "126: Medium".color(self.format_style.data_value_color),
-                    PayloadLength::Long(_) => "127: Long".color(self.format_style.data_value_color),
-                },
-                match self.payload_length {
-                    PayloadLength::Short(_) => format!("{}", ""),
-                    PayloadLength::Medium(length)  => 
-                        format!("{0:^6}{1:^19}{2:^6}", 
-                            format!("({})", self.payload_length_bytes[0]).color(self.format_style.byte_value_color),
-                            format!("{} bytes", length).color(self.format_style.data_value_color),
-                            format!("({})", self.payload_length_bytes[1]).color(self.format_style.byte_value_color),
-                        ),
-                    PayloadLength::Long(length) => 
-                        format!("{0:^6}{1:^19}{2:^6}", 
-                            format!("({})", self.payload_length_bytes[0]).color(self.format_style.byte_value_color),
-                            format!("{} bytes", length).color(self.format_style.data_value_color),
-                            format!("({})", self.payload_length_bytes[1]).color(self.format_style.byte_value_color),
-                        ),
-                },
-            )
-        );
+        write!(
+            out,
+            "{0:7}{1}{2:^7}{1}{3}{1}{4}{1}{4}{1}{4}{1}{6:^7}{1}{5}{1}{7:^13}{1}",
+            "",
+            self.wall(),
+            "1".paint(self.theme.dword_title, self.markup),
+            "F".paint(self.theme.notes, self.markup),
+            "R".paint(self.theme.notes, self.markup),
+            "M".paint(self.theme.notes, self.markup),
+            self.opcode.label().paint(self.theme.data_value, self.markup),
+            match self.payload_length {
+                PayloadLength::Short(length) => Cow::from(format!("{} bytes", length)).paint(self.theme.data_value, self.markup),
+                PayloadLength::Medium(_) => Cow::from("126: Medium").paint(self.theme.data_value, self.markup),
+                PayloadLength::Long(_) => Cow::from("127: Long").paint(self.theme.data_value, self.markup),
+            },
+        )?;
+        self.write_cells_line(out, cells, 1)?;
         // Append the second line of bit identifiers
-        result.push_str(
-            &format!(
-                "{0:7}{1}{0:7}{1}{2}{1}{3}{1}{3}{1}{3}{1}{4:7}{1}{5}{1}{6:^13}{1}{7:^31}{1}\n",
-                "",
-                "|".color(self.format_style.border_color),
-                "I".color(self.format_style.notes_color),
-                "S".color(self.format_style.notes_color),
-                "op code".color(self.format_style.notes_color),
-                "A".color(self.format_style.notes_color),
-                "Payload len".color(self.format_style.notes_color),
-                match self.payload_length {
-                    PayloadLength::Short(_) => "Masking-key (part 1)".color(self.format_style.notes_color),
-                    PayloadLength::Medium(_) => "Payload length".color(self.format_style.notes_color),
-                    PayloadLength::Long(_) => "Payload length (Part 1 of 4)".color(self.format_style.notes_color),
-                }
-            )
-        );
+        write!(
+            out,
+            "{0:7}{1}{0:7}{1}{2}{1}{3}{1}{3}{1}{3}{1}{4:7}{1}{5}{1}{6:^13}{1}",
+            "",
+            self.wall(),
+            "I".paint(self.theme.notes, self.markup),
+            "S".paint(self.theme.notes, self.markup),
+            "op code".paint(self.theme.notes, self.markup),
+            "A".paint(self.theme.notes, self.markup),
+            "Payload len".paint(self.theme.notes, self.markup),
+        )?;
+        self.write_cells_line(out, cells, 2)?;
         // Append the third line of bit identifiers
-        result.push_str(
-            &format!(
-                "{0:7}{1}{0:7}{1}{2}{1}{3}{1}{3}{1}{3}{1}{4:^7}{1}{5}{1}{6:^13}{1}{7:^31}{1}\n",
-                "",
-                "|".color(self.format_style.border_color),
-                "N".color(self.format_style.notes_color),
-                "V".color(self.format_style.notes_color),
-                "(4 b)".color(self.format_style.notes_color),
-                "S".color(self.format_style.notes_color),
-                "(7 bits)".color(self.format_style.notes_color),
-                "(16 bits)".color(self.format_style.notes_color),
-            )
-        );
+        write!(
+            out,
+            "{0:7}{1}{0:7}{1}{2}{1}{3}{1}{3}{1}{3}{1}{4:^7}{1}{5}{1}{6:^13}{1}",
+            "",
+            self.wall(),
+            "N".paint(self.theme.notes, self.markup),
+            "V".paint(self.theme.notes, self.markup),
+            "(4 b)".paint(self.theme.notes, self.markup),
+            "S".paint(self.theme.notes, self.markup),
+            "(7 bits)".paint(self.theme.notes, self.markup),
+        )?;
+        self.write_cells_line(out, cells, 3)?;
         // Append the final line of bit identifiers
-        result.push_str(
-            &format!(
-                "{0:7}{1}{0:7}{1}{0:1}{1}{2}{1}{3}{1}{4}{1}{0:7}{1}{5}{1}{0:13}{1}{0:31}{1}\n",
-                "",
-                "|".color(self.format_style.border_color),
-                "1".color(self.format_style.notes_color),
-                "2".color(self.format_style.notes_color),
-                "3".color(self.format_style.notes_color),
-                "K".color(self.format_style.notes_color),
-            )
-        );
-        // Append border separating DWORD 1 and DWORD 2
-        result.push_str(
-            &format!(
-                "{0:7}{1}\n",
-                "",
-                "+-------+-+-+-+-+-------+-+-------------+-------------------------------+".color(self.format_style.border_color)
-            )    
-        );
+        write!(
+            out,
+            "{0:7}{1}{0:7}{1}{0:1}{1}{2}{1}{3}{1}{4}{1}{0:7}{1}{5}{1}{0:13}{1}",
+            "",
+            self.wall(),
+            "1".paint(self.theme.notes, self.markup),
+            "2".paint(self.theme.notes, self.markup),
+            "3".paint(self.theme.notes, self.markup),
+            "K".paint(self.theme.notes, self.markup),
+        )?;
+        self.write_cells_line(out, cells, 4)
+    }
 
-        result
+    /// Formats a dword following the first dword of a WebSocket data frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the dword.
+    /// * `cells` - The cells holding the bytes of the dword.
+    /// * `dword_number` - The sequential number of the dword (counted from 1).
+    fn write_dword<W: fmt::Write>(self: &WebSocketFrame<'a>, out: &mut W, cells: &[RowCell], dword_number: usize) -> fmt::Result {
+        let num_lines = cells.iter().map(|cell| self.num_cell_lines(cell)).max().unwrap_or(1);
+        for line in 0..num_lines {
+            match line {
+                0 => write!(out, "{0:7}{1}{2:^7}{1}", "", self.wall(), "DWORD".paint(self.theme.dword_title, self.markup))?,
+                1 => write!(out, "{0:7}{1}{2:^7}{1}", "", self.wall(), dword_number.paint(self.theme.dword_title, self.markup))?,
+                _ => write!(out, "{0:7}{1}{0:7}{1}", "", self.wall())?,
+            }
+            self.write_cells_line(out, cells, line)?;
+        }
+        Ok(())
     }
 
-    /// Formats the second dword of a WebSocket data frame.
-    /// 
+    /// Formats a line of a row of cells, ending the line.
+    ///
     /// # Arguments
-    /// 
-    /// * `self` - The WebSocket data frame being formatted.
-    fn format_second_dword(
-        self: &WebSocketFrame<'a>) -> String {
-        // Line 1: Format the first line of DWORD 2
-        let mut result: String = 
-            match self.payload_length {
-                PayloadLength::Short(_) => {
-                    format!(
-                        "{0:7}{1}{2:^7}{1}{3:^15}{1}{4:^15}{1}{5:^15}{1}{6:^15}{1}\n",
-                        "",
-                        "|".color(self.format_style.border_color),
-                        "DWORD".color(self.format_style.dword_title_color),
-                        &byte_str(self.masking_key[2], 8).color(self.format_style.bit_color),
-                        &byte_str(self.masking_key[3], 8).color(self.format_style.bit_color),
-                        &byte_str(self.masked_payload[0], 8).color(self.format_style.bit_color),
-                        &byte_str(self.masked_payload[1], 8).color(self.format_style.bit_color),
-                    )
-                },
-                PayloadLength::Medium(_) => {
-                    format!(
-                        "{0:7}{1}{2:^7}{1}{3:^15}{1}{4:^15}{1}{5:^15}{1}{6:^15}{1}\n",
-                        "",
-                        "|".color(self.format_style.border_color),
-                        "DWORD".color(self.format_style.dword_title_color),
-                        &byte_str(self.masking_key[0], 8).color(self.format_style.bit_color),
-                        &byte_str(self.masking_key[1], 8).color(self.format_style.bit_color),
-                        &byte_str(self.masking_key[2], 8).color(self.format_style.bit_color),
-                        &byte_str(self.masking_key[3], 8).color(self.format_style.bit_color),
-                    )
-                },
-                PayloadLength::Long(_) => {
-                    format!(
-                        "{0:7}{1}{2:^7}{1}{3:^15}{1}{4:^15}{1}{5:^15}{1}{6:^15}{1}\n",
-                        "",
-                        "|".color(self.format_style.border_color),
-                        "DWORD".color(self.format_style.dword_title_color),
-                        &byte_str(self.payload_length_bytes[2], 8).color(self.format_style.bit_color),
-                        &byte_str(self.payload_length_bytes[3], 8).color(self.format_style.bit_color),
-                        &byte_str(self.payload_length_bytes[4], 8).color(self.format_style.bit_color),
-                        &byte_str(self.payload_length_bytes[5], 8).color(self.format_style.bit_color),
-                    )
-                }
-            };
+    ///
+    /// * `out` - The writer receiving the line.
+    /// * `cells` - The cells in the row.
+    /// * `line` - The index of the line within the row (the bits are on line 0).
+    fn write_cells_line<W: fmt::Write>(self: &WebSocketFrame<'a>, out: &mut W, cells: &[RowCell], line: usize) -> fmt::Result {
+        for cell in cells {
+            self.write_cell_line(out, cell, line)?;
+            write!(out, "{}", self.wall())?;
+        }
+        out.write_char('\n')
+    }
 
-        // Line 2: Append the second line of DWORD 2
-        match self.payload_length {
-            PayloadLength::Short(_) => {
-                result.push_str(
-                    &format!(
-                        "{0:7}{1}{2:^7}{1}{0:^31}{1}{0:1}{4:>5}{0:6}{3}{0:2}{5:>5}{0:6}{1}\n",
-                        "",
-                        "|".color(self.format_style.border_color),
-                        "2".color(self.format_style.dword_title_color),
-                        "MASKED".color(self.format_style.notes_color),
-                        &format!("({})", self.masked_payload[0]).color(self.format_style.byte_value_color),
-                        &format!("({})", self.masked_payload[1]).color(self.format_style.byte_value_color),
-                    )
-                );
-            },
-            PayloadLength::Medium(_) => {
-                result.push_str(
-                    &format!(
-                        "{0:7}{1}{2:^7}{1}{0:^31}{1}{0:31}{1}\n",
-                        "",
-                        "|".color(self.format_style.border_color),
-                        "2".color(self.format_style.dword_title_color),
-                    )
-                );
+    /// Gets the number of lines in a cell, including the line of bits.
+    ///
+    /// # Arguments
+    ///
+    /// * `cell` - The cell.
+    fn num_cell_lines(self: &WebSocketFrame<'a>, cell: &RowCell) -> usize {
+        match cell {
+            // Bits, value, name and size
+            RowCell::Half { .. } => 4,
+            // Masked bits and values, unmasked bits and values, and part number
+            RowCell::Payload { .. } if self.is_payload_masked => 5,
+            // Bits, values and part number
+            RowCell::Payload { .. } => 3,
+        }
+    }
+
+    /// Formats a line of a cell (blank when the cell has fewer lines).
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the line.
+    /// * `cell` - The cell.
+    /// * `line` - The index of the line within the cell (the bits are on line 0).
+    fn write_cell_line<W: fmt::Write>(self: &WebSocketFrame<'a>, out: &mut W, cell: &RowCell, line: usize) -> fmt::Result {
+        match *cell {
+            RowCell::Half { byte_index, ref name, shows_length } => match line {
+                0 => write!(
+                    out,
+                    "{1:^15}{0}{2:^15}",
+                    self.wall(),
+                    self.paint_bits(self.byte_at(byte_index), 8, Some(byte_index * BITS_IN_BYTE), self.theme.bit),
+                    self.paint_bits(self.byte_at(byte_index + 1), 8, Some((byte_index + 1) * BITS_IN_BYTE), self.theme.bit),
+                ),
+                1 if shows_length => write!(
+                    out,
+                    "{0:^6}{1:^19}{2:^6}",
+                    format_args!("({})", self.byte_at(byte_index)).paint(self.theme.byte_value, self.markup),
+                    format_args!("{} bytes", self.payload_length.num_bytes()).paint(self.theme.data_value, self.markup),
+                    format_args!("({})", self.byte_at(byte_index + 1)).paint(self.theme.byte_value, self.markup),
+                ),
+                2 => write!(out, "{:^31}", name.paint(self.theme.notes, self.markup)),
+                3 => write!(out, "{:^31}", "(16 bits)".paint(self.theme.notes, self.markup)),
+                _ => write!(out, "{:31}", ""),
             },
-            PayloadLength::Long(_) => {
-                result.push_str(
-                    &format!(
-                        "{0:7}{1}{2:^7}{1}{0:^31}{1}{0:31}{1}\n",
-                        "",
-                        "|".color(self.format_style.border_color),
-                        "2".color(self.format_style.dword_title_color),
-                    )
-                );
+            RowCell::Payload { from, to, part } => {
+                // Unmasked payloads skip the lines of masked bits and values
+                let line = if self.is_payload_masked || line == 0 { line } else { line + 2 };
+                match line {
+                    0 => self.write_payload_bits(out, &self.masked_payload[from..to], from, self.theme.bit),
+                    1 => self.write_masked_values(out, &self.masked_payload[from..to]),
+                    2 => self.write_payload_bits(out, &self.unmasked_payload[from..to], from, self.theme.unmasked_payload_bit),
+                    3 => self.write_unmasked_values(out, from, to),
+                    4 => match to - from {
+                        1 => write!(out, "{:^15}", format_args!("Payload pt {}", part).paint(self.theme.notes, self.markup)),
+                        num_bytes => write!(
+                            out,
+                            "{:^1$}",
+                            format_args!("Payload Data (part {})", part).paint(self.theme.notes, self.markup),
+                            2 * BITS_IN_BYTE * num_bytes - 1,
+                        ),
+                    },
+                    _ => write!(out, "{:1$}", "", 2 * BITS_IN_BYTE * (to - from) - 1),
+                }
             }
         }
-        
-        // Line 3: Append the third line of DWORD 2
-        match self.payload_length {
-            PayloadLength::Short(_) => {
-                result.push_str(
-                    &format!(
-                        "{0:7}{1}{0:7}{1}{2:^31}{1}{3:^15}{1}{4:^15}{1}\n",
-                        "",
-                        "|".color(self.format_style.border_color),
-                        "Masking-key (part 2)".color(self.format_style.notes_color),
-                        &byte_str(self.unmasked_payload[0], 8).color(self.format_style.unmasked_payload_bit_color),
-                        &byte_str(self.unmasked_payload[1], 8).color(self.format_style.unmasked_payload_bit_color),
-                    )
-                );
-            },
-            PayloadLength::Medium(_) => {
-                result.push_str(
-                    &format!(
-                        "{0:7}{1}{0:7}{1}{2:^31}{1}{3:^31}{1}\n",
-                        "",
-                        "|".color(self.format_style.border_color),
-                        "Masking-key (part 1)".color(self.format_style.notes_color),
-                        "Masking-key (part 2)".color(self.format_style.notes_color),
-                    )
-                );
-            },
-            PayloadLength::Long(_) => {
-                result.push_str(
-                    &format!(
-                        "{0:7}{1}{0:7}{1}{2:^31}{1}{3:^31}{1}\n",
-                        "",
-                        "|".color(self.format_style.border_color),
-                        "Payload length (part 2 of 4)".color(self.format_style.notes_color),
-                        "(16 bits)".color(self.format_style.notes_color),
-                    )
-                );
-            },
+    }
+
+    /// Formats the bits of a run of payload bytes, separated by walls.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the bits.
+    /// * `bytes` - The bytes (masked or unmasked).
+    /// * `from` - The index of the first byte within the payload.
+    /// * `style` - The style of the bits.
+    fn write_payload_bits<W: fmt::Write>(self: &WebSocketFrame<'a>, out: &mut W, bytes: &[u8], from: usize, style: Style) -> fmt::Result {
+        for (i, byte) in bytes.iter().enumerate() {
+            if i > 0 {
+                write!(out, "{}", self.wall())?;
+            }
+            write!(out, "{}", self.paint_bits(*byte, BITS_IN_BYTE as u8, self.payload_bit(from + i), style))?;
         }
+        Ok(())
+    }
 
-        // Line 4: Append the fourth line of DWORD 2
-        match self.payload_length {
-            PayloadLength::Short(_) => {
-                result.push_str(
-                    &format!(
-                        "{0:7}{1}{0:7}{1}{2:^31}{1}{0:1}{4:>5}{0:1}{5:3}{0:1}{3}{0:1}{6:>5}{0:1}{7:3}{0:2}{1}\n",
-                        "",
-                        "|".color(self.format_style.border_color),
-                        "(16 bits)".color(self.format_style.notes_color),
-                        "UNMASKED".color(self.format_style.notes_color),
-                        &format!("({})", self.unmasked_payload[0]).color(self.format_style.byte_value_color),
-                        &format!("'{0}'", &self.payload_chars[0]).color(self.format_style.data_value_color),
-                        &format!("({})", self.unmasked_payload[1]).color(self.format_style.byte_value_color),
-                        &format!("'{0}'", &self.payload_chars[1]).color(self.format_style.data_value_color),
-                    )
-                );
-            },
-            PayloadLength::Medium(_) => {
-                result.push_str(
-                    &format!(
-                        "{0:7}{1}{0:7}{1}{2:^31}{1}{2:^31}{1}\n",
-                        "",
-                        "|".color(self.format_style.border_color),
-                        "(16 bits)".color(self.format_style.notes_color),
-                    )
-                );   
+    /// Formats the values of a run of masked payload bytes, labelling each pair of bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the values.
+    /// * `bytes` - The masked bytes.
+    fn write_masked_values<W: fmt::Write>(self: &WebSocketFrame<'a>, out: &mut W, bytes: &[u8]) -> fmt::Result {
+        for (i, pair) in bytes.chunks(2).enumerate() {
+            if i > 0 {
+                write!(out, "{}", self.wall())?;
             }
-            PayloadLength::Long(_) => {
-                result.push_str(
-                    &format!(
-                        "{0:7}{1}{0:7}{1}{2:^31}{1}{2:^31}{1}\n",
-                        "",
-                        "|".color(self.format_style.border_color),
-                        "(16 bits)".color(self.format_style.notes_color),
-                    )
-                );
+            match *pair {
+                [first, second] => write!(
+                    out,
+                    "{0:1}{2:>5}{0:6}{1}{0:2}{3:>5}{0:6}",
+                    "",
+                    "MASKED".paint(self.theme.notes, self.markup),
+                    format_args!("({})", first).paint(self.theme.byte_value, self.markup),
+                    format_args!("({})", second).paint(self.theme.byte_value, self.markup),
+                )?,
+                [byte] => write!(
+                    out,
+                    "{0:1}{2:>5}{0:5}{1}{0:1}",
+                    "",
+                    "MSK".paint(self.theme.notes, self.markup),
+                    format_args!("({})", byte).paint(self.theme.byte_value, self.markup),
+                )?,
+                _ => {}
             }
         }
+        Ok(())
+    }
 
-        // Line 5: Append the fifth line of DWORD 2
-        match self.payload_length {
-            PayloadLength::Short(_) => {
-                result.push_str(
-                    &format!(
-                        "{0:7}{1}{0:7}{1}{0:^31}{1}{2:^31}{1}\n",
-                        "",
-                        "|".color(self.format_style.border_color),
-                        "Payload Data (part 1)".color(self.format_style.notes_color)
-                    )
-                );
+    /// Formats the values and characters of a run of unmasked payload bytes, labelling each pair of
+    /// bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the values.
+    /// * `from` - The index of the first byte within the payload.
+    /// * `to` - The index following the last byte.
+    fn write_unmasked_values<W: fmt::Write>(self: &WebSocketFrame<'a>, out: &mut W, from: usize, to: usize) -> fmt::Result {
+        let bytes = &self.unmasked_payload[from..to];
+        let chars = &self.payload_chars[from..to];
+        for (i, (pair, pair_chars)) in bytes.chunks(2).zip(chars.chunks(2)).enumerate() {
+            if i > 0 {
+                write!(out, "{}", self.wall())?;
             }
-            PayloadLength::Medium(_) | PayloadLength::Long(_) => {
-                result.push_str(
-                    &format!(
-                        "{0:7}{1}{0:7}{1}{0:^31}{1}{0:^31}{1}\n",
-                        "",
-                        "|".color(self.format_style.border_color),
-                    )
-                );
+            match (pair, pair_chars) {
+                ([first, second], [first_char, second_char]) => write!(
+                    out,
+                    "{0:1}{2:>5}{0:1}{3:3}{0:1}{1}{0:1}{4:>5}{0:1}{5:3}{0:2}",
+                    "",
+                    "UNMASKED".paint(self.theme.notes, self.markup),
+                    format_args!("({})", first).paint(self.theme.byte_value, self.markup),
+                    format_args!("'{}'", first_char).paint(self.theme.data_value, self.markup),
+                    format_args!("({})", second).paint(self.theme.byte_value, self.markup),
+                    format_args!("'{}'", second_char).paint(self.theme.data_value, self.markup),
+                )?,
+                ([byte], [c]) => write!(
+                    out,
+                    "{0:1}{2:>5}{0:1}{3:3}{0:1}{1}{0:1}",
+                    "",
+                    "UNM".paint(self.theme.notes, self.markup),
+                    format_args!("({})", byte).paint(self.theme.byte_value, self.markup),
+                    format_args!("'{}'", c).paint(self.theme.data_value, self.markup),
+                )?,
+                _ => {}
             }
         }
-        
-        // Append the bottom border
-        result.push_str(
-            &format!(
-                "{0:7}{1}\n",
-                "",
-                "+-------+-------------------------------+-------------------------------+".color(self.format_style.border_color),
-            )
-        );
-
-        result
+        Ok(())
     }
-    
-    /// Formats a dword table row displaying part of a websocket frame payload.
+
+    /// Formats a line holding a horizontal rule (nothing when borders are hidden).
+    ///
     /// # Arguments
     ///
-    /// * `self` The WebSocket frame being formatted.
-    fn format_payload_dword_row(
-        self: &WebSocketFrame<'a>,
-        from_byte_ix: usize,
-        to_byte_ix: usize,
-        dword_number: usize,
-        part_number: usize,
-    ) -> String { 
-        let mut result: String = String::from("");
-
-        // Calculate number of bytes to include in this row
-        let num_bytes = to_byte_ix - from_byte_ix;
-
-        let masked_bits: &[u8] = &self.masked_payload[from_byte_ix..to_byte_ix];
-        let unmasked_bits: &[u8] = &self.unmasked_payload[from_byte_ix..to_byte_ix];
-        let payload_data: &[char] = &self.payload_chars[from_byte_ix..to_byte_ix];
-
-        // Check indexes form a valid range
-        if num_bytes < 1 || num_bytes > 4  {
-            return String::from(
-                format!("ERROR: Cannot print dword row. Illegal byte indexes provided. from_byte_ix: {} to_byte_ix: {}", 
-                from_byte_ix, 
-                to_byte_ix));
-        }
-
-        // Format masked bits (line 1)
-        result.push_str(
-            &format!(
-                "{0:7}{1}{2:^7}{1}",
-                "",
-                "|".color(self.format_style.border_color),
-                "DWORD".color(self.format_style.dword_title_color),
-            )
-        );
-        result.push_str(
-            &(0..num_bytes)
-                .map(|i| format!(
-                    "{1}{0}",
-                    "|".color(self.format_style.border_color), 
-                    &byte_str(masked_bits[i], BITS_IN_BYTE as u8).color(self.format_style.bit_color)))
-                .collect::<String>()
-        );
-        result.push_str("\n");
-
-        // Line 2: Masked char previews
-        result.push_str(
-            &format!(
-                "{0:7}{1}{2:^7}{1}",
-                "",
-                "|".color(self.format_style.border_color),
-                &dword_number.to_string().color(self.format_style.dword_title_color)
-            )
-        );
-        match num_bytes {
-            1 => result.push_str(&format!(
-                "{0:1}{3:>5}{0:5}{2}{0:1}{1}",
-                "",
-                "|".color(self.format_style.border_color),
-                "MSK".color(self.format_style.notes_color),
-                &format!("({})", masked_bits[0]).color(self.format_style.byte_value_color)
-            )),
-            2 => result.push_str(&format!(
-                "{0:1}{3:>5}{0:6}{2}{0:2}{4:>5}{0:6}{1}",
-                "",
-                "|".color(self.format_style.border_color),
-                "MASKED".color(self.format_style.notes_color),
-                &format!("({})", masked_bits[0]).color(self.format_style.byte_value_color),
-                &format!("({})", masked_bits[1]).color(self.format_style.byte_value_color),
-            )),
-            3 => result.push_str(&format!(
-                "{0:1}{4:>5}{0:6}{2}{0:2}{5:>5}{0:6}{1}{0:1}{6:>5}{0:5}{3}{0:1}{1}",
-                "",
-                "|".color(self.format_style.border_color),
-                "MASKED".color(self.format_style.notes_color),
-                "MSK".color(self.format_style.notes_color),
-                &format!("({})", masked_bits[0]).color(self.format_style.byte_value_color),
-                &format!("({})", masked_bits[1]).color(self.format_style.byte_value_color),
-                &format!("({})", masked_bits[2]).color(self.format_style.byte_value_color),
-            )),
-            4 => result.push_str(&format!(
-                "{0:1}{4:>5}{0:6}{2}{0:2}{5:>5}{0:6}{1}{0:1}{6:>5}{0:6}{3}{0:2}{7:>5}{0:6}{1}",
-                "",
-                "|".color(self.format_style.border_color),
-                "MASKED".color(self.format_style.notes_color),
-                "MASKED".color(self.format_style.notes_color),
-                &format!("({})", masked_bits[0]).color(self.format_style.byte_value_color),
-                &format!("({})", masked_bits[1]).color(self.format_style.byte_value_color),
-                &format!("({})", masked_bits[2]).color(self.format_style.byte_value_color),
-                &format!("({})", masked_bits[3]).color(self.format_style.byte_value_color)
-            )),
-            _ => {}
+    /// * `out` - The writer receiving the rule.
+    /// * `indent` - The number of spaces before the rule.
+    /// * `above` - The positions of the walls in the line above, relative to the start of the rule.
+    /// * `below` - The positions of the walls in the line below, relative to the start of the rule.
+    fn write_rule<W: fmt::Write>(self: &WebSocketFrame<'a>, out: &mut W, indent: usize, above: &[usize], below: &[usize]) -> fmt::Result {
+        if self.border_style == BorderStyle::None {
+            return Ok(());
         }
-        result.push_str("\n");
+        writeln!(out, "{0:1$}{2}", "", indent, self.border_style.rule(above, below).paint(self.theme.border, self.markup))
+    }
 
-        // Line 3: Unmasked bits
-        result.push_str(
-            &format!(
-                "{0:7}{1}{0:7}{1}",
-                "",
-                "|".color(self.format_style.border_color),
-            )
-        );
-        result.push_str(
-            &(0..num_bytes)
-                .map(|i| format!(
-                    "{1}{0}", 
-                    "|".color(self.format_style.border_color), 
-                    &byte_str(unmasked_bits[i], BITS_IN_BYTE as u8).color(self.format_style.unmasked_payload_bit_color)))
-                .collect::<String>(),
-        );
-        result.push_str("\n");
+    /// Paints the wall drawn between cells.
+    fn wall(self: &WebSocketFrame<'a>) -> Painted<&'static str> {
+        self.border_style.vertical().paint(self.theme.border, self.markup)
+    }
 
-        // Line 4: Unmasked char previews
-        result.push_str(&format!("{0:7}{1}{0:7}{1}", "", "|".color(self.format_style.border_color)));
-        match num_bytes {
-            1 => result.push_str(&format!(
-                "{0:1}{3:>5}{0:1}{4}{0:1}{2}{0:1}{1}",
-                "",
-                "|".color(self.format_style.border_color),
-                "UNM".color(self.format_style.notes_color),
-                &format!("({})", unmasked_bits[0]).color(self.format_style.byte_value_color),
-                &format!("'{0}'", payload_data[0]).color(self.format_style.data_value_color),
-            )),
-            2 => result.push_str(&format!(
-                "{0:1}{3:>5}{0:1}{4:3}{0:1}{2}{0:1}{5:>5}{0:1}{6:3}{0:2}{1}",
-                "",
-                "|".color(self.format_style.border_color),
-                "UNMASKED".color(self.format_style.notes_color),
-                &format!("({})", unmasked_bits[0]).color(self.format_style.byte_value_color),
-                &format!("'{}'", payload_data[0]).color(self.format_style.data_value_color),
-                &format!("({})", unmasked_bits[1]).color(self.format_style.byte_value_color),
-                &format!("'{}'", payload_data[1]).color(self.format_style.data_value_color)
-            )),
-            3 => result.push_str(&format!(
-                "{0:1}{4:>5}{0:1}{5:3}{0:1}{2}{0:1}{6:>5}{0:1}{7:3}{0:2}{1}{0:1}{8:>5}{0:1}{9:3}{0:1}{3}{0:1}{1}",
-                "",
-                "|".color(self.format_style.border_color),
-                "UNMASKED".color(self.format_style.notes_color),
-                "UNM".color(self.format_style.notes_color),
-                &format!("({})", unmasked_bits[0]).color(self.format_style.byte_value_color),
-                &format!("'{}'", payload_data[0]).color(self.format_style.data_value_color),
-                &format!("({})", unmasked_bits[1]).color(self.format_style.byte_value_color),
-                &format!("'{}'", payload_data[1]).color(self.format_style.data_value_color),
-                &format!("({})", unmasked_bits[2]).color(self.format_style.byte_value_color),
-                &format!("'{}'", payload_data[2]).color(self.format_style.data_value_color),
-            )),
-            4 => result.push_str(&format!(
-                "{0:1}{3:>5}{0:1}{4:3}{0:1}{2}{0:1}{5:>5}{0:1}{6:3}{0:2}{1}{0:1}{7:>5}{0:1}{8:3}{0:1}{2}{0:1}{9:>5}{0:1}{10:3}{0:2}{1}",
-                "",
-                "|".color(self.format_style.border_color),
-                "UNMASKED".color(self.format_style.notes_color),
-                &format!("({})", unmasked_bits[0]).color(self.format_style.byte_value_color),
-                &format!("'{}'", payload_data[0]).color(self.format_style.data_value_color),
-                &format!("({})", unmasked_bits[1]).color(self.format_style.byte_value_color),
-                &format!("'{}'", payload_data[1]).color(self.format_style.data_value_color),
-                &format!("({})", unmasked_bits[2]).color(self.format_style.byte_value_color),
-                &format!("'{}'", payload_data[2]).color(self.format_style.data_value_color),
-                &format!("({})", unmasked_bits[3]).color(self.format_style.byte_value_color),
-                &format!("'{}'", payload_data[3]).color(self.format_style.data_value_color),
-            )),
-            _ => {}
-        }
-        result.push_str("\n");
-
-        // Line 5: Payload part
-        result.push_str(&format!("{0:7}{1}{0:7}{1}", "", "|".color(self.format_style.border_color)));
-        match num_bytes {
-            1 => result.push_str(&format!(
-                "{1:^15}{0}",
-                "|".color(self.format_style.border_color),
-                &format!("Payload pt {}", part_number).color(self.format_style.notes_color),
-            )),
-            2 => result.push_str(&format!(
-                "{1:^31}{0}",
-                "|".color(self.format_style.border_color),
-                &format!("Payload Data (part {})", part_number).color(self.format_style.notes_color),
-            )),
-            3 => result.push_str(&format!(
-                "{1:^47}{0}",
-                "|".color(self.format_style.border_color),
-                &format!("Payload Data (part {})", part_number).color(self.format_style.notes_color),
-            )),
-            4 => result.push_str(&format!(
-                "{1:^63}{0}",
-                "|".color(self.format_style.border_color),
-                &format!("Payload Data (part {})", part_number).color(self.format_style.notes_color),
-            )),
-            _ => {}
+    /// Paints the bits of a byte (or partial byte), drawing highlighted bits in the highlight style.
+    ///
+    /// # Arguments
+    ///
+    /// * `byte` - The byte whose bits are painted.
+    /// * `num_bits` - The number of bits to paint (from the least significant end of the byte).
+    /// * `bit_offset` - The offset of the first bit from the start of the frame (`None` if the bits
+    ///   aren't part of the frame).
+    /// * `style` - The style of bits that aren't highlighted.
+    fn paint_bits(
+        self: &WebSocketFrame<'a>,
+        byte: u8,
+        num_bits: u8,
+        bit_offset: Option<usize>,
+        style: Style,
+    ) -> PaintedBits<'_, 'a> {
+        PaintedBits {
+            frame: self,
+            byte,
+            num_bits,
+            bit_offset,
+            style,
         }
-        result.push_str("\n");
+    }
 
-        // Format bottom border
-        result.push_str(&format!("{0:7}{1}", "", "+-------+".color(self.format_style.border_color)));
-        result.push_str(
-            &(0..num_bytes)
-                .map(|_| "---------------+".color(self.format_style.border_color).to_string())
-                .collect::<String>(),
-        );
-        result.push_str("\n");
+    /// Gets the offset of a payload byte from the start of the frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the byte within the payload.
+    fn payload_bit(self: &WebSocketFrame<'a>, index: usize) -> Option<usize> {
+        let masking_key_len = if self.is_payload_masked { 4 } else { 0 };
+        Some((2 + self.num_extension_bytes() + masking_key_len + index) * BITS_IN_BYTE)
+    }
 
-        result
+    /// Gets the number of extended payload length bytes.
+    fn num_extension_bytes(self: &WebSocketFrame<'a>) -> usize {
+        match self.payload_length {
+            PayloadLength::Short(_) => 0,
+            PayloadLength::Medium(_) => 2,
+            PayloadLength::Long(_) => 8,
+        }
     }
 
     /// Derives a WebSocket payload length from its payload length code and extension bytes.
@@ -869,7 +1015,7 @@ impl<'a> WebSocketFrame<'a> {
     /// * `ext_bytes` - The extension bytes.
     fn get_payload_length(
         code: u8, 
-        ext_bytes: Vec<u8>
+        ext_bytes: &[u8]
     ) -> PayloadLength {
         // Code <= 125: The code *is* the payload length
         if code <= 125 {
@@ -888,46 +1034,135 @@ impl<'a> WebSocketFrame<'a> {
     }
 }
 
-fn get_bits_from_byte(byte: u8, mask: u8) -> u8 {
-    byte & mask
+/// A cell in a DWORD row following the first two bytes of a frame.
+enum RowCell {
+    /// Two bytes of the extended payload length or masking key.
+    Half {
+        /// The index of the first byte from the start of the frame.
+        byte_index: usize,
+        /// The name of the part of the field held.
+        name: String,
+        /// Whether to show the payload length (below the first part of the extended payload length).
+        shows_length: bool,
+    },
+    /// A run of payload bytes.
+    Payload {
+        /// The index of the first byte within the payload.
+        from: usize,
+        /// The index following the last byte.
+        to: usize,
+        /// The sequential number of the run (counted from 1).
+        part: usize,
+    },
 }
 
-/// Formats a byte or partial byte.
+/// The bits of a byte (or partial byte) painted for a frame, rendered without building a string.
+///
+/// Padding requested by the format string is applied to the visible bits.
+struct PaintedBits<'f, 'a> {
+    frame: &'f WebSocketFrame<'a>,
+    byte: u8,
+    num_bits: u8,
+    bit_offset: Option<usize>,
+    style: Style,
+}
+
+impl fmt::Display for PaintedBits<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // The bits separated by spaces (e.g. `0 0 0 1`)
+        let mut buffer = [b' '; 2 * BITS_IN_BYTE - 1];
+        for i in 0..self.num_bits {
+            buffer[2 * i as usize] = if get_bit(self.byte, 8 - self.num_bits + i) { b'1' } else { b'0' };
+        }
+        let text = core::str::from_utf8(&buffer[..2 * self.num_bits as usize - 1]).map_err(|_| fmt::Error)?;
+
+        let frame = self.frame;
+        let highlighted = |i: usize| {
+            !frame.highlighted_bits.is_empty()
+                && self.bit_offset.is_some_and(|offset| frame.highlighted_bits.contains(&(offset + i)))
+        };
+        if !(0..self.num_bits as usize).any(highlighted) {
+            return fmt::Display::fmt(&text.paint(self.style, frame.markup), f);
+        }
+        let (left, right) = padding(f, text.len());
+        write!(f, "{:1$}", "", left)?;
+        for i in 0..self.num_bits as usize {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            let style = if highlighted(i) { frame.theme.highlight } else { self.style };
+            write!(f, "{}", (&text[2 * i..2 * i + 1]).paint(style, frame.markup))?;
+        }
+        write!(f, "{:1$}", "", right)
+    }
+}
+
+/// The units digits of a run of bit numbers separated by spaces (e.g. `0 1 2 3`), rendered without
+/// building a string.
+struct UnitsDigits<I>(I);
+
+impl<I: Iterator<Item = usize> + Clone> fmt::Display for UnitsDigits<I> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, number) in self.0.clone().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}", number % 10)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for WebSocketFrame<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.render_to(f)
+    }
+}
+
+/// Gets the positions of the walls in a row of cells, counted from the left edge of the DWORD rows.
+///
+/// Returns the walls of the first line (which has a wall between each byte), then the walls of the
+/// last line (which has a single cell for each run of bytes).
 ///
 /// # Arguments
 ///
-/// * `byte` - The byte to format.
-/// * `num_bits` - The number of bits to format.
-fn byte_str<'a>(byte: u8, num_bits: u8) -> String {
-    let mut result: String = String::from("");
-    result.push_str(
-        &(8 - num_bits..8)
-            .map(|i| format!("{} ", bit_str(get_bit(byte, i))))
-            .collect::<String>(),
-    );
-    result.trim().to_string()
+/// * `cells` - The cells in the row.
+/// * `first_byte` - The position of the first cell's first byte within the DWORD.
+fn cell_walls(cells: &[RowCell], first_byte: usize) -> (Vec<usize>, Vec<usize>) {
+    let mut top = Vec::new();
+    let mut bottom = Vec::new();
+    let mut byte = first_byte;
+    for cell in cells {
+        let num_bytes = match cell {
+            RowCell::Half { .. } => 2,
+            RowCell::Payload { from, to, .. } => to - from,
+        };
+        top.extend((1..=num_bytes).map(|i| 8 + (byte + i) * 16));
+        byte += num_bytes;
+        bottom.push(8 + byte * 16);
+    }
+    (top, bottom)
 }
 
-fn bit_str<'a>(bit: bool) -> &'a str {
-    if bit == true {
-        "1"
-    } else {
-        "0"
+/// Checks that data contains at least the specified number of bytes.
+///
+/// # Arguments
+///
+/// * `data` - The data to check.
+/// * `expected` - The number of bytes required.
+fn check_length(data: &[u8], expected: usize) -> Result<(), FrameError> {
+    if data.len() < expected {
+        return Err(FrameError::Truncated { expected, actual: data.len() });
     }
+    Ok(())
+}
+
+fn get_bits_from_byte(byte: u8, mask: u8) -> u8 {
+    byte & mask
 }
 
 fn get_bit(byte: u8, bit_position: u8) -> bool {
-    match bit_position {
-        0 => byte & 0b10000000 != 0,
-        1 => byte & 0b01000000 != 0,
-        2 => byte & 0b00100000 != 0,
-        3 => byte & 0b00010000 != 0,
-        4 => byte & 0b00001000 != 0,
-        5 => byte & 0b00000100 != 0,
-        6 => byte & 0b00000010 != 0,
-        7 => byte & 0b00000001 != 0,
-        _ => false,
-    }
+    BitNumbering::default().get_bit(byte, bit_position)
 }
 
 // #region WebSocket Frame Unit Tests
@@ -935,18 +1170,32 @@ fn get_bit(byte: u8, bit_position: u8) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::bit_numbering::BitOrder;
+    use proptest::prelude::*;
 
     /// Tests that a short length frame with a masked text payload is formatted correctly.
     #[test]
     fn test_short_masked_text_frame() {
         let bytes = base64::decode("gYR7q0rdD845qQ==").unwrap();
 
-        let frame = WebSocketFrame::from_bytes(&bytes);
-        // let expected = "               \u{1b}[36m+---------------+---------------+---------------+---------------+\u{1b}[0m\n  \u{1b}[37mFrame Data\u{1b}[0m   \u{1b}[36m|\u{1b}[0m\u{1b}[32m    Byte  1    \u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[32m    Byte  2    \u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[32m    Byte  3    \u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[32m    Byte  4    \u{1b}[0m\u{1b}[36m|\u{1b}[0m\n  \u{1b}[37m (Masked) \u{1b}[0m   \u{1b}[36m+---------------+---------------+---------------+---------------+\u{1b}[0m\n   Short(4)    \u{1b}[36m|\u{1b}[0m\u{1b}[32m0\u{1b}[0m              \u{1b}[36m|\u{1b}[0m    \u{1b}[32m1\u{1b}[0m          \u{1b}[36m|\u{1b}[0m        \u{1b}[32m2\u{1b}[0m      \u{1b}[36m|\u{1b}[0m            \u{1b}[32m3\u{1b}[0m  \u{1b}[36m|\u{1b}[0m\n               \u{1b}[36m|\u{1b}[0m\u{1b}[32m0 1 2 3 4 5 6 7\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[32m8 9 0 1 2 3 4 5\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[32m6 7 8 9 0 1 2 3\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[32m4 5 6 7 8 9 0 1\u{1b}[0m\u{1b}[36m|\u{1b}[0m\n       \u{1b}[36m+-------+---------------+---------------+---------------+---------------+\u{1b}[0m\n       \u{1b}[36m|\u{1b}[0m\u{1b}[32m DWORD \u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[37m1\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[37m0\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[37m0\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[37m0\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[37m0 0 0 1\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[37m1\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[37m0 0 0 0 1 0 0\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[37m0 1 1 1 1 0 1 1\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[37m1 0 1 0 1 0 1 1\u{1b}[0m\u{1b}[36m|\u{1b}[0m\n       \u{1b}[36m|\u{1b}[0m\u{1b}[32m   1   \u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35mF\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35mR\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35mR\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35mR\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[31m Text  \u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35mM\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[31m  Short(4)   \u{1b}[0m\u{1b}[36m|\u{1b}[0m                               \u{1b}[36m|\u{1b}[0m\n       \u{1b}[36m|\u{1b}[0m       \u{1b}[36m|\u{1b}[0m\u{1b}[35mI\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35mS\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35mS\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35mS\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35mop code\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35mA\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35m Payload len \u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35m     Masking-key (part 1)      \u{1b}[0m\u{1b}[36m|\u{1b}[0m\n       \u{1b}[36m|\u{1b}[0m       \u{1b}[36m|\u{1b}[0m\u{1b}[35mN\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35mV\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35mV\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35mV\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35m (4 b) \u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35mS\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35m  (7 bits)   \u{1b}[0m\u{1b}[36m|\u{1b}[0m                               \u{1b}[36m|\u{1b}[0m\n       \u{1b}[36m|\u{1b}[0m       \u{1b}[36m|\u{1b}[0m \u{1b}[36m|\u{1b}[0m\u{1b}[35m1\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35m2\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[35m3\u{1b}[0m\u{1b}[36m|\u{1b}[0m       \u{1b}[36m|\u{1b}[0m\u{1b}[35mK\u{1b}[0m\u{1b}[36m|\u{1b}[0m             \u{1b}[36m|\u{1b}[0m                               \u{1b}[36m|\u{1b}[0m\n       \u{1b}[36m+-------+-+-+-+-+-------+-+-------------+-------------------------------+\u{1b}[0m\n       \u{1b}[36m|\u{1b}[0m\u{1b}[32m DWORD \u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[37m0 1 0 0 1 0 1 0\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[37m1 1 0 1 1 1 0 1\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[37m0 0 0 0 1 1 1 1\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[37m1 1 0 0 1 1 1 0\u{1b}[0m\u{1b}[36m|\u{1b}[0m\n       \u{1b}[36m|\u{1b}[0m\u{1b}[32m   2   \u{1b}[0m\u{1b}[36m|\u{1b}[0m                               \u{1b}[36m|\u{1b}[0m \u{1b}[34m (15)\u{1b}[0m      \u{1b}[35mMASKED\u{1b}[0m  \u{1b}[34m(206)\u{1b}[0m      \u{1b}[36m|\u{1b}[0m\n       \u{1b}[36m|\u{1b}[0m       \u{1b}[36m|\u{1b}[0m\u{1b}[35m     Masking-key (part 2)      \u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[33m0 1 1 1 0 1 0 0\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[33m0 1 1 0 0 1 0 1\u{1b}[0m\u{1b}[36m|\u{1b}[0m\n       \u{1b}[36m|\u{1b}[0m       \u{1b}[36m|\u{1b}[0m\u{1b}[35m           (16 bits)           \u{1b}[0m\u{1b}[36m|\u{1b}[0m \u{1b}[34m(116)\u{1b}[0m \u{1b}[31m\'t\'\u{1b}[0m \u{1b}[35mUNMASKED\u{1b}[0m \u{1b}[34m(101)\u{1b}[0m \u{1b}[31m\'e\'\u{1b}[0m  \u{1b}[36m|\u{1b}[0m\n       \u{1b}[36m|\u{1b}[0m       \u{1b}[36m|\u{1b}[0m                               \u{1b}[36m|\u{1b}[0m\u{1b}[35m     Payload Data (part 1)     \u{1b}[0m\u{1b}[36m|\u{1b}[0m\n       \u{1b}[36m+-------+-------------------------------+-------------------------------+\u{1b}[0m\n       \u{1b}[36m|\u{1b}[0m\u{1b}[32m DWORD \u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[37m0 0 1 1 1 0 0 1\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[37m1 0 1 0 1 0 0 1\u{1b}[0m\u{1b}[36m|\u{1b}[0m\n       \u{1b}[36m|\u{1b}[0m\u{1b}[32m   3   \u{1b}[0m\u{1b}[36m|\u{1b}[0m \u{1b}[34m (57)\u{1b}[0m      \u{1b}[35mMASKED\u{1b}[0m  \u{1b}[34m(169)\u{1b}[0m      \u{1b}[36m|\u{1b}[0m\n       \u{1b}[36m|\u{1b}[0m       \u{1b}[36m|\u{1b}[0m\u{1b}[33m0 1 1 1 0 0 1 1\u{1b}[0m\u{1b}[36m|\u{1b}[0m\u{1b}[33m0 1 1 1 0 1 0 0\u{1b}[0m\u{1b}[36m|\u{1b}[0m\n       \u{1b}[36m|\u{1b}[0m       \u{1b}[36m|\u{1b}[0m \u{1b}[34m(115)\u{1b}[0m \u{1b}[31m\'s\'\u{1b}[0m \u{1b}[35mUNMASKED\u{1b}[0m \u{1b}[34m(116)\u{1b}[0m \u{1b}[31m\'t\'\u{1b}[0m  \u{1b}[36m|\u{1b}[0m\n       \u{1b}[36m|\u{1b}[0m       \u{1b}[36m|\u{1b}[0m\u{1b}[35m     Payload Data (part 2)     \u{1b}[0m\u{1b}[36m|\u{1b}[0m\n       \u{1b}[36m+-------+\u{1b}[0m\u{1b}[36m---------------+\u{1b}[0m\u{1b}[36m---------------+\u{1b}[0m\n";
-        
-        println!("{}", frame.format());
+        crate::assert_snapshot!("short_masked_text_frame", WebSocketFrame::from_bytes(&bytes));
+    }
+
+    /// Tests that a short length frame with a single payload byte is formatted correctly.
+    #[test]
+    fn test_short_masked_single_byte_frame() {
+        let bytes = vec![0x81, 0x81, 0x01, 0x02, 0x03, 0x04, 0x40];
+
+        crate::assert_snapshot!("short_masked_single_byte_frame", WebSocketFrame::from_bytes(&bytes));
+    }
+
+    /// Tests that a short length frame with an unmasked payload has no masking key.
+    #[test]
+    fn test_short_unmasked_text_frame() {
+        let bytes = vec![0x81, 0x05, b'h', b'e', b'l', b'l', b'o'];
 
-        //assert_eq!(frame.format(), expected);
+        crate::assert_snapshot!("short_unmasked_text_frame", WebSocketFrame::from_bytes(&bytes));
+        crate::assert_snapshot!("short_unmasked_empty_frame", WebSocketFrame::from_bytes(&[0x89, 0x00]));
     }
 
     /// Tests that a medium length frame with a masked text payload is formatted correctly.
@@ -954,10 +1203,343 @@ mod tests {
     fn test_medium_masked_text_frame() {
         // Medium length
         let medium_bytes = base64::decode("gf4Ago6okLi/mqOMu56ngLeYoYq9nKWOuZCpiL+ao4y7nqeAt5ihir2cpY65kKmIv5qjjLuep4C3mKGKvZyljrmQqYi/mqOMu56ngLeYoYq9nKWOuZCpiL+ao4y7nqeAt5ihir2cpY65kKmIv5qjjLuep4C3mKGKvZyljrmQqYi/mqOMu56ngLeY").unwrap();
-        let medium_frame = WebSocketFrame::from_bytes(&medium_bytes);
+        let mut medium_frame = WebSocketFrame::from_bytes(&medium_bytes);
+        medium_frame.max_payload_bytes = Some(10);
+
+        crate::assert_snapshot!("medium_masked_text_frame", medium_frame);
+    }
+
+    /// Tests that a medium length frame with an unmasked payload is formatted correctly.
+    #[test]
+    fn test_medium_unmasked_binary_frame() {
+        let mut bytes = vec![0x82, 0x7e, 0x00, 0x80];
+        bytes.extend(0..0x80);
+        let mut frame = WebSocketFrame::from_bytes(&bytes);
+        frame.max_payload_bytes = Some(6);
+
+        crate::assert_snapshot!("medium_unmasked_binary_frame", frame);
+    }
+
+    /// Tests that a long length frame shows every byte of its extended payload length.
+    #[test]
+    fn test_long_frames() {
+        let mut unmasked = vec![0x82, 0x7f, 0, 0, 0, 0, 0, 1, 0, 0];
+        unmasked.extend(core::iter::repeat_n(b'a', 65536));
+        let mut masked = vec![0x82, 0xff, 0, 0, 0, 0, 0, 1, 0, 0, 0x01, 0x02, 0x03, 0x04];
+        masked.extend(core::iter::repeat_n(0x60, 65536));
+        let mut unmasked_frame = WebSocketFrame::from_bytes(&unmasked);
+        unmasked_frame.max_payload_bytes = Some(3);
+        let mut masked_frame = WebSocketFrame::from_bytes(&masked);
+        masked_frame.max_payload_bytes = Some(3);
+
+        crate::assert_snapshot!("long_unmasked_binary_frame", unmasked_frame);
+        crate::assert_snapshot!("long_masked_binary_frame", masked_frame);
+    }
+
+    /// Tests that a frame missing payload bytes is reported as truncated.
+    #[test]
+    fn test_truncated_frame() {
+        let bytes = base64::decode("gYR7q0rdD845").unwrap();
 
-        println!("{}", medium_frame.format());
+        let result = WebSocketFrame::try_from_bytes(&bytes);
 
+        assert_eq!(Some(FrameError::Truncated { expected: 10, actual: 9 }), result.err());
+    }
+
+    /// Tests that bytes following a frame are not included in the frame.
+    #[test]
+    fn test_frame_followed_by_data() {
+        let mut bytes = base64::decode("gYR7q0rdD845qQ==").unwrap();
+        bytes.extend_from_slice(&[0x89, 0x00]);
+
+        let frame = WebSocketFrame::try_from_bytes(&bytes).unwrap();
+
+        assert_eq!(10, frame.frame_len);
+        assert_eq!(b"test".to_vec(), frame.unmasked_payload);
+    }
+
+    /// Tests that an owned frame outlives the bytes it was parsed from.
+    #[test]
+    fn test_into_owned() {
+        let mut frames: Vec<WebSocketFrame<'static>> = Vec::new();
+        let mut expected = String::new();
+        {
+            let bytes = base64::decode("gYR7q0rdD845qQ==").unwrap();
+            let mut frame = WebSocketFrame::from_bytes(&bytes);
+            frame.markup = Markup::Plain;
+            expected.push_str(&frame.format());
+            frames.push(frame.into_owned());
+        }
+
+        assert_eq!(expected, frames[0].format());
+        assert_eq!(b"test".to_vec(), frames[0].unmasked_payload);
+    }
+
+    /// Tests that box drawing borders join the different cell splits of neighbouring rows.
+    #[test]
+    fn test_box_drawing_borders() {
+        let bytes = base64::decode("gYR7q0rdD845qQ==").unwrap();
+        let mut frame = WebSocketFrame::from_bytes(&bytes);
+        frame.markup = Markup::Plain;
+        frame.border_style = BorderStyle::Light;
+
+        let formatted = frame.format();
+
+        assert!(!formatted.contains('+') && !formatted.contains('|'));
+        assert!(formatted.contains("       ┌───────┼─┬─┬─┬─┬───────┼─┬─────────────┼───────────────┼───────────────┤\n"));
+        assert!(formatted.contains("       ├───────┼─┴─┴─┴─┴───────┼─┴─────────────┼───────────────┬───────────────┤\n"));
+        assert!(formatted.contains("       ├───────┼───────────────┬───────────────┼───────────────────────────────┘\n"));
+        assert!(formatted.ends_with("       └───────┴───────────────────────────────┘\n"));
+        assert!(formatted.contains("│ DWORD │1│0│0│0│0 0 0 1│1│"));
+    }
+
+    /// Tests that the tick marks follow the bit numbering.
+    #[test]
+    fn test_bit_numbering() {
+        let bytes = base64::decode("gYR7q0rdD845qQ==").unwrap();
+        let mut frame = WebSocketFrame::from_bytes(&bytes);
+        frame.markup = Markup::Plain;
+
+        let formatted = frame.format();
+
+        assert!(formatted.contains("   Short(4)    |0              |    1          |        2      |            3  |\n"));
+        assert!(formatted.contains("|0 1 2 3 4 5 6 7|8 9 0 1 2 3 4 5|6 7 8 9 0 1 2 3|4 5 6 7 8 9 0 1|\n"));
+
+        frame.bit_numbering = BitNumbering::new(BitOrder::Lsb0, BitSpan::Row);
+        let formatted = frame.format();
+
+        assert!(formatted.contains("   Short(4)    |  3            |      2        |          1    |              0|\n"));
+        assert!(formatted.contains("|1 0 9 8 7 6 5 4|3 2 1 0 9 8 7 6|5 4 3 2 1 0 9 8|7 6 5 4 3 2 1 0|\n"));
+
+        frame.bit_numbering = BitNumbering::new(BitOrder::Lsb0, BitSpan::Byte);
+        let formatted = frame.format();
+
+        assert!(formatted.contains("   Short(4)    |               |               |               |               |\n"));
+        assert!(formatted.contains("|7 6 5 4 3 2 1 0|7 6 5 4 3 2 1 0|7 6 5 4 3 2 1 0|7 6 5 4 3 2 1 0|\n"));
+
+        frame.max_width = Some(50);
+        let formatted = frame.format();
+
+        assert!(formatted.contains(" Frame  |7 6 5 4 3 2 1 0 7 6 5 4 3 2 1 0|\n"));
+    }
+
+    /// Tests that documents are written without markup and at full width.
+    #[test]
+    fn test_documents() {
+        let bytes = base64::decode("gYR7q0rdD845qQ==").unwrap();
+        let mut frame = WebSocketFrame::from_bytes(&bytes);
+        frame.max_width = Some(40);
+        frame.max_payload_bytes = Some(1);
+
+        let markdown = frame.to_markdown();
+
+        assert!(markdown.starts_with("```text\n               +---------------+"));
+        assert!(markdown.ends_with("payload bytes\n```\n"));
+        assert!(!markdown.contains('\x1b'));
+
+        let rst = frame.to_rst();
+
+        assert!(rst.starts_with("+----------------+-------+-------------------------+\n| Field          | Bits  | Value                   |\n+================+"));
+        assert!(rst.contains("| Opcode         | 4-7   | 0x1 (1)                 |\n"));
+        assert!(rst.ends_with("| Payload byte 0 | 48-55 | 0xf (15)                |\n|                |       | 0x74 (116) unmasked     |\n+----------------+-------+-------------------------+\n"));
+    }
+
+    /// Tests that frames are written back out as they were read.
+    #[test]
+    fn test_to_bytes() {
+        let mut long = vec![0x82, 0x7f, 0, 0, 0, 0, 0, 1, 0, 0];
+        long.extend(core::iter::repeat_n(7, 65536));
+        for bytes in [base64::decode("gYR7q0rdD845qQ==").unwrap(), vec![0x89, 0x00], vec![0x01, 0x7e, 0x00, 0x02, 1, 2], long] {
+            assert_eq!(bytes, WebSocketFrame::from_bytes(&bytes).to_bytes());
+        }
+    }
+
+    /// Tests that an unmasked frame has no masking key.
+    #[test]
+    fn test_unmasked_frame() {
+        let bytes = vec![0x81, 0x02, b'h', b'i'];
+
+        let frame = WebSocketFrame::try_from_bytes(&bytes).unwrap();
+
+        assert_eq!(4, frame.frame_len);
+        assert_eq!(b"hi".to_vec(), frame.unmasked_payload);
+    }
+
+    /// Tests that frames with fewer payload bytes than the initial rows hold can be formatted.
+    #[test]
+    fn test_short_payloads() {
+        // Ping and close frames with no payload, and a masked frame with a single payload byte
+        let ping: &[u8] = &[0x89, 0x00];
+        let close: &[u8] = &[0x88, 0x80, 0x01, 0x02, 0x03, 0x04];
+        let one_byte: &[u8] = &[0x81, 0x81, 0x01, 0x02, 0x03, 0x04, b'a' ^ 0x01];
+
+        for bytes in [ping, close, one_byte].iter() {
+            let mut frame = WebSocketFrame::try_from_bytes(bytes).unwrap();
+            frame.markup = Markup::Plain;
+            frame.max_payload_bytes = Some(0);
+
+            assert!(frame.format().contains("DWORD"));
+        }
+    }
+
+    /// Tests that unmasked frames are formatted without a masking key.
+    #[test]
+    fn test_format_unmasked_frame() {
+        let short_bytes = [0x81, 0x02, b'h', b'i'];
+        let mut medium_bytes = vec![0x82, 0x7e, 0x00, 0x7e];
+        medium_bytes.extend_from_slice(&[0; 126]);
+
+        for bytes in [&short_bytes[..], &medium_bytes[..]].iter() {
+            let mut frame = WebSocketFrame::try_from_bytes(bytes).unwrap();
+            frame.markup = Markup::Plain;
+
+            assert!(!frame.format().contains("Masking-key"));
+        }
+
+        // The medium length frame starts its payload in DWORD 2, so its 32 payload rows end at DWORD 33
+        let mut frame = WebSocketFrame::try_from_bytes(&medium_bytes).unwrap();
+        frame.markup = Markup::Plain;
+        assert!(frame.format().contains("|  33   |"));
+        assert!(!frame.format().contains("|  34   |"));
+    }
+
+    /// Tests that the fields of a medium length frame are listed.
+    #[test]
+    fn test_fields() {
+        let bytes = vec![0x82, 0xfe, 0x00, 0x01, 0x01, 0x02, 0x03, 0x04, 0x05];
+
+        let frame = WebSocketFrame::from_bytes(&bytes);
+        let fields = frame.fields();
+
+        assert_eq!(10, fields.len());
+        assert_eq!(("Opcode", 4, 4, 2), (fields[4].name.as_str(), fields[4].bit_offset, fields[4].bit_len, fields[4].value));
+        assert_eq!(("Extended payload length", 16, 16, 1), (fields[7].name.as_str(), fields[7].bit_offset, fields[7].bit_len, fields[7].value));
+        assert_eq!(("Masking-key", 32, 32, 0x01020304), (fields[8].name.as_str(), fields[8].bit_offset, fields[8].bit_len, fields[8].value));
+        assert_eq!(
+            FrameField { name: String::from("Payload byte 0"), bit_offset: 64, bit_len: 8, value: 5, unmasked_value: Some(4) },
+            fields[9]
+        );
+    }
+
+    /// Tests that a frame is exported as JSON.
+    #[test]
+    fn test_to_json() {
+        let bytes = base64::decode("gYR7q0rdD845qQ==").unwrap();
+
+        let frame = WebSocketFrame::from_bytes(&bytes);
+        let expected = "{\"frame_length\":10,\"fin\":true,\"rsv1\":false,\"rsv2\":false,\"rsv3\":false,\"opcode\":{\"value\":1,\"name\":\"Text\"},\"masked\":true,\"payload_length\":{\"kind\":\"Short\",\"value\":4},\"masking_key\":[123,171,74,221],\"payload\":[116,101,115,116],\"payload_text\":\"test\"}";
+
+        assert_eq!(expected, frame.to_json());
+    }
+
+    /// Encodes a frame, masking the payload when there is a masking key.
+    ///
+    /// # Arguments
+    ///
+    /// * `first_byte` - The flags and opcode.
+    /// * `masking_key` - The masking key (`None` when unmasked).
+    /// * `payload` - The unmasked payload.
+    /// * `length_code` - The payload length code to use when the length fits it (`126` or `127` for
+    ///   a longer extended payload length than needed, anything else for the shortest).
+    fn encode(first_byte: u8, masking_key: Option<[u8; 4]>, payload: &[u8], length_code: u8) -> Vec<u8> {
+        let mask_bit = if masking_key.is_some() { 0x80 } else { 0 };
+        let mut bytes = vec![first_byte];
+        match payload.len() {
+            len if len <= 125 && length_code < 126 => bytes.push(mask_bit | len as u8),
+            len if len <= 0xffff && length_code < 127 => {
+                bytes.push(mask_bit | 126);
+                bytes.extend_from_slice(&(len as u16).to_be_bytes());
+            }
+            len => {
+                bytes.push(mask_bit | 127);
+                bytes.extend_from_slice(&(len as u64).to_be_bytes());
+            }
+        }
+        let key = masking_key.unwrap_or([0; 4]);
+        if let Some(key) = masking_key {
+            bytes.extend_from_slice(&key);
+        }
+        bytes.extend(payload.iter().enumerate().map(|(i, byte)| byte ^ key[i % 4]));
+        bytes
+    }
+
+    /// Generates the bytes of a valid frame.
+    fn frame_bytes() -> impl Strategy<Value = Vec<u8>> {
+        (any::<u8>(), any::<Option<[u8; 4]>>(), prop::collection::vec(any::<u8>(), 0..300), 124..128u8)
+            .prop_map(|(first_byte, masking_key, payload, length_code)| encode(first_byte, masking_key, &payload, length_code))
+    }
+
+    /// Renders a frame every way it can be rendered, checking the rows of each diagram line up.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame` - The frame.
+    fn render_all(frame: &mut WebSocketFrame) {
+        frame.markup = Markup::Plain;
+        // ASCII walls can't be told apart from `|` characters in the payload
+        if frame.border_style == BorderStyle::Ascii {
+            frame.border_style = BorderStyle::Light;
+        }
+        for max_width in [None, Some(60), Some(30)] {
+            frame.max_width = max_width;
+            let formatted = frame.format();
+            let widest = formatted.lines().filter(|line| !line.trim_start().starts_with("...")).map(|line| line.chars().count()).max();
+            assert!(widest <= Some(max_width.unwrap_or(compact::FULL_WIDTH)), "{}", formatted);
+            assert!(frame.border_style.rows_aligned(&formatted), "{}", formatted);
+        }
+        frame.to_json();
+        frame.to_markdown();
+        frame.to_rst();
+    }
+
+    proptest! {
+        /// Tests that encoded frames are parsed back into the same fields and bytes.
+        #[test]
+        fn prop_round_trip(first_byte: u8, masking_key: Option<[u8; 4]>, payload in prop::collection::vec(any::<u8>(), 0..300), length_code in 124..128u8) {
+            let bytes = encode(first_byte, masking_key, &payload, length_code);
+
+            let frame = WebSocketFrame::try_from_bytes(&bytes).unwrap();
+
+            prop_assert_eq!(bytes.len(), frame.frame_len);
+            prop_assert_eq!(&bytes, &frame.to_bytes());
+            prop_assert_eq!(&payload, &frame.unmasked_payload);
+            prop_assert_eq!(first_byte & 0x0f, frame.opcode());
+            prop_assert_eq!(masking_key.is_some(), frame.is_payload_masked);
+            prop_assert_eq!(payload.len() as u64, frame.payload_length.num_bytes());
+        }
+
+        /// Tests that any bytes are either parsed or reported as truncated, without panicking.
+        #[test]
+        fn prop_never_panics(bytes in prop::collection::vec(any::<u8>(), 0..64), max_payload_bytes: Option<u8>) {
+            if let Ok(mut frame) = WebSocketFrame::try_from_bytes(&bytes) {
+                prop_assert!(frame.frame_len <= bytes.len());
+                frame.max_payload_bytes = max_payload_bytes.map(usize::from);
+                render_all(&mut frame);
+            }
+        }
+
+        /// Tests that the rows of every diagram line up and fit their width.
+        #[test]
+        fn prop_rows_aligned(bytes in frame_bytes(), max_payload_bytes: Option<u8>, heavy: bool) {
+            let mut frame = WebSocketFrame::from_bytes(&bytes);
+            frame.max_payload_bytes = max_payload_bytes.map(usize::from);
+            if heavy {
+                frame.border_style = BorderStyle::Heavy;
+            }
+
+            render_all(&mut frame);
+        }
+
+        /// Tests that frames cut short are reported as truncated.
+        #[test]
+        fn prop_truncated(bytes in frame_bytes(), cut: prop::sample::Index) {
+            let len = cut.index(bytes.len());
+
+            let result = WebSocketFrame::try_from_bytes(&bytes[..len]);
+
+            let truncated = matches!(result, Err(FrameError::Truncated { expected, actual }) if actual == len && expected > len);
+            prop_assert!(truncated);
+        }
     }
 }
 
@@ -0,0 +1,312 @@
+use core::fmt;
+use super::{FrameField, WebSocketFrame, BITS_IN_BYTE};
+use crate::markup::Paint;
+use crate::prelude::*;
+
+/// The width of the full diagram, which has a DWORD on each row.
+pub(super) const FULL_WIDTH: usize = 80;
+/// The width of the compact diagram with 16 bits on each row.
+pub(super) const WORD_WIDTH: usize = 41;
+
+/// The part of a field drawn on one row of a compact diagram.
+struct Segment<'f> {
+    field: &'f FrameField,
+    /// The offset of the segment's first bit from the start of its row.
+    start: usize,
+    /// The number of bits in the segment.
+    len: usize,
+    /// Whether the segment holds the first bit of its field.
+    first: bool,
+}
+
+impl Segment<'_> {
+    /// Gets the number of characters in the segment's cell.
+    fn width(&self) -> usize {
+        2 * self.len - 1
+    }
+}
+
+impl<'a> WebSocketFrame<'a> {
+    /// Renders the frame as a compact diagram for narrow terminals.
+    ///
+    /// Each row holds 16 (or 8) bits with a cell per field, so field boundaries stay intact (a field
+    /// running past the end of a row continues in a cell on the next row). Field names are
+    /// abbreviated and wrapped to fit their cells, and the decimal values of payload bytes are
+    /// dropped from 8-bit rows.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the diagram.
+    /// * `bits_per_row` - The number of bits on each row (16 or 8).
+    pub(super) fn render_compact<W: fmt::Write>(self: &WebSocketFrame<'a>, out: &mut W, bits_per_row: usize) -> fmt::Result {
+        let payload_len = self.masked_payload.len();
+        let num_payload_bytes = self.max_payload_bytes.map_or(payload_len, |max| max.min(payload_len));
+        let end_bit = self.payload_bit(num_payload_bytes).expect("Payload bits are part of the frame.");
+        let fields: Vec<FrameField> = self.fields().into_iter().filter(|field| field.bit_offset < end_bit).collect();
+        let row_label = if bits_per_row == 16 { "WORD" } else { "BYTE" };
+
+        // Header (bit numbers within each row)
+        let header_walls = [8, 8 + 2 * bits_per_row];
+        self.write_rule(out, 0, &[], &header_walls)?;
+        let ticks: Vec<String> = (0..bits_per_row).map(|bit| (self.bit_numbering.number(bit, bits_per_row) % 10).to_string()).collect();
+        writeln!(
+            out,
+            "{1:^8}{0}{2}{0}",
+            self.wall(),
+            "Frame".paint(self.theme.title, self.markup),
+            ticks.join(" ").paint(self.theme.tick_mark, self.markup)
+        )?;
+
+        let mut walls_above = header_walls.to_vec();
+        for (row, row_start) in (0..end_bit).step_by(bits_per_row).enumerate() {
+            let row_end = (row_start + bits_per_row).min(end_bit);
+            let segments: Vec<Segment> = fields
+                .iter()
+                .filter(|field| field.bit_offset < row_end && row_start < field.bit_offset + field.bit_len)
+                .map(|field| {
+                    let start = field.bit_offset.max(row_start);
+                    let end = (field.bit_offset + field.bit_len).min(row_end);
+                    Segment {
+                        field,
+                        start: start - row_start,
+                        len: end - start,
+                        first: field.bit_offset >= row_start,
+                    }
+                })
+                .collect();
+            let mut walls = vec![0, 8];
+            walls.extend(segments.iter().map(|segment| 8 + 2 * (segment.start + segment.len)));
+            self.write_rule(out, 0, &walls_above, &walls)?;
+            walls_above = walls;
+
+            // Line 1: The bits
+            write!(out, "{0}{1:^7}{0}", self.wall(), row_label.paint(self.theme.dword_title, self.markup))?;
+            for segment in &segments {
+                self.write_segment_bits(out, row_start + segment.start, segment.len)?;
+                write!(out, "{}", self.wall())?;
+            }
+            out.write_char('\n')?;
+
+            // Following lines: The field names (the row number is on the first of them)
+            let names: Vec<Vec<String>> = segments
+                .iter()
+                .map(|segment| {
+                    let name = if segment.first { short_name(&segment.field.name) } else { "(cont.)" };
+                    wrap(name, segment.width())
+                })
+                .collect();
+            let num_name_lines = names.iter().map(Vec::len).max().unwrap_or(0).max(1);
+            for line in 0..num_name_lines {
+                let label = if line == 0 { (row + 1).to_string() } else { String::new() };
+                write!(out, "{0}{1:^7}{0}", self.wall(), label.paint(self.theme.dword_title, self.markup))?;
+                for (segment, lines) in segments.iter().zip(&names) {
+                    let name = lines.get(line).map_or("", String::as_str);
+                    write!(out, "{:^1$}{2}", name.paint(self.theme.notes, self.markup), segment.width(), self.wall())?;
+                }
+                out.write_char('\n')?;
+            }
+
+            // Last line: The values of fields starting on the row (when they have any)
+            let values: Vec<Option<String>> = segments
+                .iter()
+                .map(|segment| match segment.first {
+                    true => self.field_value(segment.field, bits_per_row >= 16),
+                    false => None,
+                })
+                .collect();
+            if values.iter().any(Option::is_some) {
+                write!(out, "{0}{1:7}{0}", self.wall(), "")?;
+                for (segment, value) in segments.iter().zip(&values) {
+                    let value: String = value.as_deref().unwrap_or("").chars().take(segment.width()).collect();
+                    write!(out, "{:^1$}{2}", value.paint(self.theme.data_value, self.markup), segment.width(), self.wall())?;
+                }
+                out.write_char('\n')?;
+            }
+        }
+        self.write_rule(out, 0, &walls_above, &[])?;
+
+        // Note any payload bytes left out
+        if num_payload_bytes < payload_len {
+            writeln!(
+                out,
+                "{}",
+                format!("... {} more payload bytes", payload_len - num_payload_bytes).paint(self.theme.notes, self.markup),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Formats a run of the frame's bits, drawing highlighted bits in the highlight style.
+    ///
+    /// # Arguments
+    ///
+    /// * `out` - The writer receiving the bits.
+    /// * `bit_offset` - The offset of the first bit from the start of the frame.
+    /// * `num_bits` - The number of bits.
+    fn write_segment_bits<W: fmt::Write>(self: &WebSocketFrame<'a>, out: &mut W, bit_offset: usize, num_bits: usize) -> fmt::Result {
+        // Paint the bits a byte at a time
+        let end = bit_offset + num_bits;
+        let mut bit = bit_offset;
+        while bit < end {
+            let chunk_end = ((bit / BITS_IN_BYTE + 1) * BITS_IN_BYTE).min(end);
+            let chunk_len = chunk_end - bit;
+            let shift = BITS_IN_BYTE - bit % BITS_IN_BYTE - chunk_len;
+            let chunk = (self.byte_at(bit / BITS_IN_BYTE) >> shift) & (0xff >> (BITS_IN_BYTE - chunk_len));
+            if bit > bit_offset {
+                out.write_char(' ')?;
+            }
+            write!(out, "{}", self.paint_bits(chunk, chunk_len as u8, Some(bit), self.theme.bit))?;
+            bit = chunk_end;
+        }
+        Ok(())
+    }
+
+    /// Gets a byte of the frame as it was parsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the byte from the start of the frame.
+    pub(super) fn byte_at(self: &WebSocketFrame<'a>, index: usize) -> u8 {
+        let num_extension_bytes = self.num_extension_bytes();
+        let masking_key_len = if self.is_payload_masked { 4 } else { 0 };
+        match index {
+            0 => u8::from(self.fin_bit) << 7 | u8::from(self.rsv1) << 6 | u8::from(self.rsv2) << 5 | u8::from(self.rsv3) << 4 | self.opcode_bits,
+            1 => u8::from(self.mask_bit) << 7 | self.payload_length_code,
+            i if i < 2 + num_extension_bytes => self.payload_length_bytes[i - 2],
+            i if i < 2 + num_extension_bytes + masking_key_len => self.masking_key[i - 2 - num_extension_bytes],
+            i => self.masked_payload[i - 2 - num_extension_bytes - masking_key_len],
+        }
+    }
+
+    /// Describes the value of a field (`None` for single bit flags, whose bit is their value).
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - The field.
+    /// * `show_decimal` - Whether to show the decimal value of payload bytes next to their character.
+    fn field_value(self: &WebSocketFrame<'a>, field: &FrameField, show_decimal: bool) -> Option<String> {
+        if let Some(byte) = field.unmasked_value {
+            let c = if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' };
+            return Some(match show_decimal {
+                true => format!("'{}' ({})", c, byte),
+                false => format!("'{}'", c),
+            });
+        }
+        match field.name.as_str() {
+            "Opcode" => Some(self.opcode.label().to_string()),
+            "Payload length" | "Extended payload length" => Some(field.value.to_string()),
+            "Masking-key" => Some(format!("{:#010x}", field.value)),
+            _ => None,
+        }
+    }
+}
+
+/// Gets the abbreviated name of a field shown in compact diagrams.
+///
+/// # Arguments
+///
+/// * `name` - The name of the field.
+fn short_name(name: &str) -> &str {
+    match name {
+        "Payload length" => "Payload len",
+        "Extended payload length" => "Ext. payload len",
+        name => name,
+    }
+}
+
+/// Wraps text into lines no wider than a cell, only breaking words wider than the cell.
+///
+/// # Arguments
+///
+/// * `text` - The text to wrap.
+/// * `width` - The width of the cell.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        if !line.is_empty() && line.chars().count() + 1 + word.len() <= width {
+            line.push(' ');
+            line.extend(word);
+            continue;
+        }
+        if !line.is_empty() {
+            lines.push(core::mem::take(&mut line));
+        }
+        while word.len() > width {
+            lines.push(word.drain(..width).collect());
+        }
+        line = word.into_iter().collect();
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+// #region Unit tests
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markup::Markup;
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(vec!["F", "I", "N"], wrap("FIN", 1));
+        assert_eq!(vec!["Payload", "len"], wrap("Payload len", 7));
+        assert_eq!(vec!["Masking-key"], wrap("Masking-key", 31));
+    }
+
+    #[test]
+    fn test_word_rows() {
+        let bytes = base64::decode("gYR7q0rdD845qQ==").unwrap();
+        let mut frame = WebSocketFrame::from_bytes(&bytes);
+        frame.markup = Markup::Plain;
+        frame.max_width = Some(60);
+
+        let expected = "        +-------------------------------+\n \
+                        Frame  |0 1 2 3 4 5 6 7 8 9 0 1 2 3 4 5|\n\
+                        +-------+-+-+-+-+-------+-+-------------+\n\
+                        | WORD  |1|0|0|0|0 0 0 1|1|0 0 0 0 1 0 0|\n\
+                        |   1   |F|R|R|R|Opcode |M| Payload len |\n\
+                        |       |I|S|S|S|       |A|             |\n\
+                        |       |N|V|V|V|       |S|             |\n\
+                        |       | |1|2|3|       |K|             |\n\
+                        |       | | | | | Text  | |      4      |\n\
+                        +-------+-+-+-+-+-------+-+-------------+\n\
+                        | WORD  |0 1 1 1 1 0 1 1 1 0 1 0 1 0 1 1|\n\
+                        |   2   |          Masking-key          |\n\
+                        |       |          0x7bab4add           |\n\
+                        +-------+-------------------------------+\n\
+                        | WORD  |0 1 0 0 1 0 1 0 1 1 0 1 1 1 0 1|\n\
+                        |   3   |            (cont.)            |\n\
+                        +-------+---------------+---------------+\n\
+                        | WORD  |0 0 0 0 1 1 1 1|1 1 0 0 1 1 1 0|\n\
+                        |   4   |Payload byte 0 |Payload byte 1 |\n\
+                        |       |   't' (116)   |   'e' (101)   |\n\
+                        +-------+---------------+---------------+\n\
+                        | WORD  |0 0 1 1 1 0 0 1|1 0 1 0 1 0 0 1|\n\
+                        |   5   |Payload byte 2 |Payload byte 3 |\n\
+                        |       |   's' (115)   |   't' (116)   |\n\
+                        +-------+---------------+---------------+\n";
+
+        assert_eq!(expected, frame.format());
+    }
+
+    #[test]
+    fn test_byte_rows() {
+        let bytes = base64::decode("gYR7q0rdD845qQ==").unwrap();
+        let mut frame = WebSocketFrame::from_bytes(&bytes);
+        frame.markup = Markup::Plain;
+        frame.max_width = Some(30);
+        frame.max_payload_bytes = Some(1);
+        let formatted = frame.format();
+
+        assert!(formatted.lines().all(|line| line.chars().count() <= 30));
+        assert!(formatted.contains("| BYTE  |0 0 0 0 1 1 1 1|\n|   7   |Payload byte 0 |\n|       |      't'      |\n"));
+        assert!(formatted.ends_with("+-------+---------------+\n... 3 more payload bytes\n"));
+    }
+}
+
+// #endregion Unit tests
@@ -1,5 +1,5 @@
 #[derive(Debug)]
-#[derive(PartialEq)]
+#[derive(Clone, PartialEq)]
 pub enum WebSocketOpCode {
     Continuation,
     Text,
@@ -26,6 +26,20 @@ impl WebSocketOpCode {
             _ => WebSocketOpCode::Unrecognized,
         }
     }
+
+    /// Gets a name for the opcode short enough to fit the opcode cell of a diagram (7 characters).
+    pub fn label(&self) -> &'static str {
+        match self {
+            WebSocketOpCode::Continuation => "Cont.",
+            WebSocketOpCode::Text => "Text",
+            WebSocketOpCode::Binary => "Binary",
+            WebSocketOpCode::CloseConnection => "Close",
+            WebSocketOpCode::Ping => "Ping",
+            WebSocketOpCode::Pong => "Pong",
+            WebSocketOpCode::Unrecognized => "Unknown",
+            WebSocketOpCode::ReservedFuture => "Rsvd.",
+        }
+    }
 }
 
 // #region Unit tests
@@ -33,6 +47,7 @@ impl WebSocketOpCode {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_get_opcode_continuation() {
@@ -89,6 +104,17 @@ mod tests {
         // Pong
         assert_eq!(WebSocketOpCode::Unrecognized, WebSocketOpCode::from_bit_value(0b01000000));
     }
+
+    proptest! {
+        /// Tests that every 4-bit opcode is recognised (if only as reserved) and nothing wider is.
+        #[test]
+        fn prop_four_bit_opcodes(bits: u8) {
+            let opcode = WebSocketOpCode::from_bit_value(bits);
+
+            prop_assert_eq!(bits > 0x0f, opcode == WebSocketOpCode::Unrecognized);
+            prop_assert!(opcode.label().len() <= 7);
+        }
+    }
 }
 
 // #endregion Unit tests
\ No newline at end of file
@@ -0,0 +1,2 @@
+// Exports the JavaScript API of `bitformat` from this WebAssembly module
+pub use bitformat::wasm::*;